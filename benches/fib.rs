@@ -52,7 +52,7 @@ fn setup<C: Chipset<BabyBear>>(
 ) {
     let code = build_lurk_expr(arg);
     let zstore = &mut lurk_zstore();
-    let ZPtr { tag, digest } = zstore.read(&code, &Default::default());
+    let ZPtr { tag, digest } = zstore.read(&code, &Default::default()).unwrap();
 
     let mut record = QueryRecord::new(toplevel);
     record.inject_inv_queries("hash4", toplevel, &zstore.hashes4);
@@ -1,12 +1,19 @@
-use std::borrow::{Borrow, BorrowMut};
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
 
 use num_bigint::BigUint;
 use p3_air::AirBuilder;
 use p3_field::{PrimeField, PrimeField32};
 
-use crate::gadgets::big_num::cmp::BigNumCompareWitness;
+use crate::gadgets::big_num::{
+    arith::{BigNumDiff, BigNumDivRem, BigNumMulMod, BigNumProduct, BigNumSum},
+    cmp::BigNumCompareWitness,
+};
 use crate::{
     air::builder::{LookupBuilder, Record, RequireRecord},
+    core::zstore::DIGEST_SIZE,
     gadgets::bytes::{builder::BytesAirRecordWithContext, record::DummyBytesRecord},
     lair::{chipset::Chipset, execute::QueryRecord},
 };
@@ -14,30 +21,58 @@ use crate::{
 #[derive(Clone)]
 pub enum BigNum {
     LessThan,
+    Add,
+    Sub,
+    Mul,
+    DivRem,
+    MulMod,
+}
+
+/// Splits the input of a `BigNum` chip into its digests
+fn split_digests<T: Clone, const N: usize>(input: &[T]) -> [[T; DIGEST_SIZE]; N] {
+    array::from_fn(|i| {
+        input[i * DIGEST_SIZE..(i + 1) * DIGEST_SIZE]
+            .to_vec()
+            .try_into()
+            .unwrap_or_else(|_| unreachable!())
+    })
 }
 
 impl<F: PrimeField32> Chipset<F> for BigNum {
     fn input_size(&self) -> usize {
         match self {
-            BigNum::LessThan => 16,
+            BigNum::MulMod => 24,
+            _ => 16,
         }
     }
 
     fn output_size(&self) -> usize {
         match self {
             BigNum::LessThan => 1,
+            BigNum::DivRem => 16,
+            _ => 8,
         }
     }
 
     fn witness_size(&self) -> usize {
         match self {
             BigNum::LessThan => BigNumCompareWitness::<F>::witness_size(),
+            BigNum::Add => BigNumSum::<F>::witness_size(),
+            BigNum::Sub => BigNumDiff::<F>::witness_size(),
+            BigNum::Mul => BigNumProduct::<F>::witness_size(),
+            BigNum::DivRem => BigNumDivRem::<F>::witness_size(),
+            BigNum::MulMod => BigNumMulMod::<F>::witness_size(),
         }
     }
 
     fn require_size(&self) -> usize {
         match self {
             BigNum::LessThan => BigNumCompareWitness::<F>::num_requires(),
+            BigNum::Add => BigNumSum::<F>::num_requires(),
+            BigNum::Sub => BigNumDiff::<F>::num_requires(),
+            BigNum::Mul => BigNumProduct::<F>::num_requires(),
+            BigNum::DivRem => BigNumDivRem::<F>::num_requires(),
+            BigNum::MulMod => BigNumMulMod::<F>::num_requires(),
         }
     }
 
@@ -48,8 +83,7 @@ impl<F: PrimeField32> Chipset<F> for BigNum {
         queries: &mut QueryRecord<F>,
         requires: &mut Vec<Record>,
     ) -> Vec<F> {
-        let in1: [F; 8] = input[0..8].try_into().unwrap();
-        let in2: [F; 8] = input[8..16].try_into().unwrap();
+        let [in1, in2] = split_digests(&input[0..16]);
         let bytes = &mut queries.bytes.context(nonce, requires);
         match self {
             BigNum::LessThan => {
@@ -57,12 +91,33 @@ impl<F: PrimeField32> Chipset<F> for BigNum {
                 witness.populate(&in1, &in2, bytes);
                 witness.iter_result().into_iter().collect()
             }
+            BigNum::Add => {
+                let mut witness = BigNumSum::<F>::default();
+                witness.populate(&in1, &in2, bytes).to_vec()
+            }
+            BigNum::Sub => {
+                let mut witness = BigNumDiff::<F>::default();
+                witness.populate(&in1, &in2, bytes).to_vec()
+            }
+            BigNum::Mul => {
+                let mut witness = BigNumProduct::<F>::default();
+                witness.populate(&in1, &in2, bytes).to_vec()
+            }
+            BigNum::DivRem => {
+                let mut witness = BigNumDivRem::<F>::default();
+                let (quot, rem) = witness.populate(&in1, &in2, bytes);
+                [quot, rem].concat()
+            }
+            BigNum::MulMod => {
+                let [in3] = split_digests(&input[16..24]);
+                let mut witness = BigNumMulMod::<F>::default();
+                witness.populate(&in1, &in2, &in3, bytes).to_vec()
+            }
         }
     }
 
     fn populate_witness(&self, input: &[F], witness: &mut [F]) -> Vec<F> {
-        let in1: [F; 8] = input[0..8].try_into().unwrap();
-        let in2: [F; 8] = input[8..16].try_into().unwrap();
+        let [in1, in2] = split_digests(&input[0..16]);
         let bytes = &mut DummyBytesRecord;
         match self {
             BigNum::LessThan => {
@@ -70,6 +125,28 @@ impl<F: PrimeField32> Chipset<F> for BigNum {
                 witness.populate(&in1, &in2, bytes);
                 witness.iter_result().into_iter().collect()
             }
+            BigNum::Add => {
+                let witness: &mut BigNumSum<F> = witness.borrow_mut();
+                witness.populate(&in1, &in2, bytes).to_vec()
+            }
+            BigNum::Sub => {
+                let witness: &mut BigNumDiff<F> = witness.borrow_mut();
+                witness.populate(&in1, &in2, bytes).to_vec()
+            }
+            BigNum::Mul => {
+                let witness: &mut BigNumProduct<F> = witness.borrow_mut();
+                witness.populate(&in1, &in2, bytes).to_vec()
+            }
+            BigNum::DivRem => {
+                let witness: &mut BigNumDivRem<F> = witness.borrow_mut();
+                let (quot, rem) = witness.populate(&in1, &in2, bytes);
+                [quot, rem].concat()
+            }
+            BigNum::MulMod => {
+                let [in3] = split_digests(&input[16..24]);
+                let witness: &mut BigNumMulMod<F> = witness.borrow_mut();
+                witness.populate(&in1, &in2, &in3, bytes).to_vec()
+            }
         }
     }
 
@@ -82,15 +159,41 @@ impl<F: PrimeField32> Chipset<F> for BigNum {
         nonce: AB::Expr,
         requires: &[RequireRecord<AB::Var>],
     ) -> Vec<AB::Expr> {
-        let in1: [AB::Expr; 8] = ins[0..8].to_vec().try_into().unwrap();
-        let in2: [AB::Expr; 8] = ins[8..16].to_vec().try_into().unwrap();
+        let [in1, in2] = split_digests(&ins[0..16]);
         let mut air_record = BytesAirRecordWithContext::default();
+        let record = &mut air_record;
         let out = match self {
             BigNum::LessThan => {
                 let witness: &BigNumCompareWitness<AB::Var> = witness.borrow();
-                let cmp = witness.eval(builder, &in1, &in2, &mut air_record, is_real.clone());
+                let cmp = witness.eval(builder, &in1, &in2, record, is_real.clone());
                 vec![cmp.is_less_than()]
             }
+            BigNum::Add => {
+                let witness: &BigNumSum<AB::Var> = witness.borrow();
+                let out = witness.eval(builder, &in1, &in2, record, is_real.clone());
+                out.map(Into::into).to_vec()
+            }
+            BigNum::Sub => {
+                let witness: &BigNumDiff<AB::Var> = witness.borrow();
+                let out = witness.eval(builder, &in1, &in2, record, is_real.clone());
+                out.map(Into::into).to_vec()
+            }
+            BigNum::Mul => {
+                let witness: &BigNumProduct<AB::Var> = witness.borrow();
+                let out = witness.eval(builder, &in1, &in2, record, is_real.clone());
+                out.map(Into::into).to_vec()
+            }
+            BigNum::DivRem => {
+                let witness: &BigNumDivRem<AB::Var> = witness.borrow();
+                let (quot, rem) = witness.eval(builder, &in1, &in2, record, is_real.clone());
+                quot.into_iter().chain(rem).map(Into::into).collect()
+            }
+            BigNum::MulMod => {
+                let [in3] = split_digests(&ins[16..24]);
+                let witness: &BigNumMulMod<AB::Var> = witness.borrow();
+                let out = witness.eval(builder, &in1, &in2, &in3, record, is_real.clone());
+                out.map(Into::into).to_vec()
+            }
         };
         air_record.require_all(builder, nonce, requires.iter().cloned());
         out
@@ -107,10 +210,25 @@ pub fn field_elts_to_biguint<F: PrimeField>(elts: &[F]) -> BigUint {
     num
 }
 
+/// Returns the digest of field elements, in little-endian order, of a `BigUint`
+/// smaller than the order of the field to the power of `DIGEST_SIZE`.
+pub fn biguint_to_field_elts<F: PrimeField32>(num: &BigUint) -> [F; DIGEST_SIZE] {
+    let order = F::order();
+    let mut num = num.clone();
+    let elts = array::from_fn(|_| {
+        let rem = &num % &order;
+        num /= &order;
+        F::from_canonical_u32(rem.try_into().unwrap())
+    });
+    assert_eq!(num, BigUint::ZERO, "Integer too large");
+    elts
+}
+
 #[cfg(test)]
 mod test {
     use std::sync::Arc;
 
+    use num_bigint::BigUint;
     use p3_baby_bear::BabyBear as F;
     use p3_field::AbstractField;
     use sp1_stark::StarkMachine;
@@ -118,7 +236,7 @@ mod test {
 
     use crate::{
         air::debug::debug_chip_constraints_and_queries_with_sharding,
-        core::chipset::lurk_chip_map_native,
+        core::{big_num::biguint_to_field_elts, chipset::lurk_chip_map_native},
         func,
         lair::{
             execute::{QueryRecord, Shard},
@@ -185,4 +303,38 @@ mod test {
         let shard = Shard::new_arc(&queries);
         machine.debug_constraints(&pk, shard.clone(), &mut challenger_d);
     }
+
+    #[test]
+    fn big_num_arith_test() {
+        let arith_func = func!(
+        fn arith(a: [8], b: [8], m: [8]): [48] {
+            let sum: [8] = extern_call(big_num_add, a, b);
+            let diff: [8] = extern_call(big_num_sub, b, a);
+            let prod: [8] = extern_call(big_num_mul, a, b);
+            let (quot: [8], rem: [8]) = extern_call(big_num_divrem, b, a);
+            let res: [8] = extern_call(big_num_mulmod, b, b, m);
+            return (sum, diff, prod, quot, rem, res)
+        });
+        let lurk_chip_map = lurk_chip_map_native();
+        let toplevel = Toplevel::new(&[arith_func], lurk_chip_map);
+
+        let arith_chip = FuncChip::from_name("arith", &toplevel);
+        let mut queries = QueryRecord::new(&toplevel);
+        let a = BigUint::from(0x1234_5678_9abc_def0u64);
+        let b = BigUint::from(3u32).pow(100);
+        let m = BigUint::from(2u32).pow(200) + 7u32;
+        let args = [&a, &b, &m].map(biguint_to_field_elts::<F>).concat();
+        let out = toplevel
+            .execute_by_name("arith", &args, &mut queries, None)
+            .unwrap();
+        let expected = [&a + &b, &b - &a, &a * &b, &b / &a, &b % &a, &b * &b % &m]
+            .iter()
+            .flat_map(biguint_to_field_elts::<F>)
+            .collect::<Vec<_>>();
+        assert_eq!(out.as_ref(), expected);
+
+        let lair_chips = build_lair_chip_vector(&arith_chip);
+        let queries = Arc::new(queries);
+        debug_chip_constraints_and_queries_with_sharding(&queries, &lair_chips, None);
+    }
 }
@@ -14,7 +14,10 @@ use crate::{
 
 use crate::core::poseidon::PoseidonChipset;
 
-use super::{big_num::BigNum, u64::U64, zstore::Hasher};
+use super::{
+    big_num::BigNum, i64::I64, keccak::Keccak, num::Num, sha256::Sha256, signature::Edwards,
+    u64::U64, zstore::Hasher,
+};
 
 #[derive(Clone)]
 pub enum LurkChip {
@@ -22,7 +25,12 @@ pub enum LurkChip {
     Hasher4(PoseidonChipset<BabyBearConfig32, 32>),
     Hasher5(PoseidonChipset<BabyBearConfig40, 40>),
     U64(U64),
+    I64(I64),
     BigNum(BigNum),
+    Num(Num),
+    Sha256(Sha256),
+    Keccak(Keccak),
+    Edwards(Edwards),
 }
 
 pub fn lurk_chip_map<C2: Chipset<BabyBear>>(
@@ -37,7 +45,28 @@ pub fn lurk_chip_map<C2: Chipset<BabyBear>>(
     let u64_divrem = LurkChip::U64(U64::DivRem);
     let u64_lessthan = LurkChip::U64(U64::LessThan);
     let u64_iszero = LurkChip::U64(U64::IsZero);
+    let u64_and = LurkChip::U64(U64::And);
+    let u64_or = LurkChip::U64(U64::Or);
+    let u64_xor = LurkChip::U64(U64::Xor);
+    let u64_not = LurkChip::U64(U64::Not);
+    let u64_shl = LurkChip::U64(U64::Shl);
+    let u64_shr = LurkChip::U64(U64::Shr);
+    let i64_isneg = LurkChip::I64(I64::IsNegative);
     let big_num_lessthan = LurkChip::BigNum(BigNum::LessThan);
+    let big_num_add = LurkChip::BigNum(BigNum::Add);
+    let big_num_sub = LurkChip::BigNum(BigNum::Sub);
+    let big_num_mul = LurkChip::BigNum(BigNum::Mul);
+    let big_num_divrem = LurkChip::BigNum(BigNum::DivRem);
+    let big_num_mulmod = LurkChip::BigNum(BigNum::MulMod);
+    let num_to_bytes = LurkChip::Num(Num::ToBytes);
+    let byte_to_bits = LurkChip::Num(Num::ByteToBits);
+    let sha256_schedule = LurkChip::Sha256(Sha256::Schedule);
+    let sha256_round = LurkChip::Sha256(Sha256::Round);
+    let sha256_add_state = LurkChip::Sha256(Sha256::AddState);
+    let keccak_absorber = LurkChip::Keccak(Keccak::Absorb);
+    let keccak_permuter = LurkChip::Keccak(Keccak::Round);
+    let edwards_add = LurkChip::Edwards(Edwards::Add);
+    let edwards_residual = LurkChip::Edwards(Edwards::Residual);
     let mut chips: FxIndexMap<_, _> = [
         (Name("hasher3"), Either::Left(hasher3)),
         (Name("hasher4"), Either::Left(hasher4)),
@@ -48,7 +77,28 @@ pub fn lurk_chip_map<C2: Chipset<BabyBear>>(
         (Name("u64_divrem"), Either::Left(u64_divrem)),
         (Name("u64_lessthan"), Either::Left(u64_lessthan)),
         (Name("u64_iszero"), Either::Left(u64_iszero)),
+        (Name("u64_and"), Either::Left(u64_and)),
+        (Name("u64_or"), Either::Left(u64_or)),
+        (Name("u64_xor"), Either::Left(u64_xor)),
+        (Name("u64_not"), Either::Left(u64_not)),
+        (Name("u64_shl"), Either::Left(u64_shl)),
+        (Name("u64_shr"), Either::Left(u64_shr)),
+        (Name("i64_isneg"), Either::Left(i64_isneg)),
         (Name("big_num_lessthan"), Either::Left(big_num_lessthan)),
+        (Name("big_num_add"), Either::Left(big_num_add)),
+        (Name("big_num_sub"), Either::Left(big_num_sub)),
+        (Name("big_num_mul"), Either::Left(big_num_mul)),
+        (Name("big_num_divrem"), Either::Left(big_num_divrem)),
+        (Name("big_num_mulmod"), Either::Left(big_num_mulmod)),
+        (Name("num_to_bytes"), Either::Left(num_to_bytes)),
+        (Name("byte_to_bits"), Either::Left(byte_to_bits)),
+        (Name("sha256_schedule"), Either::Left(sha256_schedule)),
+        (Name("sha256_round"), Either::Left(sha256_round)),
+        (Name("sha256_add_state"), Either::Left(sha256_add_state)),
+        (Name("keccak_absorber"), Either::Left(keccak_absorber)),
+        (Name("keccak_permuter"), Either::Left(keccak_permuter)),
+        (Name("edwards_add"), Either::Left(edwards_add)),
+        (Name("edwards_residual"), Either::Left(edwards_residual)),
     ]
     .into_iter()
     .collect();
@@ -75,7 +125,12 @@ impl Chipset<BabyBear> for LurkChip {
             LurkChip::Hasher4(op) => op.input_size(),
             LurkChip::Hasher5(op) => op.input_size(),
             LurkChip::U64(op) => <U64 as Chipset<BabyBear>>::input_size(op),
+            LurkChip::I64(op) => <I64 as Chipset<BabyBear>>::input_size(op),
             LurkChip::BigNum(op) => <BigNum as Chipset<BabyBear>>::input_size(op),
+            LurkChip::Num(op) => <Num as Chipset<BabyBear>>::input_size(op),
+            LurkChip::Sha256(op) => <Sha256 as Chipset<BabyBear>>::input_size(op),
+            LurkChip::Keccak(op) => <Keccak as Chipset<BabyBear>>::input_size(op),
+            LurkChip::Edwards(op) => <Edwards as Chipset<BabyBear>>::input_size(op),
         }
     }
 
@@ -86,7 +141,12 @@ impl Chipset<BabyBear> for LurkChip {
             LurkChip::Hasher4(op) => op.output_size(),
             LurkChip::Hasher5(op) => op.output_size(),
             LurkChip::U64(op) => <U64 as Chipset<BabyBear>>::output_size(op),
+            LurkChip::I64(op) => <I64 as Chipset<BabyBear>>::output_size(op),
             LurkChip::BigNum(op) => <BigNum as Chipset<BabyBear>>::output_size(op),
+            LurkChip::Num(op) => <Num as Chipset<BabyBear>>::output_size(op),
+            LurkChip::Sha256(op) => <Sha256 as Chipset<BabyBear>>::output_size(op),
+            LurkChip::Keccak(op) => <Keccak as Chipset<BabyBear>>::output_size(op),
+            LurkChip::Edwards(op) => <Edwards as Chipset<BabyBear>>::output_size(op),
         }
     }
 
@@ -96,7 +156,12 @@ impl Chipset<BabyBear> for LurkChip {
             LurkChip::Hasher4(op) => op.witness_size(),
             LurkChip::Hasher5(op) => op.witness_size(),
             LurkChip::U64(op) => <U64 as Chipset<BabyBear>>::witness_size(op),
+            LurkChip::I64(op) => <I64 as Chipset<BabyBear>>::witness_size(op),
             LurkChip::BigNum(op) => <BigNum as Chipset<BabyBear>>::witness_size(op),
+            LurkChip::Num(op) => <Num as Chipset<BabyBear>>::witness_size(op),
+            LurkChip::Sha256(op) => <Sha256 as Chipset<BabyBear>>::witness_size(op),
+            LurkChip::Keccak(op) => <Keccak as Chipset<BabyBear>>::witness_size(op),
+            LurkChip::Edwards(op) => <Edwards as Chipset<BabyBear>>::witness_size(op),
         }
     }
 
@@ -106,7 +171,12 @@ impl Chipset<BabyBear> for LurkChip {
             LurkChip::Hasher4(op) => op.require_size(),
             LurkChip::Hasher5(op) => op.require_size(),
             LurkChip::U64(op) => <U64 as Chipset<BabyBear>>::require_size(op),
+            LurkChip::I64(op) => <I64 as Chipset<BabyBear>>::require_size(op),
             LurkChip::BigNum(op) => <BigNum as Chipset<BabyBear>>::require_size(op),
+            LurkChip::Num(op) => <Num as Chipset<BabyBear>>::require_size(op),
+            LurkChip::Sha256(op) => <Sha256 as Chipset<BabyBear>>::require_size(op),
+            LurkChip::Keccak(op) => <Keccak as Chipset<BabyBear>>::require_size(op),
+            LurkChip::Edwards(op) => <Edwards as Chipset<BabyBear>>::require_size(op),
         }
     }
 
@@ -115,7 +185,13 @@ impl Chipset<BabyBear> for LurkChip {
             LurkChip::Hasher3(hasher) => hasher.execute_simple(input),
             LurkChip::Hasher4(hasher) => hasher.execute_simple(input),
             LurkChip::Hasher5(hasher) => hasher.execute_simple(input),
-            LurkChip::U64(..) | LurkChip::BigNum(..) => panic!("use `execute`"),
+            LurkChip::Edwards(op) => op.execute_simple(input),
+            LurkChip::U64(..)
+            | LurkChip::I64(..)
+            | LurkChip::BigNum(..)
+            | LurkChip::Num(..)
+            | LurkChip::Sha256(..)
+            | LurkChip::Keccak(..) => panic!("use `execute`"),
         }
     }
 
@@ -131,7 +207,12 @@ impl Chipset<BabyBear> for LurkChip {
             LurkChip::Hasher4(hasher) => hasher.execute(input, nonce, queries, requires),
             LurkChip::Hasher5(hasher) => hasher.execute(input, nonce, queries, requires),
             LurkChip::U64(op) => op.execute(input, nonce, queries, requires),
+            LurkChip::I64(op) => op.execute(input, nonce, queries, requires),
             LurkChip::BigNum(op) => op.execute(input, nonce, queries, requires),
+            LurkChip::Num(op) => op.execute(input, nonce, queries, requires),
+            LurkChip::Sha256(op) => op.execute(input, nonce, queries, requires),
+            LurkChip::Keccak(op) => op.execute(input, nonce, queries, requires),
+            LurkChip::Edwards(op) => op.execute(input, nonce, queries, requires),
         }
     }
 
@@ -141,7 +222,12 @@ impl Chipset<BabyBear> for LurkChip {
             LurkChip::Hasher4(hasher) => hasher.populate_witness(input, witness),
             LurkChip::Hasher5(hasher) => hasher.populate_witness(input, witness),
             LurkChip::U64(op) => op.populate_witness(input, witness),
+            LurkChip::I64(op) => op.populate_witness(input, witness),
             LurkChip::BigNum(op) => op.populate_witness(input, witness),
+            LurkChip::Num(op) => op.populate_witness(input, witness),
+            LurkChip::Sha256(op) => op.populate_witness(input, witness),
+            LurkChip::Keccak(op) => op.populate_witness(input, witness),
+            LurkChip::Edwards(op) => op.populate_witness(input, witness),
         }
     }
 
@@ -168,7 +254,12 @@ impl Chipset<BabyBear> for LurkChip {
                 hasher.eval(builder, is_real, input, witness, nonce, requires)
             }
             LurkChip::U64(op) => op.eval(builder, is_real, input, witness, nonce, requires),
+            LurkChip::I64(op) => op.eval(builder, is_real, input, witness, nonce, requires),
             LurkChip::BigNum(op) => op.eval(builder, is_real, input, witness, nonce, requires),
+            LurkChip::Num(op) => op.eval(builder, is_real, input, witness, nonce, requires),
+            LurkChip::Sha256(op) => op.eval(builder, is_real, input, witness, nonce, requires),
+            LurkChip::Keccak(op) => op.eval(builder, is_real, input, witness, nonce, requires),
+            LurkChip::Edwards(op) => op.eval(builder, is_real, input, witness, nonce, requires),
         }
     }
 }
@@ -0,0 +1,248 @@
+use anyhow::{bail, Result};
+use camino::Utf8PathBuf;
+use clap::Args;
+use p3_baby_bear::BabyBear;
+
+use crate::core::{
+    parser::source::{parse_source, SourceNode},
+    state::State,
+};
+
+/// Lines are broken when a node doesn't fit within this width
+const MAX_WIDTH: usize = 100;
+
+#[derive(Args, Debug)]
+pub(crate) struct FmtArgs {
+    /// The Lurk files to be formatted
+    #[clap(value_parser, required = true)]
+    files: Vec<Utf8PathBuf>,
+
+    /// Flag to check whether the files are formatted without modifying them
+    #[arg(long)]
+    check: bool,
+}
+
+/// Renders a node in a single line, which is only possible if it doesn't
+/// contain comments, blank lines or atoms with line breaks
+fn fmt_flat(node: &SourceNode<'_>) -> Option<String> {
+    match node {
+        SourceNode::Comment { .. } | SourceNode::BlankLine => None,
+        SourceNode::Atom(text) => (!text.contains('\n')).then(|| text.to_string()),
+        SourceNode::Dot => Some(".".into()),
+        SourceNode::Quote(prefix, node) => Some(format!("{prefix}{}", fmt_flat(node)?)),
+        SourceNode::List { meta, nodes } => {
+            let nodes = nodes.iter().map(fmt_flat).collect::<Option<Vec<_>>>()?;
+            let open = if *meta { "!(" } else { "(" };
+            Some(format!("{open}{})", nodes.join(" ")))
+        }
+    }
+}
+
+/// Returns how many code nodes of a list stay on the line of its opening
+/// parenthesis and the indentation of the nodes that follow them:
+/// * `lambda`, `let` and `letrec` keep their first argument and indent the
+///   body by 2 spaces
+/// * `if` and other applications of symbols keep their first argument and
+///   align the others with it
+/// * meta commands keep all their arguments on the first line, breaking them
+///   internally if needed
+/// * other lists align all their elements
+fn list_layout(meta: bool, nodes: &[SourceNode<'_>], col: usize) -> (usize, usize) {
+    let inner_col = col + if meta { 2 } else { 1 };
+    let head = nodes
+        .iter()
+        .find(|node| !matches!(node, SourceNode::Comment { .. } | SourceNode::BlankLine));
+    match head {
+        Some(SourceNode::Atom(_)) if meta => (usize::MAX, 0),
+        Some(SourceNode::Atom("lambda" | "let" | "letrec")) => (2, col + 2),
+        Some(SourceNode::Atom("if")) => (2, inner_col + 3),
+        Some(SourceNode::Atom(head)) => (2, inner_col + head.chars().count() + 1),
+        _ => (1, inner_col),
+    }
+}
+
+fn newline(out: &mut String, indent: usize) {
+    out.push('\n');
+    out.push_str(&" ".repeat(indent));
+}
+
+fn fmt_list(meta: bool, nodes: &[SourceNode<'_>], col: usize) -> String {
+    let (num_header_nodes, indent) = list_layout(meta, nodes, col);
+    let mut out = String::from(if meta { "!(" } else { "(" });
+    let mut cur_col = col + out.len();
+    // meta commands break the line after their first argument spanning multiple lines
+    let indent = if meta { cur_col } else { indent };
+    let mut num_code_nodes = 0;
+    let mut must_break = false;
+    let mut prev_multiline = false;
+    for (i, node) in nodes.iter().enumerate() {
+        match node {
+            SourceNode::Comment {
+                text,
+                trailing: true,
+            } if num_code_nodes > 0 => {
+                out.push(' ');
+                out.push_str(text);
+                must_break = true;
+            }
+            SourceNode::Comment { text, .. } => {
+                newline(&mut out, indent);
+                out.push_str(text);
+                must_break = true;
+            }
+            SourceNode::BlankLine => {
+                // blank lines right before the closing parenthesis are dropped
+                if i + 1 < nodes.len() {
+                    out.push('\n');
+                }
+                must_break = true;
+            }
+            _ => {
+                let same_line = !must_break
+                    && (num_code_nodes == 0
+                        || num_code_nodes < num_header_nodes
+                            && !(meta && num_code_nodes > 1 && prev_multiline));
+                if !same_line {
+                    newline(&mut out, indent);
+                    cur_col = indent;
+                } else if num_code_nodes > 0 {
+                    out.push(' ');
+                    cur_col += 1;
+                }
+                let formatted = fmt_node(node, cur_col);
+                match formatted.rsplit_once('\n') {
+                    Some((_, last_line)) => {
+                        cur_col = last_line.chars().count();
+                        prev_multiline = true;
+                    }
+                    None => {
+                        cur_col += formatted.chars().count();
+                        prev_multiline = false;
+                    }
+                }
+                out.push_str(&formatted);
+                num_code_nodes += 1;
+                must_break = false;
+            }
+        }
+    }
+    if must_break {
+        // the list ended with a comment
+        newline(&mut out, indent);
+    }
+    out.push(')');
+    out
+}
+
+/// Formats a node that starts at column `col`
+fn fmt_node(node: &SourceNode<'_>, col: usize) -> String {
+    if let Some(flat) = fmt_flat(node) {
+        if col + flat.chars().count() <= MAX_WIDTH {
+            return flat;
+        }
+    }
+    match node {
+        SourceNode::Quote(prefix, node) => {
+            format!("{prefix}{}", fmt_node(node, col + prefix.len()))
+        }
+        SourceNode::List { meta, nodes } => fmt_list(*meta, nodes, col),
+        SourceNode::Atom(text) | SourceNode::Comment { text, .. } => text.to_string(),
+        SourceNode::Dot => ".".into(),
+        SourceNode::BlankLine => String::new(),
+    }
+}
+
+/// Formats Lurk source code, preserving comments and single blank lines
+pub(crate) fn format_source(input: &str) -> Result<String> {
+    let state = State::init_lurk_state().rccell();
+    let nodes = parse_source::<BabyBear>(input, state)?;
+    let mut out = String::new();
+    for (i, node) in nodes.iter().enumerate() {
+        match node {
+            SourceNode::Comment {
+                text,
+                trailing: true,
+            } if !out.is_empty() => {
+                // put the comment back on the line of the previous node
+                out.pop();
+                out.push(' ');
+                out.push_str(text);
+                out.push('\n');
+            }
+            SourceNode::BlankLine => {
+                if i + 1 < nodes.len() {
+                    out.push('\n');
+                }
+            }
+            _ => {
+                out.push_str(&fmt_node(node, 0));
+                out.push('\n');
+            }
+        }
+    }
+    Ok(out)
+}
+
+impl FmtArgs {
+    pub(crate) fn run(self) -> Result<()> {
+        let FmtArgs { files, check } = self;
+        let mut num_unformatted = 0;
+        for file in &files {
+            let input = std::fs::read_to_string(file)?;
+            let formatted = match format_source(&input) {
+                Ok(formatted) => formatted,
+                Err(e) => bail!("Failed to parse {file}: {e}"),
+            };
+            if formatted == input {
+                continue;
+            }
+            if check {
+                println!("{file} is not formatted");
+                num_unformatted += 1;
+            } else {
+                std::fs::write(file, formatted)?;
+                println!("Formatted {file}");
+            }
+        }
+        if num_unformatted > 0 {
+            bail!("{num_unformatted} file(s) would be reformatted");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::format_source;
+
+    #[test]
+    fn test_format_source() {
+        let input =
+            "; a comment\n\n\n!(def  x   1) ; trailing\n(let ((a 1)\n(b 2)) ; here\n  (+ a b))";
+        let expected =
+            "; a comment\n\n!(def x 1) ; trailing\n(let ((a 1) (b 2)) ; here\n  (+ a b))\n";
+        assert_eq!(format_source(input).unwrap(), expected);
+        // formatting is idempotent
+        assert_eq!(format_source(expected).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_source_breaks_long_forms() {
+        let input = "!(defrec sum (lambda (vals) (if vals (+ (car vals) (sum (cdr vals))) (begin (emit :some-long-keyword-to-exceed-the-width) 0))))";
+        let expected = "\
+!(defrec sum (lambda (vals)
+               (if vals
+                   (+ (car vals) (sum (cdr vals)))
+                   (begin (emit :some-long-keyword-to-exceed-the-width) 0))))
+";
+        assert_eq!(format_source(input).unwrap(), expected);
+        assert_eq!(format_source(expected).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_source_quasiquote() {
+        let input = "!(defmacro  unless (c x)  `(if ,c   nil ,@(list x)))";
+        let expected = "!(defmacro unless (c x) `(if ,c nil ,@(list x)))\n";
+        assert_eq!(format_source(input).unwrap(), expected);
+    }
+}
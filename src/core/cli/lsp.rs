@@ -0,0 +1,634 @@
+//! A minimal language server for Lurk, speaking LSP (JSON-RPC) over stdio
+
+use anyhow::{bail, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use nom::{sequence::preceded, Parser};
+use p3_baby_bear::BabyBear;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    io::{self, BufRead, Read, Write},
+};
+
+use crate::{
+    core::{
+        chipset::LurkChip,
+        package::{Package, SymbolRef},
+        parser::{
+            error::ParseError,
+            position::Pos,
+            syntax::{parse_space, parse_syntax_eof},
+            Span,
+        },
+        state::{meta_sym, State, StateRcCell, BUILTIN_SYMBOLS, META_SYMBOLS},
+        syntax::Syntax,
+    },
+    lair::chipset::NoChip,
+};
+
+use super::meta::{meta_cmds, MetaCmdsMap};
+
+type F = BabyBear;
+
+/// LSP's `CompletionItemKind` values
+const COMPLETION_KIND_FUNCTION: u8 = 3;
+const COMPLETION_KIND_VARIABLE: u8 = 6;
+const COMPLETION_KIND_KEYWORD: u8 = 14;
+
+/// JSON-RPC's "method not found" error code
+const METHOD_NOT_FOUND: i32 = -32601;
+
+fn read_message(reader: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(length) = line.strip_prefix("Content-Length:") {
+            content_length = Some(length.trim().parse::<usize>()?);
+        }
+    }
+    let Some(content_length) = content_length else {
+        bail!("Missing Content-Length header");
+    };
+    let mut content = vec![0; content_length];
+    Read::read_exact(reader, &mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+fn write_message(writer: &mut impl Write, message: &Value) -> Result<()> {
+    let content = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{content}", content.len())?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = if bytes[i] == b'%' {
+            s.get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+        if let Some(byte) = escaped {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn uri_to_path(uri: &str) -> Utf8PathBuf {
+    percent_decode(uri.strip_prefix("file://").unwrap_or(uri)).into()
+}
+
+fn path_to_uri(path: &Utf8Path) -> String {
+    format!("file://{path}")
+}
+
+/// Converts an LSP position (zero-based line and character) into a byte offset
+fn offset_at(text: &str, line: usize, character: usize) -> usize {
+    let mut offset = 0;
+    for (i, line_text) in text.split_inclusive('\n').enumerate() {
+        if i == line {
+            let column: usize = line_text.chars().take(character).map(char::len_utf8).sum();
+            return offset + column;
+        }
+        offset += line_text.len();
+    }
+    offset
+}
+
+fn range(from_line: usize, from_column: usize, upto_line: usize, upto_column: usize) -> Value {
+    json!({
+        "start": { "line": from_line, "character": from_column },
+        "end": { "line": upto_line, "character": upto_column },
+    })
+}
+
+/// Converts a `Pos`, whose lines and columns start at 1, into an LSP range
+fn pos_range(pos: &Pos) -> Value {
+    match pos {
+        Pos::No => range(0, 0, 0, 0),
+        Pos::Pos {
+            from_line,
+            from_column,
+            upto_line,
+            upto_column,
+            ..
+        } => range(
+            from_line.saturating_sub(1),
+            from_column.saturating_sub(1),
+            upto_line.saturating_sub(1),
+            upto_column.saturating_sub(1),
+        ),
+    }
+}
+
+fn pos_contains(pos: &Pos, offset: usize) -> bool {
+    match pos {
+        Pos::No => false,
+        Pos::Pos {
+            from_offset,
+            upto_offset,
+            ..
+        } => *from_offset <= offset && offset <= *upto_offset,
+    }
+}
+
+fn diagnostic(range: Value, message: String) -> Value {
+    json!({
+        "range": range,
+        "severity": 1,
+        "source": "lurk",
+        "message": message,
+    })
+}
+
+fn parse_error_diagnostic(e: &ParseError<Span<'_>>) -> Value {
+    let line = (e.input.location_line() as usize).saturating_sub(1);
+    let column = e.input.get_utf8_column().saturating_sub(1);
+    let mut messages = e
+        .errors
+        .iter()
+        .filter(|kind| !kind.is_nom_err())
+        .map(|kind| format!("{kind}"))
+        .collect::<Vec<_>>();
+    if let Some(expected) = e.expected {
+        messages.insert(0, format!("Expected {expected}"));
+    }
+    let message = if messages.is_empty() {
+        "Syntax error".into()
+    } else {
+        messages.join("\n")
+    };
+    diagnostic(range(line, column, line, column + 1), message)
+}
+
+/// Finds the innermost `Syntax` node that contains a byte offset
+fn innermost(syn: &Syntax<F>, offset: usize) -> Option<&Syntax<F>> {
+    if !pos_contains(syn.get_pos(), offset) {
+        return None;
+    }
+    let children: Vec<&Syntax<F>> = match syn {
+        Syntax::Quote(_, x)
+        | Syntax::Quasiquote(_, x)
+        | Syntax::Unquote(_, x)
+        | Syntax::UnquoteSplicing(_, x) => vec![x.as_ref()],
+        Syntax::List(_, xs) | Syntax::Meta(_, _, xs) => xs.iter().collect(),
+        Syntax::Improper(_, xs, end) => xs.iter().chain([end.as_ref()]).collect(),
+        Syntax::Env(_, pairs) => pairs.iter().map(|(_, x)| x).collect(),
+        _ => vec![],
+    };
+    children
+        .into_iter()
+        .find_map(|x| innermost(x, offset))
+        .or(Some(syn))
+}
+
+/// The result of analyzing a document
+struct Analysis {
+    /// The top-level forms of the document
+    forms: Vec<Syntax<F>>,
+    diagnostics: Vec<Value>,
+    /// Locations of the bindings made with `!(def ...)`, `!(defq ...)`,
+    /// `!(defrec ...)` and `!(defmacro ...)` in the document and in the files
+    /// it loads
+    definitions: FxHashMap<SymbolRef, Value>,
+    /// The state after the document was processed
+    state: StateRcCell,
+}
+
+impl Analysis {
+    fn node_at(&self, offset: usize) -> Option<&Syntax<F>> {
+        self.forms.iter().find_map(|form| innermost(form, offset))
+    }
+}
+
+/// Walks through documents mimicking the effects that meta commands have on
+/// the REPL state, without evaluating anything
+struct Analyzer<'a> {
+    documents: &'a FxHashMap<String, String>,
+    meta_cmds: &'a MetaCmdsMap<F, LurkChip, NoChip>,
+    state: StateRcCell,
+    definitions: FxHashMap<SymbolRef, Value>,
+    visited: FxHashSet<Utf8PathBuf>,
+}
+
+impl Analyzer<'_> {
+    fn analyze_source(
+        &mut self,
+        uri: &str,
+        path: &Utf8Path,
+        text: &str,
+    ) -> (Vec<Syntax<F>>, Vec<Value>) {
+        let mut forms = vec![];
+        let mut diagnostics = vec![];
+        let mut input = Span::new(text);
+        loop {
+            match preceded(parse_space, parse_syntax_eof::<F>(self.state.clone(), true))
+                .parse(input)
+            {
+                Ok((_, None)) => break,
+                Ok((rest, Some(syn))) => {
+                    if let Syntax::Meta(pos, sym, args) = &syn {
+                        self.process_meta(uri, path, pos, sym, args, &mut diagnostics);
+                    }
+                    forms.push(syn);
+                    input = rest;
+                }
+                Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+                    diagnostics.push(parse_error_diagnostic(&e));
+                    break;
+                }
+                Err(nom::Err::Incomplete(_)) => {
+                    let line = (input.location_line() as usize).saturating_sub(1);
+                    let column = input.get_utf8_column().saturating_sub(1);
+                    let error_range = range(line, column, line, column + 1);
+                    diagnostics.push(diagnostic(error_range, "Incomplete input".into()));
+                    break;
+                }
+            }
+        }
+        (forms, diagnostics)
+    }
+
+    fn analyze_loaded(&mut self, path: &Utf8Path) -> Result<()> {
+        if !self.visited.insert(path.to_path_buf()) {
+            return Ok(());
+        }
+        let uri = path_to_uri(path);
+        let text = match self.documents.get(&uri) {
+            Some(text) => text.clone(),
+            None => {
+                let Ok(text) = fs::read_to_string(path) else {
+                    bail!("Can't read {path}");
+                };
+                text
+            }
+        };
+        let (_, diagnostics) = self.analyze_source(&uri, path, &text);
+        if !diagnostics.is_empty() {
+            bail!("Errors found in {path}");
+        }
+        Ok(())
+    }
+
+    fn package_name(&self, syn: &Syntax<F>) -> Option<SymbolRef> {
+        match syn {
+            Syntax::String(_, name) => Some(self.state.borrow_mut().intern(name)),
+            Syntax::Symbol(_, name) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    fn process_meta(
+        &mut self,
+        uri: &str,
+        path: &Utf8Path,
+        pos: &Pos,
+        sym: &SymbolRef,
+        args: &[Syntax<F>],
+        diagnostics: &mut Vec<Value>,
+    ) {
+        if !self.meta_cmds.contains_key(&**sym) {
+            diagnostics.push(diagnostic(
+                pos_range(pos),
+                format!("Invalid meta command: {sym}"),
+            ));
+            return;
+        }
+        let Ok(name) = sym.name() else {
+            return;
+        };
+        let mut error = |message: String| diagnostics.push(diagnostic(pos_range(pos), message));
+        match (name, args) {
+            ("def" | "defq" | "defrec" | "defmacro", [Syntax::Symbol(var_pos, var), ..]) => {
+                let location = json!({ "uri": uri, "range": pos_range(var_pos) });
+                self.definitions.insert(var.clone(), location);
+            }
+            ("load", [Syntax::String(_, file_name)]) => {
+                let file_path = match path.parent() {
+                    Some(dir) => dir.join(file_name),
+                    None => file_name.into(),
+                };
+                if let Err(e) = self.analyze_loaded(&file_path) {
+                    error(format!("{e}"));
+                }
+            }
+            ("defpackage", [name, ..]) => match self.package_name(name) {
+                Some(name) => self.state.borrow_mut().add_package(Package::new(name)),
+                None => error("Package name must be a string or a symbol".into()),
+            },
+            ("in-package", [name]) => match self.package_name(name) {
+                Some(name) => {
+                    if let Err(e) = self.state.borrow_mut().set_current_package(name) {
+                        error(format!("{e}"));
+                    }
+                }
+                None => error("Expected string or symbol".into()),
+            },
+            ("import", [symbols, ..]) => {
+                let symbols = match symbols {
+                    Syntax::Symbol(_, symbol) => vec![symbol.clone()],
+                    Syntax::List(_, xs) => xs
+                        .iter()
+                        .filter_map(|x| match x {
+                            Syntax::Symbol(_, symbol) => Some(symbol.clone()),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => vec![],
+                };
+                if let Err(e) = self.state.borrow_mut().import(&symbols) {
+                    error(format!("{e}"));
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+struct LanguageServer {
+    /// The contents of the open documents, indexed by their URIs
+    documents: FxHashMap<String, String>,
+    meta_cmds: MetaCmdsMap<F, LurkChip, NoChip>,
+}
+
+impl LanguageServer {
+    fn new() -> Self {
+        Self {
+            documents: Default::default(),
+            meta_cmds: meta_cmds(),
+        }
+    }
+
+    /// Analyzes an open document, following the files it loads
+    fn analyze(&self, uri: &str) -> Analysis {
+        let mut analyzer = Analyzer {
+            documents: &self.documents,
+            meta_cmds: &self.meta_cmds,
+            state: State::init_lurk_state().rccell(),
+            definitions: Default::default(),
+            visited: Default::default(),
+        };
+        let (forms, diagnostics) = match self.documents.get(uri) {
+            Some(text) => {
+                let path = uri_to_path(uri);
+                analyzer.visited.insert(path.clone());
+                analyzer.analyze_source(uri, &path, text)
+            }
+            None => Default::default(),
+        };
+        let Analyzer {
+            state, definitions, ..
+        } = analyzer;
+        Analysis {
+            forms,
+            diagnostics,
+            definitions,
+            state,
+        }
+    }
+
+    fn publish_diagnostics(&self, uri: &str) -> Value {
+        let Analysis { diagnostics, .. } = self.analyze(uri);
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        })
+    }
+
+    /// Extracts the document URI and the byte offset of a `TextDocumentPositionParams`
+    fn document_position<'a>(&self, params: &'a Value) -> Option<(&'a str, usize)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let text = self.documents.get(uri)?;
+        let line = params["position"]["line"].as_u64()?;
+        let character = params["position"]["character"].as_u64()?;
+        Some((uri, offset_at(text, line as usize, character as usize)))
+    }
+
+    fn definition(&self, params: &Value) -> Value {
+        let Some((uri, offset)) = self.document_position(params) else {
+            return Value::Null;
+        };
+        let analysis = self.analyze(uri);
+        match analysis.node_at(offset) {
+            Some(Syntax::Symbol(_, sym)) => analysis
+                .definitions
+                .get(sym)
+                .cloned()
+                .unwrap_or(Value::Null),
+            _ => Value::Null,
+        }
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let Some((uri, offset)) = self.document_position(params) else {
+            return Value::Null;
+        };
+        let analysis = self.analyze(uri);
+        let (pos, value) = match analysis.node_at(offset) {
+            Some(Syntax::Meta(pos, sym, _)) => match self.meta_cmds.get(&**sym) {
+                Some(meta_cmd) => (pos, meta_cmd.fmt_help()),
+                None => return Value::Null,
+            },
+            // show the fully qualified symbol, as resolved by the current package
+            Some(Syntax::Symbol(pos, sym)) => (pos, sym.fmt_to_string()),
+            _ => return Value::Null,
+        };
+        json!({
+            "contents": { "kind": "plaintext", "value": value },
+            "range": pos_range(pos),
+        })
+    }
+
+    fn completion(&self, params: &Value) -> Value {
+        let mut items = Vec::with_capacity(BUILTIN_SYMBOLS.len() + META_SYMBOLS.len());
+        for name in BUILTIN_SYMBOLS {
+            items.push(json!({
+                "label": name,
+                "kind": COMPLETION_KIND_FUNCTION,
+                "detail": "builtin",
+            }));
+        }
+        for name in META_SYMBOLS {
+            let summary = self.meta_cmds.get(&meta_sym(name)).map(|m| m.summary());
+            items.push(json!({
+                "label": name,
+                "kind": COMPLETION_KIND_KEYWORD,
+                "detail": summary.unwrap_or_default(),
+            }));
+        }
+        if let Some(uri) = params["textDocument"]["uri"].as_str() {
+            let analysis = self.analyze(uri);
+            let state = analysis.state.borrow();
+            for sym in analysis.definitions.keys().sorted() {
+                items.push(json!({
+                    "label": state.fmt_to_string(sym),
+                    "kind": COMPLETION_KIND_VARIABLE,
+                    "detail": "definition",
+                }));
+            }
+        }
+        Value::Array(items)
+    }
+
+    fn handle_request(&self, method: &str, params: &Value) -> Result<Value> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    // full document synchronization
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "hoverProvider": true,
+                    "completionProvider": { "triggerCharacters": ["(", "!"] },
+                },
+                "serverInfo": { "name": "lurk", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/definition" => Ok(self.definition(params)),
+            "textDocument/hover" => Ok(self.hover(params)),
+            "textDocument/completion" => Ok(self.completion(params)),
+            _ => bail!("Method not found: {method}"),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Option<Value> {
+        match method {
+            "textDocument/didOpen" => {
+                let uri = params["textDocument"]["uri"].as_str()?;
+                let text = params["textDocument"]["text"].as_str()?;
+                self.documents.insert(uri.into(), text.into());
+                Some(self.publish_diagnostics(uri))
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str()?;
+                // with full synchronization, the last change has the whole text
+                let text = params["contentChanges"].as_array()?.last()?["text"].as_str()?;
+                self.documents.insert(uri.into(), text.into());
+                Some(self.publish_diagnostics(uri))
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str()?;
+                self.documents.remove(uri);
+                Some(json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": [] },
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Runs the language server until the client sends an `exit` notification or
+/// closes the input stream
+pub(crate) fn run_lsp() -> Result<()> {
+    let mut reader = io::stdin().lock();
+    let mut writer = io::stdout().lock();
+    let mut server = LanguageServer::new();
+    while let Some(message) = read_message(&mut reader)? {
+        // ignore responses from the client since the server makes no requests
+        let Some(method) = message["method"].as_str() else {
+            continue;
+        };
+        let params = &message["params"];
+        if let Some(id) = message.get("id") {
+            let response = match server.handle_request(method, params) {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err(e) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": METHOD_NOT_FOUND, "message": format!("{e}") },
+                }),
+            };
+            write_message(&mut writer, &response)?;
+        } else if method == "exit" {
+            break;
+        } else if let Some(notification) = server.handle_notification(method, params) {
+            write_message(&mut writer, &notification)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::{offset_at, LanguageServer};
+
+    const URI: &str = "file:///tmp/test.lurk";
+
+    fn server_with(text: &str) -> LanguageServer {
+        let mut server = LanguageServer::new();
+        server.documents.insert(URI.into(), text.into());
+        server
+    }
+
+    fn position_params(line: usize, character: usize) -> Value {
+        json!({
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character },
+        })
+    }
+
+    #[test]
+    fn test_offset_at() {
+        let text = "ab\ncd\n";
+        assert_eq!(offset_at(text, 0, 1), 1);
+        assert_eq!(offset_at(text, 1, 0), 3);
+        assert_eq!(offset_at(text, 1, 2), 5);
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let server = server_with("!(def a 1)\n(+ a 1");
+        let diagnostics = server.analyze(URI).diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+
+        let server = server_with("!(def a 1)\n!(foo)");
+        let diagnostics = server.analyze(URI).diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+    }
+
+    #[test]
+    fn test_definition() {
+        let server = server_with("!(def foo 1)\n(+ foo 1)");
+        let location = server.definition(&position_params(1, 4));
+        assert_eq!(location["uri"], URI);
+        assert_eq!(location["range"]["start"]["line"], 0);
+        assert_eq!(location["range"]["start"]["character"], 6);
+
+        // symbols from different packages don't get mixed
+        let server = server_with("!(def foo 1)\n!(defpackage abc)\n!(in-package abc)\nfoo");
+        assert_eq!(server.definition(&position_params(3, 1)), Value::Null);
+    }
+
+    #[test]
+    fn test_hover() {
+        let server = server_with("!(load \"foo.lurk\")");
+        let hover = server.hover(&position_params(0, 1));
+        let help = hover["contents"]["value"].as_str().unwrap();
+        assert!(help.starts_with("load - "));
+    }
+}
@@ -0,0 +1,331 @@
+//! Macro expansion for the REPL.
+//!
+//! Before a form is reduced, the REPL expands the calls to the macros defined
+//! with `!(defmacro ...)` in the current package, as well as the `quasiquote`
+//! forms produced by macros.
+//!
+//! Expansion is hygienic w.r.t. the bindings introduced by macros: the symbols
+//! in a macro call's arguments are replaced by fresh marked symbols while the
+//! macro runs. The bindings (`lambda`, `let` and `letrec`) in the expansion
+//! whose symbols aren't marked were introduced by the macro itself, so they're
+//! renamed to fresh symbols before the marks are removed. This way, they can't
+//! capture the variables from the arguments.
+
+use anyhow::{bail, Result};
+use p3_field::PrimeField32;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    core::{
+        package::Digest,
+        state::{builtin_sym, lurk_sym, LURK_SYMBOLS, READER_SYMBOLS},
+        symbol::Symbol,
+        tag::Tag,
+        zstore::ZPtr,
+    },
+    lair::chipset::Chipset,
+};
+
+use super::repl::Repl;
+
+type Renaming<F> = FxHashMap<ZPtr<F>, ZPtr<F>>;
+
+/// Converts a pointer's digest to the representation kept by packages
+pub(crate) fn to_package_digest<F: PrimeField32>(zptr: &ZPtr<F>) -> Digest {
+    zptr.digest.map(|f| f.as_canonical_u32())
+}
+
+/// Recovers a pointer from a digest kept by a package
+pub(crate) fn from_package_digest<F: PrimeField32>(tag: Tag, digest: &Digest) -> ZPtr<F> {
+    ZPtr {
+        tag,
+        digest: digest.map(F::from_canonical_u32),
+    }
+}
+
+impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
+    #[inline]
+    fn builtin(&mut self, name: &str) -> ZPtr<F> {
+        self.zstore
+            .intern_symbol(&builtin_sym(name), &self.lang_symbols)
+    }
+
+    #[inline]
+    fn lurk(&mut self, name: &str) -> ZPtr<F> {
+        self.zstore.intern_symbol_no_lang(&lurk_sym(name))
+    }
+
+    /// Whether a symbol belongs to the `.lurk` package, whose symbols are never
+    /// renamed
+    fn is_lurk_symbol(&mut self, zptr: &ZPtr<F>) -> bool {
+        LURK_SYMBOLS
+            .iter()
+            .chain(&READER_SYMBOLS)
+            .any(|name| &self.lurk(name) == zptr)
+    }
+
+    /// Returns the elements of a list, along with its tail if it's improper
+    fn list_elts(&self, zptr: &ZPtr<F>) -> (Vec<ZPtr<F>>, Option<ZPtr<F>>) {
+        let (elts, tail) = self.zstore.fetch_list(zptr);
+        (elts.into_iter().copied().collect(), tail.copied())
+    }
+
+    /// Returns the function of the macro named by `head` in the current package
+    fn macro_fun(&self, head: &ZPtr<F>) -> Option<ZPtr<F>> {
+        if head.tag != Tag::Sym {
+            return None;
+        }
+        let state = self.state.borrow();
+        let fun = state.get_macro(&to_package_digest(head))?;
+        Some(from_package_digest(Tag::Fun, fun))
+    }
+
+    /// Creates a symbol that can't clash with the ones written by users by
+    /// appending a unique suffix to the name of `zptr`
+    fn gensym(&mut self, zptr: &ZPtr<F>) -> ZPtr<F> {
+        let mut path = self.zstore.fetch_symbol(zptr).path().to_vec();
+        let name = path.pop().unwrap_or_default();
+        self.gensym_counter += 1;
+        path.push(format!("{name}#{}", self.gensym_counter));
+        self.zstore
+            .intern_symbol(&Symbol::sym_from_vec(path), &self.lang_symbols)
+    }
+
+    /// Expands quasiquotes and macro calls in a Lurk expression, recursively
+    pub(crate) fn macroexpand(&mut self, expr: &ZPtr<F>) -> Result<ZPtr<F>> {
+        if expr.tag != Tag::Cons {
+            return Ok(*expr);
+        }
+        let (&head, &args) = self.zstore.fetch_tuple11(expr);
+        if head == *self.zstore.quote() {
+            return Ok(*expr);
+        }
+        if head == self.lurk("quasiquote") {
+            let [&x] = self.take(&args)?;
+            let expanded = self.zstore.expand_quasiquote(&x)?;
+            return self.macroexpand(&expanded);
+        }
+        if head == self.lurk("unquote") || head == self.lurk("unquote-splicing") {
+            bail!("{} outside of a quasiquote", self.fmt(&head));
+        }
+        if let Some(fun) = self.macro_fun(&head) {
+            let expanded = self.expand_macro_call(&fun, &args)?;
+            return self.macroexpand(&expanded);
+        }
+        let (mut elts, tail) = self.list_elts(expr);
+        // the positions of `elts` that aren't expressions
+        let skip = if head == self.builtin("lambda")
+            || head == self.builtin("eqq")
+            || head == self.builtin("type-eqq")
+        {
+            1
+        } else if head == self.builtin("let") || head == self.builtin("letrec") {
+            if let Some(bindings) = elts.get(1).filter(|b| b.tag == Tag::Cons) {
+                let (bindings, bindings_tail) = self.list_elts(bindings);
+                let bindings = bindings
+                    .iter()
+                    .map(|binding| self.map_binding(binding, |repl, val| repl.macroexpand(val)))
+                    .collect::<Result<Vec<_>>>()?;
+                elts[1] = self.intern_list_with_tail(bindings, bindings_tail);
+            }
+            1
+        } else {
+            0
+        };
+        for elt in elts.iter_mut().skip(skip + 1) {
+            *elt = self.macroexpand(elt)?;
+        }
+        if skip == 0 {
+            elts[0] = self.macroexpand(&elts[0])?;
+        }
+        Ok(self.intern_list_with_tail(elts, tail))
+    }
+
+    fn intern_list_with_tail(&mut self, elts: Vec<ZPtr<F>>, tail: Option<ZPtr<F>>) -> ZPtr<F> {
+        match tail {
+            None => self.zstore.intern_list(elts),
+            Some(tail) => self.zstore.intern_list_full(elts, tail),
+        }
+    }
+
+    /// Applies `f` to the value of a `(<symbol> <value>)` binding, leaving
+    /// malformed bindings untouched
+    fn map_binding(
+        &mut self,
+        binding: &ZPtr<F>,
+        f: impl FnOnce(&mut Self, &ZPtr<F>) -> Result<ZPtr<F>>,
+    ) -> Result<ZPtr<F>> {
+        if binding.tag != Tag::Cons {
+            return Ok(*binding);
+        }
+        match self.list_elts(binding) {
+            (elts, None) if elts.len() == 2 => {
+                let val = f(self, &elts[1])?;
+                Ok(self.zstore.intern_list([elts[0], val]))
+            }
+            _ => Ok(*binding),
+        }
+    }
+
+    /// Applies a macro function to the arguments of a macro call, returning the
+    /// hygienic expansion
+    fn expand_macro_call(&mut self, fun: &ZPtr<F>, args: &ZPtr<F>) -> Result<ZPtr<F>> {
+        if args.tag != Tag::Cons && args != self.zstore.nil() {
+            bail!("Invalid macro call arguments: {}", self.fmt(args));
+        }
+        if let (_, Some(_)) = self.zstore.fetch_list(args) {
+            bail!("Invalid macro call arguments: {}", self.fmt(args));
+        }
+        let mut marks = Renaming::default();
+        let marked_args = self.mark(args, &mut marks);
+        let apply = self.builtin("apply");
+        let marked_args = self.zstore.intern_quoted(marked_args);
+        let expr = self.zstore.intern_list([apply, *fun, marked_args]);
+        let env = self.env;
+        let (expansion, _) = self.reduce_aux_with_env(&expr, &env)?;
+        if expansion.tag == Tag::Err {
+            bail!(
+                "Macro expansion error: {}",
+                self.zstore.fmt_error_with_state(&self.state, &expansion)
+            );
+        }
+        self.memoize_dag(&expansion);
+        let unmarks: Renaming<F> = marks.into_iter().map(|(sym, mark)| (mark, sym)).collect();
+        let renamed = self.rename_binders(&expansion, &unmarks, &Renaming::default());
+        Ok(self.rename_symbols(&renamed, &unmarks))
+    }
+
+    /// Replaces the symbols in `zptr` by marked ones, registering them in `marks`
+    fn mark(&mut self, zptr: &ZPtr<F>, marks: &mut Renaming<F>) -> ZPtr<F> {
+        match zptr.tag {
+            Tag::Sym if !self.is_lurk_symbol(zptr) => {
+                if let Some(mark) = marks.get(zptr) {
+                    *mark
+                } else {
+                    let mark = self.gensym(zptr);
+                    marks.insert(*zptr, mark);
+                    mark
+                }
+            }
+            Tag::Cons => {
+                let (&car, &cdr) = self.zstore.fetch_tuple11(zptr);
+                let car = self.mark(&car, marks);
+                let cdr = self.mark(&cdr, marks);
+                self.zstore.intern_cons(car, cdr)
+            }
+            _ => *zptr,
+        }
+    }
+
+    /// Replaces the symbols in `zptr` according to `renaming`
+    fn rename_symbols(&mut self, zptr: &ZPtr<F>, renaming: &Renaming<F>) -> ZPtr<F> {
+        match zptr.tag {
+            Tag::Sym => renaming.get(zptr).copied().unwrap_or(*zptr),
+            Tag::Cons => {
+                let (&car, &cdr) = self.zstore.fetch_tuple11(zptr);
+                let car = self.rename_symbols(&car, renaming);
+                let cdr = self.rename_symbols(&cdr, renaming);
+                self.zstore.intern_cons(car, cdr)
+            }
+            _ => *zptr,
+        }
+    }
+
+    /// Whether a binding symbol was introduced by a macro, in which case it
+    /// must be renamed
+    fn is_introduced(&mut self, zptr: &ZPtr<F>, unmarks: &Renaming<F>) -> bool {
+        zptr.tag == Tag::Sym && !unmarks.contains_key(zptr) && !self.is_lurk_symbol(zptr)
+    }
+
+    /// Renames the symbols bound by macro-introduced bindings within their scopes.
+    /// `scope` holds the renamings of the enclosing bindings.
+    fn rename_binders(
+        &mut self,
+        zptr: &ZPtr<F>,
+        unmarks: &Renaming<F>,
+        scope: &Renaming<F>,
+    ) -> ZPtr<F> {
+        match zptr.tag {
+            Tag::Sym => scope.get(zptr).copied().unwrap_or(*zptr),
+            Tag::Cons => {
+                let (&head, _) = self.zstore.fetch_tuple11(zptr);
+                if head == *self.zstore.quote() {
+                    return *zptr;
+                }
+                let (mut elts, tail) = self.list_elts(zptr);
+                let mut scope = scope.clone();
+                let mut skip = 0;
+                if elts.len() > 2 && (elts[1].tag == Tag::Cons || elts[1] == *self.zstore.nil()) {
+                    if head == self.builtin("lambda") {
+                        let (params, params_tail) = self.list_elts(&elts[1]);
+                        for param in &params {
+                            if self.is_introduced(param, unmarks) {
+                                let fresh = self.gensym(param);
+                                scope.insert(*param, fresh);
+                            }
+                        }
+                        let params = params
+                            .iter()
+                            .map(|p| self.rename_symbols(p, &scope))
+                            .collect();
+                        elts[1] = self.intern_list_with_tail(params, params_tail);
+                        skip = 1;
+                    } else if head == self.builtin("let") || head == self.builtin("letrec") {
+                        let (bindings, bindings_tail) = self.list_elts(&elts[1]);
+                        let is_letrec = head == self.builtin("letrec");
+                        let mut renamed = Vec::with_capacity(bindings.len());
+                        if is_letrec {
+                            for binding in &bindings {
+                                self.bind_fresh(binding, unmarks, &mut scope);
+                            }
+                        }
+                        for binding in &bindings {
+                            let binding = self.map_binding(binding, |repl, val| {
+                                Ok(repl.rename_binders(val, unmarks, &scope))
+                            });
+                            let binding = binding.expect("renaming is infallible");
+                            if !is_letrec {
+                                // `let` bindings are sequential
+                                self.bind_fresh(&binding, unmarks, &mut scope);
+                            }
+                            renamed.push(self.rename_binding_symbol(&binding, &scope));
+                        }
+                        elts[1] = self.intern_list_with_tail(renamed, bindings_tail);
+                        skip = 1;
+                    }
+                }
+                if skip == 0 {
+                    elts[0] = self.rename_binders(&elts[0], unmarks, &scope);
+                }
+                for elt in elts.iter_mut().skip(skip + 1) {
+                    *elt = self.rename_binders(elt, unmarks, &scope);
+                }
+                let tail = tail.map(|tail| self.rename_binders(&tail, unmarks, &scope));
+                self.intern_list_with_tail(elts, tail)
+            }
+            _ => *zptr,
+        }
+    }
+
+    /// Registers a fresh symbol in `scope` if the symbol of a binding was
+    /// introduced by a macro
+    fn bind_fresh(&mut self, binding: &ZPtr<F>, unmarks: &Renaming<F>, scope: &mut Renaming<F>) {
+        if binding.tag != Tag::Cons {
+            return;
+        }
+        let (&sym, _) = self.zstore.fetch_tuple11(binding);
+        if self.is_introduced(&sym, unmarks) {
+            let fresh = self.gensym(&sym);
+            scope.insert(sym, fresh);
+        }
+    }
+
+    fn rename_binding_symbol(&mut self, binding: &ZPtr<F>, scope: &Renaming<F>) -> ZPtr<F> {
+        if binding.tag != Tag::Cons {
+            return *binding;
+        }
+        let (&sym, &rest) = self.zstore.fetch_tuple11(binding);
+        let sym = scope.get(&sym).copied().unwrap_or(sym);
+        self.zstore.intern_cons(sym, rest)
+    }
+}
@@ -11,6 +11,7 @@ use crate::{
     core::{
         big_num::field_elts_to_biguint,
         package::{Package, SymbolRef},
+        signature,
         stark_machine::new_machine,
         state::{builtin_sym, meta_sym, META_SYMBOLS},
         symbol::Symbol,
@@ -25,11 +26,14 @@ use super::{
     comm_data::CommData,
     debug::debug_mode,
     lurk_data::LurkData,
+    macros::to_package_digest,
     microchain::{read_data, write_data, CallableData, ChainState, Request, Response},
     paths::{commits_dir, proofs_dir},
     proofs::{get_verifier_version, CachedProof, ChainProof, OpaqueChainProof, ProtocolProof},
     rdg::rand_digest,
-    repl::Repl,
+    repl::{emitted_on, Repl},
+    session::Session,
+    watch::watch_file,
 };
 
 #[allow(clippy::type_complexity)]
@@ -46,6 +50,33 @@ pub(crate) struct MetaCmd<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> {
 
 pub(crate) type MetaCmdsMap<F, C1, C2> = FxHashMap<Symbol, MetaCmd<F, C1, C2>>;
 
+impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> MetaCmd<F, C1, C2> {
+    #[inline]
+    pub(crate) fn summary(&self) -> &'static str {
+        self.summary
+    }
+
+    /// Formats the full help text, as printed by `!(help <symbol>)`
+    pub(crate) fn fmt_help(&self) -> String {
+        let mut lines = vec![format!("{} - {}", self.name, self.summary)];
+        if !self.info.is_empty() {
+            lines.push("  Info:".into());
+        }
+        for e in self.info {
+            lines.push(format!("    {e}"));
+        }
+        lines.push(format!("  Format: {}", self.format));
+        if !self.example.is_empty() {
+            lines.push("  Example:".into());
+        }
+        for e in self.example {
+            lines.push(format!("    {e}"));
+        }
+        lines.push(format!("  Returns: {}", self.returns));
+        lines.join("\n")
+    }
+}
+
 impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> MetaCmd<F, C1, C2> {
     const ASSERT: Self = Self {
         name: "assert",
@@ -126,19 +157,37 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> MetaCmd<F, C1, C2> {
         info: &[
             "Asserts that the list of values in the first <expr> are emitted by",
             "the reduction of the second <expr>.",
+            "If a channel keyword is provided, only the values emitted on that",
+            "channel are considered.",
             "Exits the REPL if the assertion is not satisfied.",
         ],
-        format: "!(assert-emitted <expr> <expr>)",
-        example: &["!(assert-emitted '(1 2) (begin (emit 1) (emit 2)))"],
+        format: "!(assert-emitted <channel>? <expr> <expr>)",
+        example: &[
+            "!(assert-emitted '(1 2) (begin (emit 1) (emit 2)))",
+            "!(assert-emitted :log '(1) (begin (emit :log 1) (emit 2)))",
+        ],
         returns: "t",
         run: |repl, args, _dir| {
-            let [&expected_expr, &expr] = repl.take(args)?;
+            let (channel, expected_expr, expr) = match repl.take(args) {
+                Ok([&channel, &expected_expr, &expr]) => {
+                    if channel.tag != Tag::Key {
+                        bail!("Channel must be a keyword");
+                    }
+                    (Some(channel), expected_expr, expr)
+                }
+                Err(_) => {
+                    let [&expected_expr, &expr] = repl.take(args)?;
+                    (None, expected_expr, expr)
+                }
+            };
             let (expected, _) = repl.reduce_aux(&expected_expr)?;
             let (result, emitted) = repl.reduce_aux(&expr)?;
             if result.tag == Tag::Err {
                 bail!("Reduction error: {}", repl.fmt(&result));
             }
-            let emitted = repl.zstore.intern_list(emitted);
+            let emitted = repl
+                .zstore
+                .intern_list(emitted_on(&emitted, channel.as_ref()));
             if expected != emitted {
                 repl.memoize_dag(&expected);
                 // DAG for `emitted` has already been memoized
@@ -153,6 +202,37 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> MetaCmd<F, C1, C2> {
         },
     };
 
+    const EMITTED: Self = Self {
+        name: "emitted",
+        summary: "Returns the values emitted by the latest reduction",
+        info: &[
+            "Returns the list of values emitted by the latest reduction of a Lurk",
+            "expression. Reductions done by meta commands don't count.",
+            "If a channel keyword is provided, only the values emitted on that",
+            "channel with (emit <channel> <value>) are returned.",
+        ],
+        format: "!(emitted <channel>?)",
+        example: &[
+            "(begin (emit :log 1) (emit 2) (emit :log 3))",
+            "!(emitted)",
+            "!(emitted :log)",
+        ],
+        returns: "The list of emitted values",
+        run: |repl, args, _dir| {
+            let channel = if args != repl.zstore.nil() {
+                let [&channel] = repl.take(args)?;
+                if channel.tag != Tag::Key {
+                    bail!("Channel must be a keyword");
+                }
+                Some(channel)
+            } else {
+                None
+            };
+            let emitted = emitted_on(&repl.emitted, channel.as_ref());
+            Ok(repl.zstore.intern_list(emitted))
+        },
+    };
+
     const DEBUG: Self = Self {
         name: "debug",
         summary: "Enters the debug mode for a reduction",
@@ -213,6 +293,29 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> MetaCmd<F, C1, C2> {
         },
     };
 
+    const WATCH: Self = Self {
+        name: "watch",
+        summary: "Reloads a file every time it or any file it loads changes.",
+        info: &[
+            "The file is evaluated in a fresh REPL, with the native Lang, on every change.",
+            "Only the forms whose results changed since the previous run are printed.",
+            "Blocks until interrupted.",
+        ],
+        format: "!(watch <string>)",
+        example: &["!(watch \"my_file.lurk\")"],
+        returns: "t",
+        run: |repl, args, path| {
+            let [file_name_zptr] = repl.take(args)?;
+            Self::validate_path_type(file_name_zptr)?;
+            if repl.watch_log.is_some() {
+                bail!("Can't watch files while in watch mode");
+            }
+            let file_name = repl.zstore.fetch_string(file_name_zptr);
+            watch_file(&path.join(file_name))?;
+            Ok(*repl.zstore.t())
+        },
+    };
+
     const DEFQ: Self = Self {
         name: "defq",
         summary: "Extends env with a non-evaluated expression.",
@@ -283,6 +386,44 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> MetaCmd<F, C1, C2> {
         },
     };
 
+    const DEFMACRO: Self = Self {
+        name: "defmacro",
+        summary: "Defines a macro in the current package.",
+        info: &[
+            "Macro calls are expanded before reduction, with the parameters bound to",
+            "the unevaluated arguments. Bindings introduced by the expansion are",
+            "renamed so they can't capture the variables from the arguments.",
+            "While the macro runs, the symbols in its arguments are renamed too, so",
+            "literal markers should be compared against keywords.",
+        ],
+        format: "!(defmacro <symbol> <params> <body>)",
+        example: &[
+            "!(defmacro unless (c x) `(if ,c nil ,x))",
+            "(unless (= 1 2) :ok)",
+        ],
+        returns: "The macro symbol",
+        run: |repl, args, _dir| {
+            let [&sym, &params, &body] = repl.take(args)?;
+            if sym.tag != Tag::Sym {
+                bail!("Illegal macro name: {}", repl.fmt(&sym));
+            }
+            Self::validate_binding_symbol(repl, &sym)?;
+            let lambda = repl
+                .zstore
+                .intern_symbol(&builtin_sym("lambda"), &repl.lang_symbols);
+            let expr = repl.zstore.intern_list([lambda, params, body]);
+            let (fun, _) = repl.reduce_aux(&expr)?;
+            if fun.tag != Tag::Fun {
+                bail!("Reduction resulted in {}", repl.fmt(&fun));
+            }
+            repl.memoize_dag(&fun);
+            repl.state
+                .borrow_mut()
+                .add_macro(to_package_digest(&sym), to_package_digest(&fun));
+            Ok(sym)
+        },
+    };
+
     fn validate_binding_symbol(repl: &Repl<F, C1, C2>, zptr: &ZPtr<F>) -> Result<()> {
         match zptr.tag {
             Tag::Builtin | Tag::Coroutine => Ok(()),
@@ -435,6 +576,59 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> MetaCmd<F, C1, C2> {
         },
     };
 
+    const KEYGEN: Self = Self {
+        name: "keygen",
+        summary: "Creates a random key pair for signatures",
+        info: &[
+            "The secret key is a big num and the public key is a pair of big",
+            "nums, the coordinates of a point. The randomness comes from fresh",
+            "system entropy everytime.",
+        ],
+        format: "!(keygen)",
+        example: &["!(defq keys !(keygen))", "!(sign (car keys) 42)"],
+        returns: "The pair of the secret key and the public key",
+        run: |repl, args, _dir| {
+            if args != repl.zstore.nil() {
+                bail!("No arguments are accepted");
+            }
+            let sk = signature::secret_key(&rand_digest());
+            let pk = signature::public_key(&sk);
+            let pk = signature::intern_point(&mut repl.zstore, &pk);
+            let sk = repl.zstore.intern_big_num(sk);
+            Ok(repl.zstore.intern_cons(sk, pk))
+        },
+    };
+
+    const SIGN: Self = Self {
+        name: "sign",
+        summary: "Signs a value with a secret key.",
+        info: &[
+            "The secret key is the reduction of <sk_expr>, which must be a",
+            "big num, and the signed value is the reduction of <expr>. The",
+            "signature can be checked with the `verify-signature` builtin.",
+        ],
+        format: "!(sign <sk_expr> <expr>)",
+        example: &[
+            "!(defq keys !(keygen))",
+            "!(defq sig !(sign (car keys) 42))",
+            "(verify-signature (cdr keys) 42 sig)",
+        ],
+        returns: "The signature",
+        run: |repl, args, _dir| {
+            let [&sk_expr, &expr] = repl.take(args)?;
+            let (sk, _) = repl.reduce_aux(&sk_expr)?;
+            if sk.tag != Tag::BigNum {
+                bail!("Secret key must reduce to a bignum");
+            }
+            let (msg, _) = repl.reduce_aux(&expr)?;
+            if msg.tag == Tag::Err {
+                bail!("Reduction error: {}", repl.fmt(&msg));
+            }
+            let sig = signature::sign(&mut repl.zstore, &sk.digest, &msg);
+            Ok(signature::intern_signature(&mut repl.zstore, &sig))
+        },
+    };
+
     const COMMIT: Self = Self {
         name: "commit",
         summary: "Persists a commitment.",
@@ -754,6 +948,99 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> MetaCmd<F, C1, C2> {
         },
     };
 
+    const SAVE_SESSION: Self = Self {
+        name: "save-session",
+        summary: "Persists the current REPL session to the file system",
+        info: &[
+            "The session is made of the env, the packages (including the current",
+            "one) and all the Lurk data reachable from the env.",
+        ],
+        format: "!(save-session <string>)",
+        example: &["!(def a 1)", "!(save-session \"my_session\")"],
+        returns: "t",
+        run: |repl, args, _dir| {
+            let [&path] = repl.take(args)?;
+            Self::validate_path_type(&path)?;
+            let path_str = repl.zstore.fetch_string(&path);
+            Session::save(repl, Utf8Path::new(&path_str))?;
+            println!("Session persisted on file `{path_str}`");
+            Ok(*repl.zstore.t())
+        },
+    };
+
+    const RESTORE_SESSION: Self = Self {
+        name: "restore-session",
+        summary: "Restores a REPL session from the file system",
+        info: &["The current env and packages are replaced by the ones from the session."],
+        format: "!(restore-session <string>)",
+        example: &[
+            "!(def a 1)",
+            "!(save-session \"my_session\")",
+            "!(clear)",
+            "!(restore-session \"my_session\")",
+            "!(assert-eq a 1)",
+        ],
+        returns: "t",
+        run: |repl, args, _dir| {
+            let [&path] = repl.take(args)?;
+            Self::validate_path_type(&path)?;
+            let path_str = repl.zstore.fetch_string(&path);
+            Session::load(repl, Utf8Path::new(&path_str))?;
+            Ok(*repl.zstore.t())
+        },
+    };
+
+    const TIME: Self = Self {
+        name: "time",
+        summary: "Evaluates an expression and prints resource statistics",
+        info: &[
+            "The statistics include the wall time of the evaluation and, for each",
+            "chip, the number of queries and lookups as well as the estimated",
+            "trace rows and columns. The evaluation is cached for proving.",
+        ],
+        format: "!(time <expr>)",
+        example: &["!(time (+ 1 1))", "!(prove)"],
+        returns: "The result of the evaluation",
+        run: |repl, args, _dir| {
+            let [&expr] = repl.take(args)?;
+            let stats = std::mem::replace(&mut repl.stats, true);
+            let result = repl.handle_non_meta(&expr);
+            repl.stats = stats;
+            result
+        },
+    };
+
+    const SET_FUEL: Self = Self {
+        name: "set-fuel",
+        summary: "Limits the number of function queries of each evaluation",
+        info: &[
+            "Evaluations that would register more function queries than the",
+            "provided u64 are aborted and result in an out of fuel error, which",
+            "can't be proved. Memoized calls don't consume fuel. Use `nil` to",
+            "remove the limit.",
+        ],
+        format: "!(set-fuel <u64 or nil>)",
+        example: &[
+            "!(set-fuel 1000)",
+            "(letrec ((loop (lambda (n) (loop (+ n 1))))) (loop 0))",
+            "!(set-fuel nil)",
+        ],
+        returns: "t",
+        run: |repl, args, _dir| {
+            let [&fuel] = repl.take(args)?;
+            repl.fuel = match fuel.tag {
+                Tag::U64 => {
+                    let bytes = fuel.digest.map(|f| f.as_canonical_u32() as u8);
+                    let fuel = u64::from_le_bytes(bytes);
+                    Some(usize::try_from(fuel)?)
+                }
+                _ if fuel == *repl.zstore.nil() => None,
+                _ => bail!("Fuel must be a u64 or nil"),
+            };
+            Ok(*repl.zstore.t())
+        },
+    };
+
     const DEFPROTOCOL: Self = Self {
         name: "defprotocol",
         summary: "Defines a protocol",
@@ -775,7 +1062,7 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> MetaCmd<F, C1, C2> {
             "!(defprotocol my-protocol (hash pair)",
             "  (cons",
             "    (if (= (+ (car pair) (cdr pair)) 30)",
-            "      (cons (cons (cons 'open (cons hash nil)) (empty-env)) pair)",
+            "      `(((open ,hash) . ,(empty-env)) . ,pair)",
             "      nil)",
             "    (lambda () (> (car pair) 10)))",
             "  :description \"hash opens to a pair (a, b) s.t. a+b=30 and a>10\")",
@@ -842,21 +1129,7 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> MetaCmd<F, C1, C2> {
                 let Some(meta_cmd) = repl.meta_cmds.get(&meta_sym(name)) else {
                     bail!("Unknown meta command");
                 };
-                println!("{} - {}", meta_cmd.name, meta_cmd.summary);
-                if !meta_cmd.info.is_empty() {
-                    println!("  Info:");
-                }
-                for e in meta_cmd.info {
-                    println!("    {e}");
-                }
-                println!("  Format: {}", meta_cmd.format);
-                if !meta_cmd.example.is_empty() {
-                    println!("  Example:");
-                }
-                for e in meta_cmd.example {
-                    println!("    {e}");
-                }
-                println!("  Returns: {}", meta_cmd.returns);
+                println!("{}", meta_cmd.fmt_help());
             } else {
                 println!("Available commands:");
                 for (_, i) in repl.meta_cmds.iter().sorted_by_key(|x| x.0) {
@@ -1503,17 +1776,22 @@ pub(crate) fn meta_cmds<C1: Chipset<F>, C2: Chipset<F>>() -> MetaCmdsMap<F, C1,
         MetaCmd::ASSERT_EQ,
         MetaCmd::ASSERT_ERROR,
         MetaCmd::ASSERT_EMITTED,
+        MetaCmd::EMITTED,
         MetaCmd::DEBUG,
         MetaCmd::LOAD,
+        MetaCmd::WATCH,
         MetaCmd::DEFQ,
         MetaCmd::DEF,
         MetaCmd::DEFREC,
+        MetaCmd::DEFMACRO,
         MetaCmd::UPDATE,
         MetaCmd::CLEAR,
         MetaCmd::SET_ENV,
         MetaCmd::ERASE_FROM_ENV,
         MetaCmd::HIDE,
         MetaCmd::RAND,
+        MetaCmd::KEYGEN,
+        MetaCmd::SIGN,
         MetaCmd::COMMIT,
         MetaCmd::OPEN,
         MetaCmd::CALL,
@@ -1524,6 +1802,10 @@ pub(crate) fn meta_cmds<C1: Chipset<F>, C2: Chipset<F>>() -> MetaCmdsMap<F, C1,
         MetaCmd::IN_PACKAGE,
         MetaCmd::DUMP_EXPR,
         MetaCmd::LOAD_EXPR,
+        MetaCmd::SAVE_SESSION,
+        MetaCmd::RESTORE_SESSION,
+        MetaCmd::TIME,
+        MetaCmd::SET_FUEL,
         MetaCmd::PROVE,
         MetaCmd::VERIFY,
         MetaCmd::INSPECT,
@@ -1550,7 +1832,7 @@ mod test {
     use camino::{Utf8Path, Utf8PathBuf};
     use once_cell::sync::OnceCell;
 
-    use crate::core::state::user_sym;
+    use crate::core::{error::EvalErr, state::user_sym};
 
     use super::{MetaCmd, Repl};
 
@@ -1574,4 +1856,42 @@ mod test {
             foo
         );
     }
+
+    #[test]
+    fn test_set_fuel() {
+        let mut repl = Repl::new_native(false);
+        let set_fuel = |repl: &mut Repl<_, _, _>, fuel| {
+            let args = repl.zstore.intern_list([fuel]);
+            (MetaCmd::SET_FUEL.run)(repl, &args, dummy_path())
+        };
+        let fuel = repl.zstore.intern_u64(1000);
+        assert!(set_fuel(&mut repl, fuel).is_ok());
+        assert_eq!(repl.fuel, Some(1000));
+
+        let expr = repl
+            .zstore
+            .read(
+                "(letrec ((loop (lambda (n) (loop (+ n 1))))) (loop 0))",
+                &repl.lang_symbols,
+            )
+            .unwrap();
+        let nil = *repl.zstore.nil();
+        let out_of_fuel = repl
+            .zstore
+            .intern_error_with_payload(EvalErr::OutOfFuel, expr, nil);
+        assert_eq!(repl.handle_non_meta(&expr).unwrap(), out_of_fuel);
+        // the execution was aborted, so there's nothing to prove
+        assert!(repl.queries.public_values.is_none());
+
+        let expr = repl.zstore.read("(+ 1 2)", &repl.lang_symbols).unwrap();
+        let three = repl.zstore.intern_u64(3);
+        assert_eq!(repl.handle_non_meta(&expr).unwrap(), three);
+        assert!(repl.queries.public_values.is_some());
+
+        assert!(set_fuel(&mut repl, nil).is_ok());
+        assert_eq!(repl.fuel, None);
+
+        let t = *repl.zstore.t();
+        assert!(set_fuel(&mut repl, t).is_err());
+    }
 }
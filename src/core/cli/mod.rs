@@ -1,23 +1,33 @@
 mod comm_data;
 mod config;
 mod debug;
+mod fmt;
+mod lsp;
 mod lurk_data;
+mod macros;
 mod meta;
 mod microchain;
 mod paths;
 mod proofs;
 mod rdg;
 pub mod repl;
+mod session;
+mod stats;
 #[cfg(test)]
 mod tests;
+mod watch;
 mod zdag;
 
 use anyhow::{bail, Result};
 use camino::Utf8PathBuf;
 use clap::{Args, Parser, Subcommand};
 use config::{set_config, Config};
+use fmt::FmtArgs;
+use lsp::run_lsp;
 use microchain::MicrochainArgs;
 use repl::Repl;
+use session::Session;
+use watch::watch_file;
 
 #[derive(Parser, Debug)]
 #[clap(version)]
@@ -34,6 +44,10 @@ enum Command {
     Load(LoadArgs),
     /// Starts the microchain server
     Microchain(MicrochainArgs),
+    /// Starts a language server for Lurk files, communicating over stdio
+    Lsp,
+    /// Formats Lurk source files in place
+    Fmt(FmtArgs),
 }
 
 #[derive(Args, Debug)]
@@ -42,8 +56,20 @@ struct ReplArgs {
     #[clap(long, value_parser)]
     preload: Option<Utf8PathBuf>,
 
+    /// Optional session file to be restored, if it exists, and saved on exit
+    #[clap(long, value_parser)]
+    session: Option<Utf8PathBuf>,
+
     #[arg(long)]
     lurkscript: bool,
+
+    /// Flag to print resource statistics after each evaluation
+    #[arg(long)]
+    stats: bool,
+
+    /// Optional limit on the number of function queries of each evaluation
+    #[arg(long)]
+    fuel: Option<usize>,
 }
 
 #[derive(Parser, Debug)]
@@ -51,8 +77,17 @@ struct ReplCli {
     #[clap(long, value_parser)]
     preload: Option<Utf8PathBuf>,
 
+    #[clap(long, value_parser)]
+    session: Option<Utf8PathBuf>,
+
     #[arg(long)]
     lurkscript: bool,
+
+    #[arg(long)]
+    stats: bool,
+
+    #[arg(long)]
+    fuel: Option<usize>,
 }
 
 #[derive(Args, Debug)]
@@ -68,6 +103,18 @@ struct LoadArgs {
     /// Flag to load the file in demo mode
     #[arg(long)]
     demo: bool,
+
+    /// Flag to reload the file whenever it or the files it loads change
+    #[arg(long, conflicts_with_all = ["prove", "demo"])]
+    watch: bool,
+
+    /// Flag to print resource statistics after each evaluation
+    #[arg(long)]
+    stats: bool,
+
+    /// Optional limit on the number of function queries of each evaluation
+    #[arg(long)]
+    fuel: Option<usize>,
 }
 
 #[derive(Parser, Debug)]
@@ -80,10 +127,19 @@ struct LoadCli {
 
     #[arg(long)]
     demo: bool,
+
+    #[arg(long, conflicts_with_all = ["prove", "demo"])]
+    watch: bool,
+
+    #[arg(long)]
+    stats: bool,
+
+    #[arg(long)]
+    fuel: Option<usize>,
 }
 
 fn parse_filename(file: &str) -> Result<Utf8PathBuf> {
-    if ["help", "microchain"].contains(&file) {
+    if ["help", "microchain", "lsp", "fmt"].contains(&file) {
         bail!("Invalid file name");
     }
     Ok(file.into())
@@ -93,11 +149,17 @@ impl ReplArgs {
     fn into_cli(self) -> ReplCli {
         let Self {
             preload,
+            session,
             lurkscript,
+            stats,
+            fuel,
         } = self;
         ReplCli {
             preload,
+            session,
             lurkscript,
+            stats,
+            fuel,
         }
     }
 }
@@ -108,11 +170,17 @@ impl LoadArgs {
             lurk_file,
             prove,
             demo,
+            watch,
+            stats,
+            fuel,
         } = self;
         LoadCli {
             lurk_file,
             prove,
             demo,
+            watch,
+            stats,
+            fuel,
         }
     }
 }
@@ -123,6 +191,8 @@ impl Cli {
             Command::Repl(repl_args) => repl_args.into_cli().run(),
             Command::Load(load_args) => load_args.into_cli().run(),
             Command::Microchain(microchain_args) => microchain_args.run(),
+            Command::Lsp => run_lsp(),
+            Command::Fmt(fmt_args) => fmt_args.run(),
         }
     }
 }
@@ -130,16 +200,33 @@ impl Cli {
 impl ReplCli {
     fn run(&self) -> Result<()> {
         let mut repl = Repl::new_native(self.lurkscript);
+        repl.stats = self.stats;
+        repl.fuel = self.fuel;
+        if let Some(session) = &self.session {
+            if session.exists() {
+                Session::load(&mut repl, session)?;
+            }
+        }
         if let Some(lurk_file) = &self.preload {
             repl.load_file(lurk_file, false)?;
         }
-        repl.run()
+        repl.run()?;
+        if let Some(session) = &self.session {
+            Session::save(&mut repl, session)?;
+            println!("Session persisted on file `{session}`");
+        }
+        Ok(())
     }
 }
 
 impl LoadCli {
     fn run(&self) -> Result<()> {
+        if self.watch {
+            return watch_file(&self.lurk_file);
+        }
         let mut repl = Repl::new_native(false);
+        repl.stats = self.stats;
+        repl.fuel = self.fuel;
         repl.load_file(&self.lurk_file, self.demo)?;
         if self.prove {
             repl.prove_last_reduction()?;
@@ -14,7 +14,9 @@ use rustyline::{
 };
 use sp1_stark::{CpuProver, StarkGenericConfig};
 use sp1_stark::{MachineProver, SP1CoreOpts};
-use std::{fmt::Debug, fs, io, io::Write, marker::PhantomData, process::Command, sync::Arc};
+use std::{
+    fmt::Debug, fs, io, io::Write, marker::PhantomData, process::Command, sync::Arc, time::Instant,
+};
 
 use crate::{
     core::{
@@ -25,7 +27,10 @@ use crate::{
             meta::{meta_cmds, MetaCmdsMap},
             paths::{current_dir, proofs_dir, repl_history},
             proofs::{CachedProof, CryptoProof},
+            stats::EvalStats,
+            watch::WatchLog,
         },
+        error::EvalErr,
         eval_direct::build_lurk_toplevel,
         lang::Lang,
         parser::{
@@ -35,15 +40,16 @@ use crate::{
         stark_machine::{new_machine, INPUT_SIZE},
         state::{State, StateRcCell},
         symbol::Symbol,
-        syntax::Syntax,
+        syntax::{i64_from_sign_magnitude, Syntax},
         tag::Tag,
         zstore::{ZPtr, ZStore, DIGEST_SIZE},
     },
     lair::{
         chipset::{Chipset, NoChip},
-        execute::{DebugEntry, DebugEntryKind, QueryRecord, QueryResult, Shard},
+        execute::{DebugEntry, DebugEntryKind, OutOfFuel, QueryRecord, QueryResult, Shard},
         lair_chip::LairMachineProgram,
         toplevel::Toplevel,
+        List,
     },
 };
 
@@ -145,6 +151,26 @@ impl FuncIndices {
     }
 }
 
+/// A value emitted during a reduction, along with its channel keyword if it was
+/// emitted with `(emit :channel value)`
+#[derive(Clone, Copy)]
+pub(crate) struct Emitted<F> {
+    pub(crate) channel: Option<ZPtr<F>>,
+    pub(crate) value: ZPtr<F>,
+}
+
+/// The values emitted on `channel`, or all of them if `channel` is `None`
+pub(crate) fn emitted_on<F: Copy + PartialEq>(
+    emitted: &[Emitted<F>],
+    channel: Option<&ZPtr<F>>,
+) -> Vec<ZPtr<F>> {
+    emitted
+        .iter()
+        .filter(|e| channel.is_none() || e.channel.as_ref() == channel)
+        .map(|e| e.value)
+        .collect()
+}
+
 pub(crate) struct Repl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> {
     pub(crate) zstore: ZStore<F, C1>,
     pub(crate) queries: QueryRecord<F>,
@@ -155,6 +181,17 @@ pub(crate) struct Repl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> {
     pub(crate) meta_cmds: MetaCmdsMap<F, C1, C2>,
     pub(crate) lang_symbols: FxHashSet<Symbol>,
     pub(crate) lurkscript: bool,
+    /// When set, loaded files and form results are recorded instead of printed
+    pub(crate) watch_log: Option<WatchLog>,
+    /// Whether resource statistics are printed after each evaluation
+    pub(crate) stats: bool,
+    /// Counter for the fresh symbols created during macro expansion
+    pub(crate) gensym_counter: usize,
+    /// The maximum number of function queries a single reduction may register
+    pub(crate) fuel: Option<usize>,
+    /// The values emitted by the latest top-level reduction. Reductions done by meta
+    /// commands return their emitted values instead of updating this
+    pub(crate) emitted: Vec<Emitted<F>>,
 }
 
 impl<C2: Chipset<BabyBear>> Repl<BabyBear, LurkChip, C2> {
@@ -172,6 +209,11 @@ impl<C2: Chipset<BabyBear>> Repl<BabyBear, LurkChip, C2> {
             meta_cmds: meta_cmds(),
             lang_symbols,
             lurkscript,
+            watch_log: None,
+            stats: false,
+            gensym_counter: 0,
+            fuel: None,
+            emitted: Vec::new(),
         }
     }
 }
@@ -295,6 +337,7 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
 
     fn prepare_queries(&mut self) {
         self.queries.clean();
+        self.queries.set_fuel(self.fuel);
         let hashes3 = std::mem::take(&mut self.zstore.hashes3_diff);
         let hashes4 = std::mem::take(&mut self.zstore.hashes4_diff);
         let hashes5 = std::mem::take(&mut self.zstore.hashes5_diff);
@@ -354,6 +397,7 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
             bytes: Default::default(),
             emitted: Default::default(),
             debug_data: Default::default(),
+            fuel: None,
         }
     }
 
@@ -377,6 +421,40 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
         ZPtr::from_flat_digest(Tag::from_field(&egress_output[0]), &egress_output[1..])
     }
 
+    /// Egresses the raw data pushed by `emit`, which is the tag and pointer of the
+    /// value, preceded by the tag and pointer of the channel if there's one
+    fn egress_emitted(
+        &self,
+        emitted_raw: &[List<F>],
+        queries_tmp: &mut QueryRecord<F>,
+    ) -> Vec<Emitted<F>> {
+        let mut emitted = Vec::with_capacity(emitted_raw.len());
+        for raw in emitted_raw {
+            let (channel, value) = raw.split_at(raw.len() - 2);
+            let channel = if channel.is_empty() {
+                None
+            } else {
+                Some(self.manual_egression(channel, queries_tmp))
+            };
+            let value = self.manual_egression(value, queries_tmp);
+            emitted.push(Emitted { channel, value });
+        }
+        emitted
+    }
+
+    /// Memoizes and prints emitted values, prefixed by their channels
+    fn print_emitted(&mut self, emitted: &[Emitted<F>]) {
+        for Emitted { channel, value } in emitted {
+            self.memoize_dag(value);
+            if let Some(channel) = channel {
+                self.memoize_dag(channel);
+                println!("{} {}", self.fmt(channel), self.fmt(value));
+            } else {
+                println!("{}", self.fmt(value));
+            }
+        }
+    }
+
     pub(crate) fn format_debug_data(&mut self) -> FormattedDebugData<'_> {
         let mut dbg_depth_map: FxHashMap<usize, Vec<usize>> = FxHashMap::default();
         let mut processed_debug_entries = Vec::with_capacity(self.queries.debug_data.entries.len());
@@ -466,7 +544,7 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
         &mut self,
         expr: &ZPtr<F>,
         env: &ZPtr<F>,
-    ) -> Result<(ZPtr<F>, Vec<ZPtr<F>>)> {
+    ) -> Result<(ZPtr<F>, Vec<Emitted<F>>)> {
         self.prepare_queries();
         let mut queries_tmp = self.queries.clone();
         let result_data = self.toplevel.execute_by_index(
@@ -475,23 +553,19 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
             &mut queries_tmp,
             None,
         );
-        let emitted_raw_vec = std::mem::take(&mut queries_tmp.emitted);
-        let mut emitted = Vec::with_capacity(emitted_raw_vec.len());
-        for emitted_raw in &emitted_raw_vec {
-            emitted.push(self.manual_egression(emitted_raw, &mut queries_tmp));
-        }
+        let emitted_raw = std::mem::take(&mut queries_tmp.emitted);
+        let emitted = self.egress_emitted(&emitted_raw, &mut queries_tmp);
         self.queries.inv_func_queries = queries_tmp.inv_func_queries;
-        for zptr in &emitted {
-            self.memoize_dag(zptr);
-            println!("{}", self.fmt(zptr));
-        }
-        result_data.map(|data| (ZPtr::from_flat_data(&data), emitted))
+        self.print_emitted(&emitted);
+        let result = self.reduction_result(expr, result_data)?;
+        Ok((result, emitted))
     }
 
     #[inline]
-    pub(crate) fn reduce_aux(&mut self, expr: &ZPtr<F>) -> Result<(ZPtr<F>, Vec<ZPtr<F>>)> {
+    pub(crate) fn reduce_aux(&mut self, expr: &ZPtr<F>) -> Result<(ZPtr<F>, Vec<Emitted<F>>)> {
+        let expr = self.macroexpand(expr)?;
         let env = self.env;
-        self.reduce_aux_with_env(expr, &env)
+        self.reduce_aux_with_env(&expr, &env)
     }
 
     pub(crate) fn reduce_with_env(&mut self, expr: &ZPtr<F>, env: &ZPtr<F>) -> Result<ZPtr<F>> {
@@ -502,36 +576,69 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
             &mut self.queries,
             Some(self.func_indices.eval),
         );
+        if result_data.is_err() {
+            // an aborted execution leaves nothing to be proved
+            self.queries.public_values = None;
+        }
+        self.emitted.clear();
         if !self.queries.emitted.is_empty() {
             let mut queries_tmp = self.tmp_queries_for_egression();
-            let mut emitted = Vec::with_capacity(self.queries.emitted.len());
-            for emitted_raw in &self.queries.emitted {
-                emitted.push(self.manual_egression(emitted_raw, &mut queries_tmp));
-            }
+            let emitted = self.egress_emitted(&self.queries.emitted, &mut queries_tmp);
             self.retrieve_inv_query_data_from_tmp_queries(queries_tmp);
-            for zptr in &emitted {
-                self.memoize_dag(zptr);
-                println!("{}", self.fmt(zptr));
+            self.print_emitted(&emitted);
+            self.emitted = emitted;
+        }
+        self.reduction_result(expr, result_data)
+    }
+
+    /// Reads the result of executing `lurk_main` on `expr`. Running out of fuel
+    /// aborts the execution, in which case the result is an `OutOfFuel` error
+    /// carrying `expr`
+    fn reduction_result(
+        &mut self,
+        expr: &ZPtr<F>,
+        result_data: Result<List<F>>,
+    ) -> Result<ZPtr<F>> {
+        match result_data {
+            Ok(data) => Ok(ZPtr::from_flat_data(&data)),
+            Err(err) if err.is::<OutOfFuel>() => {
+                let nil = *self.zstore.nil();
+                Ok(self
+                    .zstore
+                    .intern_error_with_payload(EvalErr::OutOfFuel, *expr, nil))
             }
+            Err(err) => Err(err),
         }
-        result_data.map(|data| ZPtr::from_flat_data(&data))
     }
 
     /// Evaluates an expression with a custom env and prints the number of
-    /// iterations and the result. The computation is cached for proving.
+    /// iterations and the result, followed by resource statistics if `stats`
+    /// is set. The computation is cached for proving.
     pub(crate) fn handle_non_meta_with_env(
         &mut self,
         expr: &ZPtr<F>,
         env: &ZPtr<F>,
     ) -> Result<ZPtr<F>> {
+        let start = Instant::now();
         let result = self.reduce_with_env(expr, env)?;
+        let elapsed = start.elapsed();
         self.memoize_dag(&result);
         let iterations = self.queries.func_queries[self.func_indices.eval].len();
-        println!(
-            "[{}] => {}",
-            pretty_iterations_display(iterations),
+        let result_str = if result.tag == Tag::Err {
+            format!(
+                "Error: {}",
+                self.zstore.fmt_error_with_state(&self.state, &result)
+            )
+        } else {
             self.fmt(&result)
+        };
+        println!(
+            "[{}] => {result_str}",
+            pretty_iterations_display(iterations)
         );
+        if self.stats {
+            println!("{}", EvalStats::new(&self.toplevel, &self.queries, elapsed));
+        }
         Ok(result)
     }
 
@@ -575,11 +682,15 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
                 } else {
                     bail!("Invalid meta command: {sym}")
                 }
-            },
+            }
             Syntax::Num(_, f) => self.zstore.intern_num(*f),
             Syntax::Char(_, c) => self.zstore.intern_char(*c),
             Syntax::U64(_, u) => self.zstore.intern_u64(*u),
-            Syntax::I64(..) => bail!("Transient error: Signed integers are not yet supported. Using `(- 0 x)` instead of `-x` might work as a temporary workaround."),
+            Syntax::I64(_, neg, x) => match i64_from_sign_magnitude(*neg, *x) {
+                Some(i) => self.zstore.intern_i64(i),
+                None => bail!("Signed integer out of range: {syn}"),
+            },
+            Syntax::Decimal(_, x) => self.zstore.intern_decimal(*x),
             Syntax::BigNum(_, c) => self.zstore.intern_big_num(*c),
             Syntax::Comm(_, c) => self.zstore.intern_comm(*c),
             Syntax::String(_, s) => self.zstore.intern_string(s),
@@ -597,10 +708,24 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
                 let x = self.intern_syntax(x, file_dir)?;
                 self.zstore.intern_list([*self.zstore.quote(), x])
             }
+            Syntax::Quasiquote(_, x) => {
+                let x = self.intern_syntax(x, file_dir)?;
+                self.zstore.intern_reader_form("quasiquote", x)
+            }
+            Syntax::Unquote(_, x) => {
+                let x = self.intern_syntax(x, file_dir)?;
+                self.zstore.intern_reader_form("unquote", x)
+            }
+            Syntax::UnquoteSplicing(_, x) => {
+                let x = self.intern_syntax(x, file_dir)?;
+                self.zstore.intern_reader_form("unquote-splicing", x)
+            }
             Syntax::Env(_, env) => {
                 let zptrs = self.intern_syntax_env(env, file_dir)?;
                 let empty_env = self.zstore.intern_empty_env();
-                zptrs.into_iter().rev().fold(empty_env, |acc, (sym, val)| self.zstore.intern_env(sym, val, acc))
+                zptrs.into_iter().rev().fold(empty_env, |acc, (sym, val)| {
+                    self.zstore.intern_env(sym, val, acc)
+                })
             }
         };
         Ok(zptr)
@@ -628,6 +753,8 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
             .expect("Parsed syntax should have its Pos set");
         let meta = matches!(syn, Syntax::Meta(..));
         let zptr = self.intern_syntax(&syn, file_dir)?;
+        let zptr = self.zstore.expand_quasiquotes(&zptr)?;
+        let zptr = if meta { zptr } else { self.macroexpand(&zptr)? };
         Ok(Some((offset, rest, zptr, meta)))
     }
 
@@ -656,7 +783,27 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
             // ENTER already prints a new line so we can remove it from the start of incoming input
             new_input = new_input.trim_start_matches('\n').into();
         }
-        if meta {
+        if self.watch_log.is_some() {
+            let result = if meta {
+                zptr
+            } else {
+                let env = self.env;
+                let result = self.reduce_with_env(&zptr, &env)?;
+                self.memoize_dag(&result);
+                result
+            };
+            let from = syntax_start - input.location_offset();
+            let upto = new_input.location_offset() - input.location_offset();
+            let form = input[from..upto].to_string();
+            let formatted = self.fmt(&result);
+            if let Some(watch_log) = &mut self.watch_log {
+                watch_log.record_form(form, formatted);
+            }
+            if !meta && result.tag == Tag::Err {
+                // error out when loading a file
+                bail!("Reduction error: {}", self.fmt(&result));
+            }
+        } else if meta {
             println!("{}", self.fmt(&zptr));
         } else {
             let result = self.handle_non_meta(&zptr)?;
@@ -673,6 +820,11 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
             bail!("Can't get the parent of {file_path}");
         };
 
+        if let Some(watch_log) = &mut self.watch_log {
+            // register the file before reading it so it's watched even if it can't be read
+            watch_log.record_file(file_path);
+        }
+
         let input = if file_path.extension().map_or(false, |ext| ext == "ls") {
             // Compile LurkScript (.ls) to Lurk using `lurkscript -c <filename>`
             let output = Command::new("lurkscript")
@@ -693,10 +845,13 @@ impl<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>> Repl<F, C1, C2> {
             fs::read_to_string(file_path)?
         };
 
-        if demo {
-            println!("Loading {file_path} in demo mode");
-        } else {
-            println!("Loading {file_path}");
+        // loading is silent in watch mode
+        if self.watch_log.is_none() {
+            if demo {
+                println!("Loading {file_path} in demo mode");
+            } else {
+                println!("Loading {file_path}");
+            }
         }
 
         let mut input = Span::new(&input);
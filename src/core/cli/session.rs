@@ -0,0 +1,80 @@
+use anyhow::Result;
+use camino::Utf8Path;
+use p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{
+        state::{State, StateData},
+        tag::Tag,
+        zstore::ZPtr,
+    },
+    lair::chipset::Chipset,
+};
+
+use super::{macros::from_package_digest, repl::Repl, zdag::ZDag};
+
+/// The persistable state of a REPL session: its env, its packages (along with
+/// their macros) and the Lurk data reachable from the env and the macros
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Session<F: std::hash::Hash + Eq> {
+    env: ZPtr<F>,
+    state: StateData,
+    zdag: ZDag<F>,
+}
+
+impl<F: PrimeField32> Session<F> {
+    pub(crate) fn new<C1: Chipset<F>, C2: Chipset<F>>(repl: &mut Repl<F, C1, C2>) -> Self {
+        repl.memoize_env_dag();
+        let state = repl.state.borrow();
+        let zptrs = state
+            .macros()
+            .flat_map(|(sym, fun)| {
+                [
+                    from_package_digest(Tag::Sym, sym),
+                    from_package_digest(Tag::Fun, fun),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let mut zdag = ZDag::default();
+        zdag.populate_with_many(std::iter::once(&repl.env).chain(&zptrs), &repl.zstore);
+        Self {
+            env: repl.env,
+            state: state.to_data(),
+            zdag,
+        }
+    }
+
+    /// Replaces the env and the state of a `Repl` by the ones from the session
+    pub(crate) fn restore<C1: Chipset<F>, C2: Chipset<F>>(
+        self,
+        repl: &mut Repl<F, C1, C2>,
+    ) -> Result<()> {
+        let Self { env, state, zdag } = self;
+        let state = State::from_data(state)?;
+        zdag.populate_zstore(&mut repl.zstore);
+        // the state is mutated in place because it's shared with the REPL's
+        // input validator
+        *repl.state.borrow_mut() = state;
+        repl.env = env;
+        Ok(())
+    }
+
+    pub(crate) fn save<C1: Chipset<F>, C2: Chipset<F>>(
+        repl: &mut Repl<F, C1, C2>,
+        path: &Utf8Path,
+    ) -> Result<()> {
+        let session_bytes = bincode::serialize(&Self::new(repl))?;
+        std::fs::write(path, session_bytes)?;
+        Ok(())
+    }
+
+    pub(crate) fn load<C1: Chipset<F>, C2: Chipset<F>>(
+        repl: &mut Repl<F, C1, C2>,
+        path: &Utf8Path,
+    ) -> Result<()> {
+        let session_bytes = std::fs::read(path)?;
+        let session: Self = bincode::deserialize(&session_bytes)?;
+        session.restore(repl)
+    }
+}
@@ -0,0 +1,178 @@
+use p3_air::BaseAir;
+use p3_field::PrimeField32;
+use std::{fmt, sync::Arc, time::Duration};
+
+use crate::{
+    gadgets::bytes::trace::BytesChip,
+    lair::{
+        chipset::Chipset,
+        execute::{QueryRecord, MEM_TABLE_SIZES},
+        func_chip::FuncChip,
+        memory::MemChip,
+        toplevel::Toplevel,
+    },
+};
+
+const BYTE_OPERATIONS: [&str; 6] = ["range_u8", "range_u16", "less_than", "and", "xor", "or"];
+const HASH_FUNCS: [&str; 3] = ["hash3", "hash4", "hash5"];
+
+/// The trace dimensions estimated for a chip
+struct ChipStats {
+    name: String,
+    /// Number of distinct queries, which become non-dummy rows
+    queries: usize,
+    /// Number of times the queries were looked up
+    lookups: usize,
+    rows: usize,
+    width: usize,
+}
+
+impl ChipStats {
+    fn new(name: String, queries: usize, lookups: usize, width: usize) -> Self {
+        // traces are padded to a power of two with at least 16 rows
+        let rows = queries.next_power_of_two().max(16);
+        Self {
+            name,
+            queries,
+            lookups,
+            rows,
+            width,
+        }
+    }
+
+    #[inline]
+    fn area(&self) -> usize {
+        self.rows * self.width
+    }
+}
+
+/// Resource statistics of an evaluation, meant to help predicting the cost of
+/// proving it
+pub(crate) struct EvalStats {
+    elapsed: Duration,
+    funcs: Vec<ChipStats>,
+    mems: Vec<ChipStats>,
+    bytes: ChipStats,
+    byte_lookups: [usize; BYTE_OPERATIONS.len()],
+    hash_calls: Vec<(&'static str, usize)>,
+}
+
+impl EvalStats {
+    pub(crate) fn new<F: PrimeField32, C1: Chipset<F>, C2: Chipset<F>>(
+        toplevel: &Arc<Toplevel<F, C1, C2>>,
+        queries: &QueryRecord<F>,
+        elapsed: Duration,
+    ) -> Self {
+        let mut funcs = vec![];
+        let mut hash_calls = vec![];
+        for (func, func_queries) in toplevel.func_map.values().zip(&queries.func_queries) {
+            let lookups = func_queries
+                .values()
+                .map(|res| res.provide.count as usize)
+                .sum();
+            if HASH_FUNCS.contains(&func.name.0) {
+                hash_calls.push((func.name.0, lookups));
+            }
+            if func_queries.is_empty() {
+                continue;
+            }
+            let width = FuncChip::from_func(func, toplevel).width();
+            let name = func.name.to_string();
+            funcs.push(ChipStats::new(name, func_queries.len(), lookups, width));
+        }
+        let mems = MEM_TABLE_SIZES
+            .iter()
+            .zip(&queries.mem_queries)
+            .map(|(&len, mem_queries)| {
+                let lookups = mem_queries
+                    .values()
+                    .map(|res| res.provide.count as usize)
+                    .sum();
+                let width = BaseAir::<F>::width(&MemChip::<F>::new(len));
+                ChipStats::new(format!("mem{len}"), mem_queries.len(), lookups, width)
+            })
+            .collect();
+        let byte_lookups = queries.bytes.lookup_counts();
+        let bytes = ChipStats {
+            name: "bytes".into(),
+            queries: queries.bytes.num_inputs(),
+            lookups: byte_lookups.iter().sum(),
+            // the bytes chip has a fixed height because it's preprocessed
+            rows: 1 << 16,
+            width: BaseAir::<F>::width(&BytesChip::<F>::default()),
+        };
+        Self {
+            elapsed,
+            funcs,
+            mems,
+            bytes,
+            byte_lookups,
+            hash_calls,
+        }
+    }
+
+    fn chips(&self) -> impl Iterator<Item = &ChipStats> {
+        self.funcs
+            .iter()
+            .chain(&self.mems)
+            .chain(std::iter::once(&self.bytes))
+    }
+}
+
+impl fmt::Display for EvalStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Time: {:?}", self.elapsed)?;
+        writeln!(
+            f,
+            "{:<32} {:>10} {:>10} {:>10} {:>8}",
+            "Chip", "Queries", "Lookups", "Rows", "Columns"
+        )?;
+        for chip in self.chips() {
+            writeln!(
+                f,
+                "{:<32} {:>10} {:>10} {:>10} {:>8}",
+                chip.name, chip.queries, chip.lookups, chip.rows, chip.width
+            )?;
+        }
+        let byte_lookups = BYTE_OPERATIONS
+            .iter()
+            .zip(self.byte_lookups)
+            .map(|(op, count)| format!("{op} {count}"))
+            .collect::<Vec<_>>();
+        writeln!(f, "Byte lookups: {}", byte_lookups.join(", "))?;
+        let hash_calls = self
+            .hash_calls
+            .iter()
+            .map(|(name, count)| format!("{name} {count}"))
+            .collect::<Vec<_>>();
+        writeln!(f, "Hash invocations: {}", hash_calls.join(", "))?;
+        write!(
+            f,
+            "Estimated trace area: {} cells",
+            self.chips().map(ChipStats::area).sum::<usize>()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::core::cli::repl::Repl;
+
+    use super::EvalStats;
+
+    #[test]
+    fn test_eval_stats() {
+        let mut repl = Repl::new_native(false);
+        let expr = repl.zstore.read("(+ 1 2)", &repl.lang_symbols).unwrap();
+        let env = repl.env;
+        repl.reduce_with_env(&expr, &env).unwrap();
+        let stats = EvalStats::new(&repl.toplevel, &repl.queries, Duration::ZERO);
+        let eval = stats.funcs.iter().find(|chip| chip.name == "eval").unwrap();
+        assert!(eval.queries > 0);
+        assert!(eval.rows >= eval.queries && eval.rows.is_power_of_two());
+        assert_eq!(stats.mems.len(), 6);
+        assert!(stats.to_string().starts_with("Time: "));
+    }
+}
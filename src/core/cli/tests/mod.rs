@@ -3,6 +3,7 @@ use std::process::Command;
 use crate::core::cli::{
     config::{set_config_if_unset, Config},
     repl::Repl,
+    watch::WatchLog,
 };
 
 #[test]
@@ -17,6 +18,37 @@ fn test_meta_commands() {
         .load_file("src/core/cli/tests/second.lurk".into(), false)
         .is_ok());
     std::fs::remove_file("repl-test-two").unwrap();
+    std::fs::remove_file("repl-test-session").unwrap();
+}
+
+#[test]
+fn test_watch_log() {
+    set_config_if_unset(Config::default());
+    let mut repl = Repl::new_native(false);
+    repl.watch_log = Some(WatchLog::default());
+    assert!(repl
+        .load_file("src/core/cli/tests/loaded.lurk".into(), false)
+        .is_ok());
+    let WatchLog { files, forms } = repl.watch_log.take().unwrap();
+    assert_eq!(
+        files,
+        [
+            "src/core/cli/tests/loaded.lurk",
+            "src/core/cli/tests/loaded-nested.lurk"
+        ]
+    );
+    let (last_form, last_result) = forms.last().unwrap();
+    assert_eq!(last_form, "!(assert-eq loaded-nested :loaded-nested)");
+    assert_eq!(last_result, "t");
+}
+
+#[test]
+fn test_macros() {
+    set_config_if_unset(Config::default());
+    let mut repl = Repl::new_native(false);
+    assert!(repl
+        .load_file("src/core/cli/tests/macros.lurk".into(), false)
+        .is_ok());
 }
 
 #[ignore]
@@ -0,0 +1,80 @@
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use rustc_hash::FxHashMap;
+use std::{fs, thread, time::Duration, time::SystemTime};
+
+use super::repl::Repl;
+
+/// How often the watched files are checked for modifications
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Data collected by a `Repl` while loading a file in watch mode
+#[derive(Default)]
+pub(crate) struct WatchLog {
+    /// The files that were loaded, including the ones reached via `!(load ...)`
+    pub(crate) files: Vec<Utf8PathBuf>,
+    /// The source code of each processed form paired with its formatted result
+    pub(crate) forms: Vec<(String, String)>,
+}
+
+impl WatchLog {
+    pub(crate) fn record_file(&mut self, file_path: &Utf8Path) {
+        if !self.files.iter().any(|f| f == file_path) {
+            self.files.push(file_path.to_path_buf());
+        }
+    }
+
+    pub(crate) fn record_form(&mut self, form: String, result: String) {
+        self.forms.push((form, result));
+    }
+}
+
+/// Loads a file in a fresh `Repl`, returning the collected `WatchLog` and the
+/// error that interrupted the loading, if any
+fn load_once(file_path: &Utf8Path) -> (WatchLog, Option<anyhow::Error>) {
+    let mut repl = Repl::new_native(false);
+    let mut watch_log = WatchLog::default();
+    // make sure the root file is watched even if it can't be read
+    watch_log.record_file(file_path);
+    repl.watch_log = Some(watch_log);
+    let error = repl.load_file(file_path, false).err();
+    let watch_log = repl.watch_log.take().expect("Watch log was dropped");
+    (watch_log, error)
+}
+
+fn modification_times(files: &[Utf8PathBuf]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| fs::metadata(file).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// Loads a file and reloads it, in a fresh `Repl`, every time it or any file
+/// it loads is modified. Only the forms whose results changed since the last
+/// evaluation are printed. Never returns unless interrupted.
+pub(crate) fn watch_file(file_path: &Utf8Path) -> Result<()> {
+    let mut previous: FxHashMap<String, String> = FxHashMap::default();
+    loop {
+        let (WatchLog { files, forms }, error) = load_once(file_path);
+        let mut num_changed = 0;
+        for (form, result) in &forms {
+            if previous.get(form) != Some(result) {
+                println!("{form}\n  => {result}");
+                num_changed += 1;
+            }
+        }
+        if let Some(e) = error {
+            eprintln!("Error: {e}");
+        } else if num_changed == 0 {
+            println!("No results changed");
+        }
+        previous = forms.into_iter().collect();
+
+        println!("Watching {} file(s) for changes...", files.len());
+        let times = modification_times(&files);
+        while modification_times(&files) == times {
+            thread::sleep(POLL_INTERVAL);
+        }
+        println!("Reloading {file_path}");
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
     lair::{chipset::NoChip, expr::FuncE, toplevel::Toplevel},
 };
 
-use super::{ingress::SymbolsDigests, tag::Tag};
+use super::{eval_direct::coerce_if_sym, ingress::SymbolsDigests, state::user_sym, tag::Tag};
 
 #[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive)]
@@ -73,6 +73,8 @@ pub enum Op {
     Begin,
     Fail,
     Breakpoint,
+    // Pattern matching
+    Match,
 }
 
 impl Op {
@@ -108,6 +110,29 @@ impl Val {
     }
 }
 
+/// Tags of the `match` patterns compiled by `compile_pattern`
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive)]
+pub enum Pat {
+    Wildcard = 0x00100000,
+    Var,
+    Literal,
+    Cons,
+    TypeEqq,
+}
+
+impl Pat {
+    #[inline]
+    pub fn to_field<F: AbstractField>(self) -> F {
+        F::from_canonical_u32(self as u32)
+    }
+
+    #[inline]
+    pub fn from_field<F: PrimeField32>(f: &F) -> Pat {
+        Pat::from_u32(f.as_canonical_u32()).expect("Field element doesn't map to a tag")
+    }
+}
+
 pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F> {
     func!(
         invertible fn compile(expr_tag, expr): [2] {
@@ -121,12 +146,16 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                     match head_tag {
                         Tag::Builtin => {
                             let op = call(symbol_to_op, head);
+                            let builtin_tag = Tag::Builtin;
+                            // the arguments are the payload of `InvalidForm` errors
+                            let (form_tag, form) = (rest_tag, rest);
                             match head [|sym| digests.builtin_symbol_ptr(sym).to_field()] {
                                 // zero elements
                                 "current-env", "empty-env", "fail" => {
                                     let rest_not_nil = sub(rest_tag, nil_tag);
                                     if rest_not_nil {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let null = 0;
                                     return (op, null)
@@ -135,12 +164,14 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                 "car", "cdr", "u64", "char", "atom", "emit", "commit", "comm", "open", "secret", "bignum" => {
                                     let rest_not_cons = sub(rest_tag, cons_tag);
                                     if rest_not_cons {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (expr_tag, expr, rest_tag, _rest) = load(rest);
                                     let rest_not_nil = sub(rest_tag, nil_tag);
                                     if rest_not_nil {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (cexpr_tag, cexpr) = call(compile, expr_tag, expr);
                                     match cexpr_tag {
@@ -164,17 +195,20 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                 "apply", "cons", "strcons", "hide", "eq", "eqq", "type-eq", "type-eqq"  => {
                                     let rest_not_cons = sub(rest_tag, cons_tag);
                                     if rest_not_cons {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (fst_tag, fst, rest_tag, rest) = load(rest);
                                     let rest_not_cons = sub(rest_tag, cons_tag);
                                     if rest_not_cons {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (snd_tag, snd, rest_tag, _rest) = load(rest);
                                     let rest_not_nil = sub(rest_tag, nil_tag);
                                     if rest_not_nil {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (cfst_tag, cfst) = call(compile, fst_tag, fst);
                                     match cfst_tag {
@@ -226,7 +260,8 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                             return (res_tag, res)
                                         }
                                     };
-                                    return (err_tag, invalid_form)
+                                    let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                    return (err_tag, err)
                                 }
                                 "=", "<", ">", "<=", ">=" => {
                                     let (res_tag, res) = call(compile_fold_rel, op, rest_tag, rest);
@@ -242,17 +277,20 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                 "lambda", "let", "letrec" => {
                                     let rest_not_cons = sub(rest_tag, cons_tag);
                                     if rest_not_cons {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (fst_tag, fst, rest_tag, rest) = load(rest);
                                     let rest_not_cons = sub(rest_tag, cons_tag);
                                     if rest_not_cons {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (snd_tag, snd, rest_tag, _rest) = load(rest);
                                     let rest_not_nil = sub(rest_tag, nil_tag);
                                     if rest_not_nil {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (cbody_tag, cbody) = call(compile, snd_tag, snd);
                                     match cbody_tag {
@@ -289,12 +327,14 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                 "quote" => {
                                     let rest_not_cons = sub(rest_tag, cons_tag);
                                     if rest_not_cons {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (expr_tag, expr, rest_tag, _rest) = load(rest);
                                     let rest_not_nil = sub(rest_tag, nil_tag);
                                     if rest_not_nil {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (cexpr_tag, cexpr) = call(convert_data, expr_tag, expr);
                                     match cexpr_tag {
@@ -309,7 +349,8 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                 "eval" => {
                                     let rest_not_cons = sub(rest_tag, cons_tag);
                                     if rest_not_cons {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (expr_tag, expr, rest_tag, rest) = load(rest);
                                     let (cexpr_tag, cexpr) = call(compile, expr_tag, expr);
@@ -330,7 +371,8 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                             let (env_expr_tag, env_expr, rest_tag, _rest) = load(rest);
                                             let rest_not_nil = sub(rest_tag, nil_tag);
                                             if rest_not_nil {
-                                                return (err_tag, invalid_form)
+                                                let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                                return (err_tag, err)
                                             }
                                             let (env_cexpr_tag, env_cexpr) = call(compile, env_expr_tag, env_expr);
                                             match env_cexpr_tag {
@@ -342,13 +384,15 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                             return (tag, ptr)
                                         }
                                     };
-                                    let not_env = EvalErr::InvalidForm;
-                                    return (err_tag, not_env)
+                                    let not_env = EvalErr::NotEnv;
+                                    let err = store(not_env, rest_tag, rest, builtin_tag, head);
+                                    return (err_tag, err)
                                 }
                                 "if" => {
                                     let rest_not_cons = sub(rest_tag, cons_tag);
                                     if rest_not_cons {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (expr_tag, expr, rest_tag, rest) = load(rest);
                                     let (cexpr_tag, cexpr) = call(compile, expr_tag, expr);
@@ -359,7 +403,8 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                     };
                                     let rest_not_cons = sub(rest_tag, cons_tag);
                                     if rest_not_cons {
-                                        return (err_tag, invalid_form)
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
                                     }
                                     let (t_branch_tag, t_branch, rest_tag, rest) = load(rest);
                                     let (ct_branch_tag, ct_branch) = call(compile, t_branch_tag, t_branch);
@@ -380,7 +425,8 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                             let (f_branch_tag, f_branch, rest_tag, _rest) = load(rest);
                                             let rest_not_nil = sub(rest_tag, nil_tag);
                                             if rest_not_nil {
-                                                return (err_tag, invalid_form)
+                                                let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                                return (err_tag, err)
                                             }
                                             let (cf_branch_tag, cf_branch) = call(compile, f_branch_tag, f_branch);
                                             match cf_branch_tag {
@@ -392,11 +438,35 @@ pub fn compile<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                             return (tag, ptr)
                                         }
                                     };
-                                    return (err_tag, invalid_form)
+                                    let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                    return (err_tag, err)
+                                }
+                                "match" => {
+                                    let rest_not_cons = sub(rest_tag, cons_tag);
+                                    if rest_not_cons {
+                                        let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                        return (err_tag, err)
+                                    }
+                                    let (expr_tag, expr, clauses_tag, clauses) = load(rest);
+                                    let (cexpr_tag, cexpr) = call(compile, expr_tag, expr);
+                                    match cexpr_tag {
+                                        Tag::Err => {
+                                            return (cexpr_tag, cexpr)
+                                        }
+                                    };
+                                    let (cclauses_tag, cclauses) = call(compile_match_clauses, clauses_tag, clauses);
+                                    match cclauses_tag {
+                                        Tag::Err => {
+                                            return (cclauses_tag, cclauses)
+                                        }
+                                    };
+                                    let ptr = store(cexpr_tag, cexpr, cclauses_tag, cclauses);
+                                    return (op, ptr)
                                 }
                                 "breakpoint" => {
                                     // TODO
-                                    return (err_tag, invalid_form)
+                                    let err = store(invalid_form, form_tag, form, builtin_tag, head);
+                                    return (err_tag, err)
                                 }
                             }
                         }
@@ -595,6 +665,10 @@ pub fn symbol_to_op<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> Func
                     let tag = Op::Fail;
                     return tag
                 }
+                "match" => {
+                    let tag = Op::Match;
+                    return tag
+                }
             }
 
         }
@@ -604,8 +678,10 @@ pub fn symbol_to_op<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> Func
 pub fn compile_lambda<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F> {
     func!(
         invertible fn compile_lambda(vars_tag, vars, cbody_tag, cbody): [2] {
+            // parameter errors are the ones `eval_direct` raises when applying the function
             let err_tag = Tag::Err;
-            let invalid_form = EvalErr::InvalidForm;
+            let nil_tag = InternalTag::Nil;
+            let nil = digests.lurk_symbol_ptr("nil");
             match vars_tag {
                 InternalTag::Nil => {
                     let tag = Op::MkThunk;
@@ -638,42 +714,55 @@ pub fn compile_lambda<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> Fu
                             }
                             match rest_vars_tag {
                                 InternalTag::Nil => {
-                                    return (err_tag, invalid_form)
+                                    let err = EvalErr::ParamInvalidRest;
+                                    let err = store(err, vars_tag, vars, nil_tag, nil);
+                                    return (err_tag, err)
                                 }
                                 Tag::Cons => {
                                     let (var_tag, var, rest_vars_tag, _rest_vars) = load(rest_vars);
                                     match var_tag {
                                         Tag::Sym, Tag::Builtin, Tag::Coroutine => {
-                                            let nil_tag = InternalTag::Nil;
                                             let rest_vars_not_nil = sub(rest_vars_tag, nil_tag);
                                             if rest_vars_not_nil {
-                                                return (err_tag, invalid_form)
+                                                let err = EvalErr::ParamInvalidRest;
+                                                let err = store(err, vars_tag, vars, nil_tag, nil);
+                                                return (err_tag, err)
                                             }
                                             let ptr = store(var_tag, var, cbody_tag, cbody);
                                             let tag = Op::MkRestFun;
                                             return (tag, ptr)
                                         }
                                     };
-                                    return (err_tag, invalid_form)
+                                    let err = EvalErr::IllegalBindingVar;
+                                    let err = store(err, var_tag, var, nil_tag, nil);
+                                    return (err_tag, err)
                                 }
                             };
-                            return (err_tag, invalid_form)
+                            let err = EvalErr::ParamsNotList;
+                            let err = store(err, vars_tag, vars, nil_tag, nil);
+                            return (err_tag, err)
                         }
                     };
-                    return (err_tag, invalid_form)
+                    let err = EvalErr::IllegalBindingVar;
+                    let err = store(err, var_tag, var, nil_tag, nil);
+                    return (err_tag, err)
                 }
             };
-            return (err_tag, invalid_form)
+            let err = EvalErr::ParamsNotList;
+            let err = store(err, vars_tag, vars, nil_tag, nil);
+            return (err_tag, err)
         }
     )
 }
 
-pub fn compile_let<F: AbstractField + Ord>() -> FuncE<F> {
+pub fn compile_let<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F> {
     func!(
         fn compile_let(binds_tag, binds, cbody_tag, cbody): [2] {
             // You can only call this function on let tags
 
             let err_tag = Tag::Err;
+            let builtin_tag = Tag::Builtin;
+            let builtin = digests.builtin_symbol_ptr("let");
             let invalid_form = EvalErr::InvalidForm;
             match binds_tag {
                 InternalTag::Nil => {
@@ -685,20 +774,23 @@ pub fn compile_let<F: AbstractField + Ord>() -> FuncE<F> {
                     let (bind_tag, bind, rest_binds_tag, rest_binds) = load(binds);
                     let bind_not_cons = sub(bind_tag, cons_tag);
                     if bind_not_cons {
-                        return (err_tag, invalid_form)
+                        let err = store(invalid_form, bind_tag, bind, builtin_tag, builtin);
+                        return (err_tag, err)
                     }
                     let (var_tag, var, rest_tag, rest) = load(bind);
                     let rest_not_cons = sub(rest_tag, cons_tag);
                     if rest_not_cons {
-                        return (err_tag, invalid_form)
-                    }
-                    let (val_tag, val, rest_tag, _rest) = load(rest);
-                    let rest_not_nil = sub(rest_tag, nil_tag);
-                    if rest_not_nil {
-                        return (err_tag, invalid_form)
+                        let err = store(invalid_form, bind_tag, bind, builtin_tag, builtin);
+                        return (err_tag, err)
                     }
                     match var_tag {
                         Tag::Sym, Tag::Builtin, Tag::Coroutine => {
+                            let (val_tag, val, rest_tag, _rest) = load(rest);
+                            let rest_not_nil = sub(rest_tag, nil_tag);
+                            if rest_not_nil {
+                                let err = store(invalid_form, bind_tag, bind, builtin_tag, builtin);
+                                return (err_tag, err)
+                            }
                             let (cval_tag, cval) = call(compile, val_tag, val);
                             match cval_tag {
                                 Tag::Err => {
@@ -716,19 +808,24 @@ pub fn compile_let<F: AbstractField + Ord>() -> FuncE<F> {
                             return (let_op, ptr)
                         }
                     };
-                    return (err_tag, invalid_form)
+                    let err = EvalErr::IllegalBindingVar;
+                    let err = store(err, var_tag, var, builtin_tag, builtin);
+                    return (err_tag, err)
                 }
             };
-            return (err_tag, invalid_form)
+            let err = store(invalid_form, binds_tag, binds, builtin_tag, builtin);
+            return (err_tag, err)
         }
     )
 }
 
-pub fn compile_mutual_binds<F: AbstractField + Ord>() -> FuncE<F> {
+pub fn compile_mutual_binds<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F> {
     func!(
         fn compile_mutual_binds(binds_tag, binds): [2] {
             let err_tag = Tag::Err;
             let env_tag = Tag::Env;
+            let builtin_tag = Tag::Builtin;
+            let builtin = digests.builtin_symbol_ptr("letrec");
             let invalid_form_err = EvalErr::InvalidForm;
             match binds_tag {
                 InternalTag::Nil => {
@@ -740,18 +837,21 @@ pub fn compile_mutual_binds<F: AbstractField + Ord>() -> FuncE<F> {
                     let (binding_tag, binding, binds_tag, binds) = load(binds);
                     let binding_not_cons = sub(binding_tag, cons_tag);
                     if binding_not_cons {
-                        return (err_tag, invalid_form_err)
+                        let err = store(invalid_form_err, binding_tag, binding, builtin_tag, builtin);
+                        return (err_tag, err)
                     }
                     let (var_tag, var, rest_tag, rest) = load(binding);
                     let rest_tag_not_cons = sub(rest_tag, cons_tag);
                     if rest_tag_not_cons {
-                        return (err_tag, invalid_form_err)
+                        let err = store(invalid_form_err, binding_tag, binding, builtin_tag, builtin);
+                        return (err_tag, err)
                     }
                     let (expr_tag, expr, rest_tag, _rest) = load(rest);
                     let nil_tag = InternalTag::Nil;
                     let rest_tag_not_nil = sub(rest_tag, nil_tag);
                     if rest_tag_not_nil {
-                        return (err_tag, invalid_form_err)
+                        let err = store(invalid_form_err, binding_tag, binding, builtin_tag, builtin);
+                        return (err_tag, err)
                     }
                     match var_tag {
                         Tag::Sym, Tag::Builtin, Tag::Coroutine => {
@@ -771,19 +871,266 @@ pub fn compile_mutual_binds<F: AbstractField + Ord>() -> FuncE<F> {
                             return (env_tag, cbinds)
                         }
                     };
-                    return (err_tag, invalid_form_err)
+                    let illegal_binding_var_err = EvalErr::IllegalBindingVar;
+                    let err = store(illegal_binding_var_err, var_tag, var, builtin_tag, builtin);
+                    return (err_tag, err)
+                }
+            };
+            let err = store(invalid_form_err, binds_tag, binds, builtin_tag, builtin);
+            return (err_tag, err)
+        }
+    )
+}
+
+/// Compiles the clauses of a `match` into a chain of `(pattern, guard, body, rest)`
+/// records ending in `nil`. Patterns are compiled by `compile_pattern` and a missing
+/// guard becomes `t`.
+pub fn compile_match_clauses<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F> {
+    func!(
+        fn compile_match_clauses(clauses_tag, clauses): [2] {
+            let err_tag = Tag::Err;
+            let builtin_tag = Tag::Builtin;
+            let builtin = digests.builtin_symbol_ptr("match");
+            let invalid_form = EvalErr::InvalidForm;
+            match clauses_tag {
+                InternalTag::Nil => {
+                    return (clauses_tag, clauses)
+                }
+                Tag::Cons => {
+                    let cons_tag = Tag::Cons;
+                    let nil_tag = InternalTag::Nil;
+                    let (clause_tag, clause, clauses_tag, clauses) = load(clauses);
+                    let clause_not_cons = sub(clause_tag, cons_tag);
+                    if clause_not_cons {
+                        let err = store(invalid_form, clause_tag, clause, builtin_tag, builtin);
+                        return (err_tag, err)
+                    }
+                    let (pat_tag, pat, rest_tag, rest) = load(clause);
+                    let rest_not_cons = sub(rest_tag, cons_tag);
+                    if rest_not_cons {
+                        let err = store(invalid_form, clause_tag, clause, builtin_tag, builtin);
+                        return (err_tag, err)
+                    }
+                    let (cpat_tag, cpat) = call(compile_pattern, pat_tag, pat);
+                    match cpat_tag {
+                        Tag::Err => {
+                            return (cpat_tag, cpat)
+                        }
+                    };
+                    let (fst_tag, fst, rest_tag, rest) = load(rest);
+                    let (cfst_tag, cfst) = call(compile, fst_tag, fst);
+                    match cfst_tag {
+                        Tag::Err => {
+                            return (cfst_tag, cfst)
+                        }
+                    };
+                    let (cclauses_tag, cclauses) = call(compile_match_clauses, clauses_tag, clauses);
+                    match cclauses_tag {
+                        Tag::Err => {
+                            return (cclauses_tag, cclauses)
+                        }
+                    };
+                    match rest_tag {
+                        InternalTag::Nil => {
+                            let t_tag = InternalTag::T;
+                            let t = digests.lurk_symbol_ptr("t");
+                            let ptr = store(cpat_tag, cpat, t_tag, t, cfst_tag, cfst, cclauses_tag, cclauses);
+                            return (cons_tag, ptr)
+                        }
+                        Tag::Cons => {
+                            let (body_tag, body, rest_tag, _rest) = load(rest);
+                            let rest_not_nil = sub(rest_tag, nil_tag);
+                            if rest_not_nil {
+                                let err = store(invalid_form, clause_tag, clause, builtin_tag, builtin);
+                                return (err_tag, err)
+                            }
+                            let (cbody_tag, cbody) = call(compile, body_tag, body);
+                            match cbody_tag {
+                                Tag::Err => {
+                                    return (cbody_tag, cbody)
+                                }
+                            };
+                            let ptr = store(cpat_tag, cpat, cfst_tag, cfst, cbody_tag, cbody, cclauses_tag, cclauses);
+                            return (cons_tag, ptr)
+                        }
+                    };
+                    let err = store(invalid_form, clause_tag, clause, builtin_tag, builtin);
+                    return (err_tag, err)
+                }
+            };
+            let err = store(invalid_form, clauses_tag, clauses, builtin_tag, builtin);
+            return (err_tag, err)
+        }
+    )
+}
+
+/// Compiles a `match` pattern into a record tagged by `Pat`:
+/// * `_` of the user package becomes `Pat::Wildcard`
+/// * other symbols become `Pat::Var`, holding the symbol
+/// * `(quote datum)` and other atoms become `Pat::Literal`, holding the converted data
+/// * `(cons car-pat cdr-pat)` becomes `Pat::Cons`, holding both compiled patterns
+/// * `(list pat1 ... patN)` becomes a chain of `Pat::Cons` ending in a literal `nil`
+/// * `(type-eqq example pat)` becomes `Pat::TypeEqq`, holding the type of `example`
+///   and the compiled `pat`
+pub fn compile_pattern<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F> {
+    func!(
+        fn compile_pattern(pat_tag, pat): [2] {
+            let err_tag = Tag::Err;
+            let builtin_tag = Tag::Builtin;
+            let builtin = digests.builtin_symbol_ptr("match");
+            let invalid_form = EvalErr::InvalidForm;
+            match pat_tag {
+                Tag::Sym => {
+                    let wildcard = digests.symbol_ptr(&user_sym("_"));
+                    let not_wildcard = sub(pat, wildcard);
+                    if not_wildcard {
+                        let tag = Pat::Var;
+                        let ptr = store(pat_tag, pat);
+                        return (tag, ptr)
+                    }
+                    let tag = Pat::Wildcard;
+                    let null = 0;
+                    return (tag, null)
+                }
+                Tag::Builtin, Tag::Coroutine => {
+                    let tag = Pat::Var;
+                    let ptr = store(pat_tag, pat);
+                    return (tag, ptr)
+                }
+                Tag::Cons => {
+                    let cons_tag = Tag::Cons;
+                    let nil_tag = InternalTag::Nil;
+                    let (head_tag, head, args_tag, args) = load(pat);
+                    match head_tag {
+                        Tag::Builtin => {
+                            match head [|name| digests.builtin_symbol_ptr(name).to_field()] {
+                                "quote" => {
+                                    let args_not_cons = sub(args_tag, cons_tag);
+                                    if args_not_cons {
+                                        let err = store(invalid_form, pat_tag, pat, builtin_tag, builtin);
+                                        return (err_tag, err)
+                                    }
+                                    let (datum_tag, datum, args_tag, _args) = load(args);
+                                    let args_not_nil = sub(args_tag, nil_tag);
+                                    if args_not_nil {
+                                        let err = store(invalid_form, pat_tag, pat, builtin_tag, builtin);
+                                        return (err_tag, err)
+                                    }
+                                    let (cdatum_tag, cdatum) = call(convert_data, datum_tag, datum);
+                                    match cdatum_tag {
+                                        Tag::Err => {
+                                            return (cdatum_tag, cdatum)
+                                        }
+                                    };
+                                    let tag = Pat::Literal;
+                                    let ptr = store(cdatum_tag, cdatum);
+                                    return (tag, ptr)
+                                }
+                                "cons", "type-eqq" => {
+                                    let args_not_cons = sub(args_tag, cons_tag);
+                                    if args_not_cons {
+                                        let err = store(invalid_form, pat_tag, pat, builtin_tag, builtin);
+                                        return (err_tag, err)
+                                    }
+                                    let (fst_tag, fst, args_tag, args) = load(args);
+                                    let args_not_cons = sub(args_tag, cons_tag);
+                                    if args_not_cons {
+                                        let err = store(invalid_form, pat_tag, pat, builtin_tag, builtin);
+                                        return (err_tag, err)
+                                    }
+                                    let (snd_tag, snd, args_tag, _args) = load(args);
+                                    let args_not_nil = sub(args_tag, nil_tag);
+                                    if args_not_nil {
+                                        let err = store(invalid_form, pat_tag, pat, builtin_tag, builtin);
+                                        return (err_tag, err)
+                                    }
+                                    let (csnd_tag, csnd) = call(compile_pattern, snd_tag, snd);
+                                    match csnd_tag {
+                                        Tag::Err => {
+                                            return (csnd_tag, csnd)
+                                        }
+                                    };
+                                    match head [|name| digests.builtin_symbol_ptr(name).to_field()] {
+                                        "cons" => {
+                                            let (cfst_tag, cfst) = call(compile_pattern, fst_tag, fst);
+                                            match cfst_tag {
+                                                Tag::Err => {
+                                                    return (cfst_tag, cfst)
+                                                }
+                                            };
+                                            let tag = Pat::Cons;
+                                            let ptr = store(cfst_tag, cfst, csnd_tag, csnd);
+                                            return (tag, ptr)
+                                        }
+                                        "type-eqq" => {
+                                            let type_tag = call(coerce_if_sym, fst_tag);
+                                            let tag = Pat::TypeEqq;
+                                            let ptr = store(type_tag, csnd_tag, csnd);
+                                            return (tag, ptr)
+                                        }
+                                    }
+                                }
+                                "list" => {
+                                    match args_tag {
+                                        InternalTag::Nil => {
+                                            let nil = digests.lurk_symbol_ptr("nil");
+                                            let tag = Pat::Literal;
+                                            let ptr = store(nil_tag, nil);
+                                            return (tag, ptr)
+                                        }
+                                        Tag::Cons => {
+                                            let (fst_tag, fst, args_tag, args) = load(args);
+                                            let (cfst_tag, cfst) = call(compile_pattern, fst_tag, fst);
+                                            match cfst_tag {
+                                                Tag::Err => {
+                                                    return (cfst_tag, cfst)
+                                                }
+                                            };
+                                            // the rest of the patterns are compiled as `(list ...)`
+                                            let rest_pat = store(head_tag, head, args_tag, args);
+                                            let (crest_tag, crest) = call(compile_pattern, cons_tag, rest_pat);
+                                            match crest_tag {
+                                                Tag::Err => {
+                                                    return (crest_tag, crest)
+                                                }
+                                            };
+                                            let tag = Pat::Cons;
+                                            let ptr = store(cfst_tag, cfst, crest_tag, crest);
+                                            return (tag, ptr)
+                                        }
+                                    };
+                                    let err = store(invalid_form, pat_tag, pat, builtin_tag, builtin);
+                                    return (err_tag, err)
+                                }
+                            };
+                            let err = store(invalid_form, pat_tag, pat, builtin_tag, builtin);
+                            return (err_tag, err)
+                        }
+                    };
+                    let err = store(invalid_form, pat_tag, pat, builtin_tag, builtin);
+                    return (err_tag, err)
+                }
+            };
+            let (cpat_tag, cpat) = call(convert_data, pat_tag, pat);
+            match cpat_tag {
+                Tag::Err => {
+                    return (cpat_tag, cpat)
                 }
             };
-            return (err_tag, invalid_form_err)
+            let tag = Pat::Literal;
+            let ptr = store(cpat_tag, cpat);
+            return (tag, ptr)
         }
     )
 }
 
-pub fn compile_fold_right<F: AbstractField + Ord>() -> FuncE<F> {
+pub fn compile_fold_right<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F> {
     func!(
         fn compile_fold_right(op, init_tag, init, exprs_tag, exprs): [2] {
             let err_tag = Tag::Err;
             let invalid_form = EvalErr::InvalidForm;
+            let nil_tag = InternalTag::Nil;
+            let nil = digests.lurk_symbol_ptr("nil");
             match exprs_tag {
                 InternalTag::Nil => {
                     return (init_tag, init)
@@ -812,16 +1159,19 @@ pub fn compile_fold_right<F: AbstractField + Ord>() -> FuncE<F> {
                     return (op, ptr)
                 }
             };
-            return (err_tag, invalid_form)
+            let err = store(invalid_form, exprs_tag, exprs, nil_tag, nil);
+            return (err_tag, err)
         }
     )
 }
 
-pub fn compile_fold_left<F: AbstractField + Ord>() -> FuncE<F> {
+pub fn compile_fold_left<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> FuncE<F> {
     func!(
         fn compile_fold_left(op, acc_tag, acc, exprs_tag, exprs): [2] {
             let err_tag = Tag::Err;
             let invalid_form = EvalErr::InvalidForm;
+            let nil_tag = InternalTag::Nil;
+            let nil = digests.lurk_symbol_ptr("nil");
             match exprs_tag {
                 InternalTag::Nil => {
                     return (acc_tag, acc)
@@ -844,7 +1194,8 @@ pub fn compile_fold_left<F: AbstractField + Ord>() -> FuncE<F> {
                     return (res_tag, res)
                 }
             };
-            return (err_tag, invalid_form)
+            let err = store(invalid_form, exprs_tag, exprs, nil_tag, nil);
+            return (err_tag, err)
         }
     )
 }
@@ -854,6 +1205,8 @@ pub fn compile_fold_rel<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) ->
         fn compile_fold_rel(op, exprs_tag, exprs): [2] {
             let err_tag = Tag::Err;
             let invalid_form = EvalErr::InvalidForm;
+            let nil_tag = InternalTag::Nil;
+            let nil = digests.lurk_symbol_ptr("nil");
             let t_tag = InternalTag::T;
             let t = digests.lurk_symbol_ptr("t");
             match exprs_tag {
@@ -897,10 +1250,12 @@ pub fn compile_fold_rel<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) ->
                             return (and, ptr)
                         }
                     };
-                    return (err_tag, invalid_form)
+                    let err = store(invalid_form, rest_tag, rest, nil_tag, nil);
+                    return (err_tag, err)
                 }
             };
-            return (err_tag, invalid_form)
+            let err = store(invalid_form, exprs_tag, exprs, nil_tag, nil);
+            return (err_tag, err)
         }
     )
 }
@@ -1079,8 +1434,8 @@ pub fn deconvert_data<F: AbstractField + Ord>(digests: &SymbolsDigests<F>) -> Fu
                     let ptr = store(body_tag, body, env);
                     return (tag, ptr)
                 }
-                InternalTag::T, InternalTag::Nil, Tag::Sym, Tag::Num, Tag::Str,
-                Tag::Char, Tag::Comm, Tag::U64, Tag::Key, Tag::Err, Tag::Builtin => {
+                InternalTag::T, InternalTag::Nil, Tag::Sym, Tag::Num, Tag::Str, Tag::Vec, Tag::Map,
+                Tag::Char, Tag::Comm, Tag::U64, Tag::I64, Tag::Decimal, Tag::Key, Tag::Err, Tag::Builtin => {
                     return (cexpr_tag, cexpr)
                 }
             }
@@ -1093,13 +1448,16 @@ pub fn compile_funcs<F: PrimeField32>(digests: &SymbolsDigests<F>) -> Vec<FuncE<
         compile(digests),
         symbol_to_op(digests),
         compile_lambda(digests),
-        compile_let(),
-        compile_mutual_binds(),
-        compile_fold_right(),
-        compile_fold_left(),
+        compile_let(digests),
+        compile_mutual_binds(digests),
+        compile_match_clauses(digests),
+        compile_pattern(digests),
+        compile_fold_right(digests),
+        compile_fold_left(digests),
         compile_fold_rel(digests),
         convert_data(digests),
         deconvert_data(digests),
+        coerce_if_sym(),
     ]
 }
 
@@ -1129,6 +1487,8 @@ mod test {
         let compile_lambda = FuncChip::from_name("compile_lambda", toplevel);
         let compile_let = FuncChip::from_name("compile_let", toplevel);
         let compile_mutual_binds = FuncChip::from_name("compile_mutual_binds", toplevel);
+        let compile_match_clauses = FuncChip::from_name("compile_match_clauses", toplevel);
+        let compile_pattern = FuncChip::from_name("compile_pattern", toplevel);
         let compile_fold_right = FuncChip::from_name("compile_fold_right", toplevel);
         let compile_fold_left = FuncChip::from_name("compile_fold_left", toplevel);
         let compile_fold_rel = FuncChip::from_name("compile_fold_rel", toplevel);
@@ -1138,11 +1498,13 @@ mod test {
         let expect_eq = |computed: usize, expected: Expect| {
             expected.assert_eq(&computed.to_string());
         };
-        expect_eq(compile.width(), expect!["116"]);
-        expect_eq(symbol_to_op.width(), expect!["46"]);
-        expect_eq(compile_lambda.width(), expect!["42"]);
+        expect_eq(compile.width(), expect!["120"]);
+        expect_eq(symbol_to_op.width(), expect!["47"]);
+        expect_eq(compile_lambda.width(), expect!["43"]);
         expect_eq(compile_let.width(), expect!["57"]);
         expect_eq(compile_mutual_binds.width(), expect!["55"]);
+        expect_eq(compile_match_clauses.width(), expect!["75"]);
+        expect_eq(compile_pattern.width(), expect!["68"]);
         expect_eq(compile_fold_right.width(), expect!["40"]);
         expect_eq(compile_fold_left.width(), expect!["38"]);
         expect_eq(compile_fold_rel.width(), expect!["58"]);
@@ -1,11 +1,18 @@
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 use p3_field::{AbstractField, PrimeField32};
+use strum::EnumIter;
 
-#[derive(Clone, Copy, FromPrimitive, Debug)]
+/// The error codes of Lurk evaluation.
+///
+/// In Lair, an error value is a pointer to a `(code, payload_tag, payload,
+/// builtin_tag, builtin)` record, with `nil` standing in for a missing payload or
+/// builtin. The payload-less records are preallocated in declaration order, so the
+/// codes start from 1 and each code is also the pointer to its payload-less record.
+#[derive(Clone, Copy, FromPrimitive, Debug, EnumIter, PartialEq, Eq)]
 #[repr(u32)]
 pub enum EvalErr {
-    UnboundVar = 0,
+    UnboundVar = 1,
     InvalidForm,
     IllegalBindingVar,
     ApplyNonFunc,
@@ -15,18 +22,27 @@ pub enum EvalErr {
     ArgsNotList,
     InvalidArg,
     DivByZero,
+    Overflow,
+    Underflow,
     NotEnv,
     NotChar,
     NotCons,
     NotString,
     NotU64,
     NotBigNum,
+    NotVector,
+    NotMap,
     CantOpen,
     CantCastToChar,
     CantCastToU64,
+    CantCastToI64,
     CantCastToBigNum,
     CantCastToComm,
-    Todo,
+    UnsupportedOp,
+    UnexpectedOp,
+    OutOfFuel,
+    NoMatch,
+    CantCastToDecimal,
 }
 
 impl EvalErr {
@@ -37,4 +53,46 @@ impl EvalErr {
     pub(crate) fn from_field<F: PrimeField32>(f: &F) -> Self {
         Self::from_u32(f.as_canonical_u32()).expect("Field element doesn't map to a EvalErr")
     }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::UnboundVar => "unbound variable",
+            Self::InvalidForm => "invalid form",
+            Self::IllegalBindingVar => "illegal binding variable",
+            Self::ApplyNonFunc => "can't apply non-function",
+            Self::ParamsNotList => "parameters are not a list",
+            Self::ParamNotSymbol => "parameter is not a symbol",
+            Self::ParamInvalidRest => "invalid &rest parameter",
+            Self::ArgsNotList => "arguments are not a list",
+            Self::InvalidArg => "invalid argument",
+            Self::DivByZero => "division by zero",
+            Self::Overflow => "overflow",
+            Self::Underflow => "underflow",
+            Self::NotEnv => "not an environment",
+            Self::NotChar => "not a char",
+            Self::NotCons => "not a cons",
+            Self::NotString => "not a string",
+            Self::NotU64 => "not a u64",
+            Self::NotBigNum => "not a bignum",
+            Self::NotVector => "not a vector",
+            Self::NotMap => "not a map",
+            Self::CantOpen => "can't open",
+            Self::CantCastToChar => "can't cast to char",
+            Self::CantCastToU64 => "can't cast to u64",
+            Self::CantCastToI64 => "can't cast to i64",
+            Self::CantCastToBigNum => "can't cast to bignum",
+            Self::CantCastToComm => "can't cast to comm",
+            Self::UnsupportedOp => "operation not supported by the compiled evaluator",
+            Self::UnexpectedOp => "operation should have been compiled away",
+            Self::OutOfFuel => "out of fuel",
+            Self::NoMatch => "no matching clause",
+            Self::CantCastToDecimal => "can't cast to decimal",
+        }
+    }
+}
+
+impl std::fmt::Display for EvalErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.description())
+    }
 }
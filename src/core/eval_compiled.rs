@@ -7,7 +7,7 @@ use rustc_hash::FxHashSet;
 use crate::{
     core::{
         big_num::field_elts_to_biguint,
-        compile::{Op, Val},
+        compile::{Op, Pat, Val},
         error::EvalErr,
         ingress::InternalTag,
     },
@@ -24,8 +24,10 @@ use super::{
     chipset::{lurk_chip_map, LurkChip},
     compile::{
         compile, compile_fold_left, compile_fold_rel, compile_fold_right, compile_lambda,
-        compile_let, compile_mutual_binds, convert_data, deconvert_data, symbol_to_op,
+        compile_let, compile_match_clauses, compile_mutual_binds, compile_pattern, convert_data,
+        deconvert_data, symbol_to_op,
     },
+    eval_direct::coerce_if_sym,
     ingress::{egress, ingress, preallocate_symbols, SymbolsDigests},
     lang::{Coroutine, Lang},
     misc::{
@@ -40,7 +42,7 @@ use super::{
 fn native_lurk_funcs<F: PrimeField32>(
     digests: &SymbolsDigests<F>,
     _coroutines: &FxIndexMap<Symbol, Coroutine<F>>,
-) -> [FuncE<F>; 35] {
+) -> [FuncE<F>; 41] {
     [
         // Entrypoint
         lurk_main(),
@@ -65,24 +67,30 @@ fn native_lurk_funcs<F: PrimeField32>(
         compile(digests),
         symbol_to_op(digests),
         compile_lambda(digests),
-        compile_let(),
-        compile_mutual_binds(),
-        compile_fold_right(),
-        compile_fold_left(),
+        compile_let(digests),
+        compile_mutual_binds(digests),
+        compile_match_clauses(digests),
+        compile_pattern(digests),
+        compile_fold_right(digests),
+        compile_fold_left(digests),
         compile_fold_rel(digests),
         convert_data(digests),
         deconvert_data(digests),
         // Evaluator
         eval(),
-        apply(),
+        apply(digests),
         eval_unop(digests),
         eval_binop(digests),
         eval_binop_num(digests),
-        eval_op_misc(),
+        eval_op_misc(digests),
         extend_env_with_mutuals(),
         eval_mutual_bindings(),
-        env_lookup(),
+        eval_match_clauses(digests),
+        match_pattern(digests),
+        env_lookup(digests),
+        op_builtin(digests),
         equal_inner(),
+        coerce_if_sym(),
     ]
 }
 
@@ -164,7 +172,7 @@ pub fn eval<F: AbstractField>() -> FuncE<F> {
     func!(
         partial fn eval(expr_tag, expr, env): [2] {
             match expr_tag {
-                Val::Fun, Val::Thunk, Val::RestFun, Tag::U64, Tag::Num, Tag::BigNum, Tag::Comm, Tag::Char, Tag::Str,
+                Val::Fun, Val::Thunk, Val::RestFun, Tag::U64, Tag::I64, Tag::Decimal, Tag::Num, Tag::BigNum, Tag::Comm, Tag::Char, Tag::Str, Tag::Vec, Tag::Map,
                 Tag::Key, Tag::Fun, Tag::Cons, Tag::Env, Tag::Err, InternalTag::T, InternalTag::Nil => {
                     return (expr_tag, expr)
                 }
@@ -240,7 +248,7 @@ pub fn eval<F: AbstractField>() -> FuncE<F> {
     )
 }
 
-pub fn apply<F: AbstractField>() -> FuncE<F> {
+pub fn apply<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
     func!(
         partial fn apply(fun_tag, fun, args_tag, args, env): [2] {
             match fun_tag {
@@ -299,7 +307,10 @@ pub fn apply<F: AbstractField>() -> FuncE<F> {
                 }
             };
             let err_tag = Tag::Err;
+            let nil_tag = InternalTag::Nil;
+            let nil = digests.lurk_symbol_ptr("nil");
             let err = EvalErr::ApplyNonFunc;
+            let err = store(err, fun_tag, fun, nil_tag, nil);
             return (err_tag, err)
         }
     )
@@ -341,7 +352,10 @@ pub fn eval_unop<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                                 }
                             };
                             let not_cons = EvalErr::NotCons;
-                            return (err_tag, not_cons)
+                            let builtin_tag = Tag::Builtin;
+                            let builtin = digests.builtin_symbol_ptr("car");
+                            let err = store(not_cons, arg_tag, arg, builtin_tag, builtin);
+                            return (err_tag, err)
                         }
                         Op::Cdr => {
                             match arg_tag {
@@ -363,7 +377,10 @@ pub fn eval_unop<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                                 }
                             };
                             let not_cons = EvalErr::NotCons;
-                            return (err_tag, not_cons)
+                            let builtin_tag = Tag::Builtin;
+                            let builtin = digests.builtin_symbol_ptr("cdr");
+                            let err = store(not_cons, arg_tag, arg, builtin_tag, builtin);
+                            return (err_tag, err)
                         }
                         Op::Atom => {
                             match arg_tag {
@@ -398,7 +415,9 @@ pub fn eval_unop<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                                 }
                             };
                             let cant_open = EvalErr::CantOpen;
-                            return (err_tag, cant_open)
+                            let (builtin_tag, builtin) = call(op_builtin, expr_tag);
+                            let err = store(cant_open, arg_tag, arg, builtin_tag, builtin);
+                            return (err_tag, err)
                         }
                         Op::U64 => {
                             match arg_tag {
@@ -414,7 +433,10 @@ pub fn eval_unop<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                                 }
                             };
                             let err = EvalErr::CantCastToU64;
-                            return(err_tag, err)
+                            let builtin_tag = Tag::Builtin;
+                            let builtin = digests.builtin_symbol_ptr("u64");
+                            let err = store(err, arg_tag, arg, builtin_tag, builtin);
+                            return (err_tag, err)
                         }
                         Op::Emit => {
                             emit(arg_tag, arg);
@@ -431,7 +453,10 @@ pub fn eval_unop<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                                 }
                             };
                             let err = EvalErr::CantCastToComm;
-                            return(err_tag, err)
+                            let builtin_tag = Tag::Builtin;
+                            let builtin = digests.builtin_symbol_ptr("comm");
+                            let err = store(err, arg_tag, arg, builtin_tag, builtin);
+                            return (err_tag, err)
                         }
                         Op::Char => {
                             match arg_tag {
@@ -446,7 +471,10 @@ pub fn eval_unop<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                                 }
                             };
                             let err = EvalErr::CantCastToChar;
-                            return(err_tag, err)
+                            let builtin_tag = Tag::Builtin;
+                            let builtin = digests.builtin_symbol_ptr("char");
+                            let err = store(err, arg_tag, arg, builtin_tag, builtin);
+                            return (err_tag, err)
                         }
                     }
                 }
@@ -486,12 +514,16 @@ pub fn eval_binop<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                             let strcons = store(val1_tag, val1, val2_tag, val2);
                             let not_char = sub(val1_tag, char_tag);
                             let not_str = sub(val2_tag, str_tag);
+                            let builtin_tag = Tag::Builtin;
+                            let builtin = digests.builtin_symbol_ptr("strcons");
                             if not_char {
                                 let err = EvalErr::NotChar;
+                                let err = store(err, val1_tag, val1, builtin_tag, builtin);
                                 return (err_tag, err)
                             }
                             if not_str {
                                 let err = EvalErr::NotString;
+                                let err = store(err, val2_tag, val2, builtin_tag, builtin);
                                 return (err_tag, err)
                             }
                             return (str_tag, strcons)
@@ -513,7 +545,10 @@ pub fn eval_binop<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                                 }
                             };
                             let not_comm = EvalErr::NotBigNum;
-                            return (err_tag, not_comm)
+                            let builtin_tag = Tag::Builtin;
+                            let builtin = digests.builtin_symbol_ptr("hide");
+                            let err = store(not_comm, val1_tag, val1, builtin_tag, builtin);
+                            return (err_tag, err)
                         }
                         Op::Eq => {
                             let eq = call(equal_inner, val1_tag, val1, val2_tag, val2);
@@ -552,6 +587,7 @@ pub fn eval_binop_num<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F>
                     let err_tag = Tag::Err;
                     let num_tag = Tag::Num;
                     let u64_tag = Tag::U64;
+                    let cons_tag = Tag::Cons;
                     let err_div_zero = EvalErr::DivByZero;
                     let nil = digests.lurk_symbol_ptr("nil");
                     let nil_tag = InternalTag::Nil;
@@ -591,7 +627,11 @@ pub fn eval_binop_num<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                 Op::Div, Op::Mod => {
                                     let is_zero = call(u64_iszero, val2);
                                     if is_zero {
-                                        return (err_tag, err_div_zero)
+                                        let (builtin_tag, builtin) = call(op_builtin, expr_tag);
+                                        let args = store(val2_tag, val2, nil_tag, nil);
+                                        let args = store(val1_tag, val1, cons_tag, args);
+                                        let err = store(err_div_zero, cons_tag, args, builtin_tag, builtin);
+                                        return (err_tag, err)
                                     }
                                     let (quot, rem) = call(u64_divrem, val1, val2);
                                     match expr_tag {
@@ -657,7 +697,11 @@ pub fn eval_binop_num<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                 }
                                 Op::Div => {
                                     if !val2 {
-                                        return (err_tag, err_div_zero)
+                                        let (builtin_tag, builtin) = call(op_builtin, expr_tag);
+                                        let args = store(val2_tag, val2, nil_tag, nil);
+                                        let args = store(val1_tag, val1, cons_tag, args);
+                                        let err = store(err_div_zero, cons_tag, args, builtin_tag, builtin);
+                                        return (err_tag, err)
                                     }
                                     let res = div(val1, val2);
                                     return (num_tag, res)
@@ -671,6 +715,10 @@ pub fn eval_binop_num<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                 }
                                 Op::Mod, Op::Less, Op::Great, Op::LessEq, Op::GreatEq => {
                                     let err = EvalErr::NotU64;
+                                    let (builtin_tag, builtin) = call(op_builtin, expr_tag);
+                                    let args = store(val2_tag, val2, nil_tag, nil);
+                                    let args = store(val1_tag, val1, cons_tag, args);
+                                    let err = store(err, cons_tag, args, builtin_tag, builtin);
                                     return (err_tag, err)
                                 }
                             }
@@ -715,12 +763,20 @@ pub fn eval_binop_num<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F>
                                 }
                                 Op::Add, Op::Sub, Op::Mul, Op::Div, Op::Mod => {
                                     let err = EvalErr::InvalidArg;
+                                    let (builtin_tag, builtin) = call(op_builtin, expr_tag);
+                                    let args = store(val2_tag, val2, nil_tag, nil);
+                                    let args = store(val1_tag, val1, cons_tag, args);
+                                    let err = store(err, cons_tag, args, builtin_tag, builtin);
                                     return (err_tag, err)
                                 }
                             }
                         }
                     };
                     let err = EvalErr::InvalidArg;
+                    let (builtin_tag, builtin) = call(op_builtin, expr_tag);
+                    let args = store(val2_tag, val2, nil_tag, nil);
+                    let args = store(val1_tag, val1, cons_tag, args);
+                    let err = store(err, cons_tag, args, builtin_tag, builtin);
                     return (err_tag, err)
                 }
             }
@@ -728,7 +784,7 @@ pub fn eval_binop_num<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F>
     )
 }
 
-pub fn eval_op_misc<F: PrimeField32>() -> FuncE<F> {
+pub fn eval_op_misc<F: PrimeField32>(digests: &SymbolsDigests<F>) -> FuncE<F> {
     func!(
         partial fn eval_op_misc(expr_tag, expr, env): [2] {
             match expr_tag {
@@ -794,16 +850,35 @@ pub fn eval_op_misc<F: PrimeField32>() -> FuncE<F> {
                     let (res_tag, res) = call(eval, t_tag, t, env);
                     return (res_tag, res)
                 }
+                Op::Match => {
+                    let (val_tag, val, clauses_tag, clauses) = load(expr);
+                    let (val_tag, val) = call(eval, val_tag, val, env);
+                    match val_tag {
+                        Tag::Err => {
+                            return (val_tag, val)
+                        }
+                    };
+                    let (res_tag, res) = call(eval_match_clauses, val_tag, val, clauses_tag, clauses, env);
+                    return (res_tag, res)
+                }
                 Op::App, Op::Apply,
                 Op::And, Op::Or, Op::Not, Op::Eval, Op::Breakpoint => {
                     let err_tag = Tag::Err;
-                    let err = EvalErr::Todo;
+                    let nil_tag = InternalTag::Nil;
+                    let nil = digests.lurk_symbol_ptr("nil");
+                    let (builtin_tag, builtin) = call(op_builtin, expr_tag);
+                    let err = EvalErr::UnsupportedOp;
+                    let err = store(err, nil_tag, nil, builtin_tag, builtin);
                     return (err_tag, err)
                 }
                 Op::Eqq, Op::TypeEqq => {
                     // (might be compiled away)
                     let err_tag = Tag::Err;
-                    let err = EvalErr::Todo;
+                    let nil_tag = InternalTag::Nil;
+                    let nil = digests.lurk_symbol_ptr("nil");
+                    let (builtin_tag, builtin) = call(op_builtin, expr_tag);
+                    let err = EvalErr::UnexpectedOp;
+                    let err = store(err, nil_tag, nil, builtin_tag, builtin);
                     return (err_tag, err)
                 }
             }
@@ -811,6 +886,186 @@ pub fn eval_op_misc<F: PrimeField32>() -> FuncE<F> {
     )
 }
 
+/// Tries the compiled clauses of a `match` in order, choosing the first one whose
+/// pattern matches `val` and whose guard doesn't evaluate to `nil`
+pub fn eval_match_clauses<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
+    func!(
+        partial fn eval_match_clauses(val_tag, val, clauses_tag, clauses, env): [2] {
+            match clauses_tag {
+                InternalTag::Nil => {
+                    let err_tag = Tag::Err;
+                    let no_match = EvalErr::NoMatch;
+                    let builtin_tag = Tag::Builtin;
+                    let builtin = digests.builtin_symbol_ptr("match");
+                    let err = store(no_match, val_tag, val, builtin_tag, builtin);
+                    return (err_tag, err)
+                }
+            };
+            let (pat_tag, pat, guard_tag, guard, body_tag, body, clauses_tag, clauses) = load(clauses);
+            let (ext_env_tag, ext_env) = call(match_pattern, pat_tag, pat, val_tag, val, env);
+            match ext_env_tag {
+                Tag::Env => {
+                    let (guard_tag, guard) = call(eval, guard_tag, guard, ext_env);
+                    match guard_tag {
+                        Tag::Err => {
+                            return (guard_tag, guard)
+                        }
+                        InternalTag::Nil => {
+                            let (res_tag, res) = call(eval_match_clauses, val_tag, val, clauses_tag, clauses, env);
+                            return (res_tag, res)
+                        }
+                    };
+                    let (res_tag, res) = call(eval, body_tag, body, ext_env);
+                    return (res_tag, res)
+                }
+            };
+            let (res_tag, res) = call(eval_match_clauses, val_tag, val, clauses_tag, clauses, env);
+            return (res_tag, res)
+        }
+    )
+}
+
+/// Matches `val` against a pattern compiled by `compile_pattern`, returning `env`
+/// extended with the bound variables, tagged as an environment, if it matches or
+/// `nil` otherwise
+pub fn match_pattern<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
+    func!(
+        fn match_pattern(pat_tag, pat, val_tag, val, env): [2] {
+            let env_tag = Tag::Env;
+            let nil_tag = InternalTag::Nil;
+            let nil = digests.lurk_symbol_ptr("nil");
+            match pat_tag {
+                Pat::Wildcard => {
+                    return (env_tag, env)
+                }
+                Pat::Var => {
+                    let (var_tag, var) = load(pat);
+                    let ext_env = store(var_tag, var, val_tag, val, env);
+                    return (env_tag, ext_env)
+                }
+                Pat::Literal => {
+                    let (datum_tag, datum) = load(pat);
+                    let eq = call(equal_inner, datum_tag, datum, val_tag, val);
+                    if eq {
+                        return (env_tag, env)
+                    }
+                    return (nil_tag, nil)
+                }
+                Pat::Cons => {
+                    match val_tag {
+                        Tag::Cons => {
+                            let (car_pat_tag, car_pat, cdr_pat_tag, cdr_pat) = load(pat);
+                            let (car_tag, car, cdr_tag, cdr) = load(val);
+                            let (res_tag, res) = call(match_pattern, car_pat_tag, car_pat, car_tag, car, env);
+                            match res_tag {
+                                Tag::Env => {
+                                    let (res_tag, res) = call(match_pattern, cdr_pat_tag, cdr_pat, cdr_tag, cdr, res);
+                                    return (res_tag, res)
+                                }
+                            };
+                            return (res_tag, res)
+                        }
+                    };
+                    return (nil_tag, nil)
+                }
+                Pat::TypeEqq => {
+                    let (type_tag, inner_tag, inner) = load(pat);
+                    let val_type_tag = call(coerce_if_sym, val_tag);
+                    let type_not_eqq = sub(type_tag, val_type_tag);
+                    if type_not_eqq {
+                        return (nil_tag, nil)
+                    }
+                    let (res_tag, res) = call(match_pattern, inner_tag, inner, val_tag, val, env);
+                    return (res_tag, res)
+                }
+            }
+        }
+    )
+}
+
+/// Returns the builtin an operation was compiled from, to be carried by the errors
+/// it raises, or `nil` if there's no such builtin
+pub fn op_builtin<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
+    func!(
+        fn op_builtin(op): [2] {
+            let builtin_tag = Tag::Builtin;
+            match op {
+                Op::Open => {
+                    let builtin = digests.builtin_symbol_ptr("open");
+                    return (builtin_tag, builtin)
+                }
+                Op::Secret => {
+                    let builtin = digests.builtin_symbol_ptr("secret");
+                    return (builtin_tag, builtin)
+                }
+                Op::Add => {
+                    let builtin = digests.builtin_symbol_ptr("+");
+                    return (builtin_tag, builtin)
+                }
+                Op::Sub => {
+                    let builtin = digests.builtin_symbol_ptr("-");
+                    return (builtin_tag, builtin)
+                }
+                Op::Mul => {
+                    let builtin = digests.builtin_symbol_ptr("*");
+                    return (builtin_tag, builtin)
+                }
+                Op::Div => {
+                    let builtin = digests.builtin_symbol_ptr("/");
+                    return (builtin_tag, builtin)
+                }
+                Op::Mod => {
+                    let builtin = digests.builtin_symbol_ptr("%");
+                    return (builtin_tag, builtin)
+                }
+                Op::NumEq => {
+                    let builtin = digests.builtin_symbol_ptr("=");
+                    return (builtin_tag, builtin)
+                }
+                Op::Less => {
+                    let builtin = digests.builtin_symbol_ptr("<");
+                    return (builtin_tag, builtin)
+                }
+                Op::LessEq => {
+                    let builtin = digests.builtin_symbol_ptr("<=");
+                    return (builtin_tag, builtin)
+                }
+                Op::Great => {
+                    let builtin = digests.builtin_symbol_ptr(">");
+                    return (builtin_tag, builtin)
+                }
+                Op::GreatEq => {
+                    let builtin = digests.builtin_symbol_ptr(">=");
+                    return (builtin_tag, builtin)
+                }
+                Op::Apply => {
+                    let builtin = digests.builtin_symbol_ptr("apply");
+                    return (builtin_tag, builtin)
+                }
+                Op::Eval => {
+                    let builtin = digests.builtin_symbol_ptr("eval");
+                    return (builtin_tag, builtin)
+                }
+                Op::Breakpoint => {
+                    let builtin = digests.builtin_symbol_ptr("breakpoint");
+                    return (builtin_tag, builtin)
+                }
+                Op::Eqq => {
+                    let builtin = digests.builtin_symbol_ptr("eqq");
+                    return (builtin_tag, builtin)
+                }
+                Op::TypeEqq => {
+                    let builtin = digests.builtin_symbol_ptr("type-eqq");
+                    return (builtin_tag, builtin)
+                }
+            };
+            let nil_tag = InternalTag::Nil;
+            let nil = digests.lurk_symbol_ptr("nil");
+            return (nil_tag, nil)
+        }
+    )
+}
+
 pub fn equal_inner<F: AbstractField>() -> FuncE<F> {
     func!(
         fn equal_inner(a_tag, a, b_tag, b): [1] {
@@ -838,7 +1093,7 @@ pub fn equal_inner<F: AbstractField>() -> FuncE<F> {
                     }
                     return one
                 }
-                Tag::Key, Tag::Sym, Tag::Builtin, Tag::Coroutine, Tag::U64, Tag::BigNum, Tag::Comm => {
+                Tag::Key, Tag::Sym, Tag::Builtin, Tag::Coroutine, Tag::U64, Tag::I64, Tag::Decimal, Tag::BigNum, Tag::Comm => {
                     let a_digest: [8] = load(a);
                     let b_digest: [8] = load(b);
                     let diff = sub(a_digest, b_digest);
@@ -847,7 +1102,7 @@ pub fn equal_inner<F: AbstractField>() -> FuncE<F> {
                     }
                     return one
                 }
-                Tag::Str => {
+                Tag::Str, Tag::Vec, Tag::Map => {
                     let a_and_b = mul(a, b);
                     if !a_and_b {
                         return zero
@@ -891,12 +1146,18 @@ pub fn equal_inner<F: AbstractField>() -> FuncE<F> {
     )
 }
 
-pub fn env_lookup<F: AbstractField>() -> FuncE<F> {
+pub fn env_lookup<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
     func!(
         fn env_lookup(x_tag_digest: [9], env): [2] {
             if !env {
+                // the unbound symbol is the payload of the error
+                let (x_tag, x_digest: [8]) = x_tag_digest;
+                let x = store(x_digest);
+                let nil_tag = InternalTag::Nil;
+                let nil = digests.lurk_symbol_ptr("nil");
                 let err_tag = Tag::Err;
                 let err = EvalErr::UnboundVar;
+                let err = store(err, x_tag, x, nil_tag, nil);
                 return (err_tag, err)
             }
             let (y_tag, y, val_tag, val, tail_env) = load(env);
@@ -975,6 +1236,9 @@ mod test {
         let eval_op_misc = FuncChip::from_name("eval_op_misc", toplevel);
         let extend_env_with_mutuals = FuncChip::from_name("extend_env_with_mutuals", toplevel);
         let eval_mutual_bindings = FuncChip::from_name("eval_mutual_bindings", toplevel);
+        let eval_match_clauses = FuncChip::from_name("eval_match_clauses", toplevel);
+        let match_pattern = FuncChip::from_name("match_pattern", toplevel);
+        let op_builtin = FuncChip::from_name("op_builtin", toplevel);
         let equal_inner = FuncChip::from_name("equal_inner", toplevel);
 
         let expect_eq = |computed: usize, expected: Expect| {
@@ -983,12 +1247,15 @@ mod test {
         expect_eq(lurk_main.width(), expect!["114"]);
         expect_eq(eval.width(), expect!["73"]);
         expect_eq(apply.width(), expect!["105"]);
-        expect_eq(eval_op_misc.width(), expect!["81"]);
+        expect_eq(eval_op_misc.width(), expect!["83"]);
         expect_eq(eval_unop.width(), expect!["122"]);
         expect_eq(eval_binop.width(), expect!["119"]);
-        expect_eq(eval_binop_num.width(), expect!["120"]);
+        expect_eq(eval_binop_num.width(), expect!["133"]);
         expect_eq(extend_env_with_mutuals.width(), expect!["30"]);
         expect_eq(eval_mutual_bindings.width(), expect!["66"]);
-        expect_eq(equal_inner.width(), expect!["58"]);
+        expect_eq(eval_match_clauses.width(), expect!["80"]);
+        expect_eq(match_pattern.width(), expect!["43"]);
+        expect_eq(op_builtin.width(), expect!["41"]);
+        expect_eq(equal_inner.width(), expect!["60"]);
     }
 }
@@ -0,0 +1,173 @@
+use std::array;
+use std::borrow::{Borrow, BorrowMut};
+
+use p3_air::AirBuilder;
+use p3_field::PrimeField32;
+
+use crate::{
+    air::builder::{LookupBuilder, Record, RequireRecord},
+    gadgets::{
+        bytes::{builder::BytesAirRecordWithContext, record::DummyBytesRecord},
+        signed::SignWitness,
+        unsigned::Word64,
+    },
+    lair::{chipset::Chipset, execute::QueryRecord},
+};
+
+/// Operations on signed 64-bit integers that can't be expressed in terms of
+/// the `U64` chipset. The other ones, such as addition, reuse the unsigned
+/// operations on the two's complement representation.
+#[derive(Clone)]
+pub enum I64 {
+    IsNegative,
+}
+
+fn into_i64<F: PrimeField32>(slice: &[F]) -> i64 {
+    assert_eq!(slice.len(), 8);
+    let buf: [u8; 8] = array::from_fn(|i| slice[i].as_canonical_u32().try_into().unwrap());
+    i64::from_le_bytes(buf)
+}
+
+impl<F: PrimeField32> Chipset<F> for I64 {
+    fn input_size(&self) -> usize {
+        match self {
+            I64::IsNegative => 8,
+        }
+    }
+
+    fn output_size(&self) -> usize {
+        match self {
+            I64::IsNegative => 1,
+        }
+    }
+
+    fn witness_size(&self) -> usize {
+        match self {
+            I64::IsNegative => SignWitness::<F>::witness_size(),
+        }
+    }
+
+    fn require_size(&self) -> usize {
+        match self {
+            I64::IsNegative => SignWitness::<F>::num_requires(),
+        }
+    }
+
+    fn execute(
+        &self,
+        input: &[F],
+        nonce: u32,
+        queries: &mut QueryRecord<F>,
+        requires: &mut Vec<Record>,
+    ) -> Vec<F> {
+        let in1 = into_i64(input);
+        let bytes = &mut queries.bytes.context(nonce, requires);
+        match self {
+            I64::IsNegative => {
+                let mut witness = SignWitness::<F>::default();
+                witness.populate(&in1, bytes);
+                witness.iter_result().into_iter().collect()
+            }
+        }
+    }
+
+    fn populate_witness(&self, input: &[F], witness: &mut [F]) -> Vec<F> {
+        let in1 = into_i64(input);
+        let bytes = &mut DummyBytesRecord;
+        match self {
+            I64::IsNegative => {
+                let witness: &mut SignWitness<F> = witness.borrow_mut();
+                witness.populate(&in1, bytes);
+                witness.iter_result().into_iter().collect()
+            }
+        }
+    }
+
+    fn eval<AB: AirBuilder<F = F> + LookupBuilder>(
+        &self,
+        builder: &mut AB,
+        is_real: AB::Expr,
+        ins: Vec<AB::Expr>,
+        witness: &[AB::Var],
+        nonce: AB::Expr,
+        requires: &[RequireRecord<AB::Var>],
+    ) -> Vec<AB::Expr> {
+        let in1 = ins.into_iter().collect::<Word64<_>>();
+        let mut air_record = BytesAirRecordWithContext::default();
+        let out = match self {
+            I64::IsNegative => {
+                let witness: &SignWitness<AB::Var> = witness.borrow();
+                let is_negative = witness.eval(&in1, &mut air_record, is_real);
+                vec![is_negative]
+            }
+        };
+        air_record.require_all(builder, nonce, requires.iter().cloned());
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use p3_baby_bear::BabyBear as F;
+    use p3_field::AbstractField;
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig, StarkMachine};
+
+    use crate::{
+        air::debug::debug_chip_constraints_and_queries_with_sharding,
+        core::chipset::lurk_chip_map_native,
+        func,
+        lair::{
+            execute::{QueryRecord, Shard},
+            func_chip::FuncChip,
+            lair_chip::{build_chip_vector, build_lair_chip_vector, LairMachineProgram},
+            toplevel::Toplevel,
+        },
+    };
+
+    #[test]
+    fn i64_isneg_test() {
+        sp1_core_machine::utils::setup_logger();
+
+        let isneg_func = func!(
+        fn isneg(a: [8], b: [8]): [2] {
+            let c = extern_call(i64_isneg, a);
+            let d = extern_call(i64_isneg, b);
+            return (c, d)
+        });
+        let lurk_chip_map = lurk_chip_map_native();
+        let toplevel = Toplevel::new(&[isneg_func], lurk_chip_map);
+
+        let isneg_chip = FuncChip::from_name("isneg", &toplevel);
+        let mut queries = QueryRecord::new(&toplevel);
+        let f = F::from_canonical_u8;
+        // Little endian
+        let args = [(-2i64).to_le_bytes(), 2i64.to_le_bytes()]
+            .concat()
+            .into_iter()
+            .map(f)
+            .collect::<Vec<_>>();
+        let out = toplevel
+            .execute_by_name("isneg", &args, &mut queries, None)
+            .unwrap();
+        assert_eq!(out.as_ref(), &[F::one(), F::zero()]);
+
+        let lair_chips = build_lair_chip_vector(&isneg_chip);
+        let queries = Arc::new(queries);
+        debug_chip_constraints_and_queries_with_sharding(&queries, &lair_chips, None);
+
+        let config = BabyBearPoseidon2::new();
+        let machine = StarkMachine::new(
+            config,
+            build_chip_vector(&isneg_chip),
+            queries.expect_public_values().len(),
+            true,
+        );
+
+        let (pk, _vk) = machine.setup(&LairMachineProgram);
+        let shard = Shard::new_arc(&queries);
+        let mut challenger = machine.config().challenger();
+        machine.debug_constraints(&pk, shard, &mut challenger);
+    }
+}
@@ -1,7 +1,7 @@
 use p3_baby_bear::BabyBear;
 use p3_field::AbstractField;
 use rustc_hash::FxHashSet;
-use strum::{EnumCount, EnumIter};
+use strum::{EnumCount, EnumIter, IntoEnumIterator};
 
 use crate::{
     func,
@@ -13,7 +13,8 @@ use crate::{
 
 use super::{
     chipset::LurkChip,
-    state::{builtin_sym, lurk_sym, BUILTIN_SYMBOLS, LURK_SYMBOLS},
+    error::EvalErr,
+    state::{builtin_sym, lurk_sym, user_sym, BUILTIN_SYMBOLS, LURK_SYMBOLS},
     symbol::Symbol,
     tag::Tag,
     zstore::{ZStore, DIGEST_SIZE},
@@ -47,6 +48,11 @@ impl SymbolsDigests<BabyBear> {
             assert_eq!(zptr.tag, Tag::Builtin);
             map.insert(symbol, zptr.digest.into());
         }
+        // the wildcard of `match` patterns, which is compared against by pointer
+        let symbol = user_sym("_");
+        let zptr = zstore.intern_symbol(&symbol, lang_symbols);
+        assert_eq!(zptr.tag, Tag::Sym);
+        map.insert(symbol, zptr.digest.into());
         for symbol in lang_symbols {
             let zptr = zstore.intern_symbol(symbol, lang_symbols);
             assert_eq!(zptr.tag, Tag::Coroutine);
@@ -88,6 +94,9 @@ impl<F> SymbolsDigests<F> {
 pub enum InternalTag {
     Nil = 0,
     T,
+    /// Marks the values of a `recur` on their way to the enclosing `loop`. It never
+    /// escapes the evaluation of the `loop`
+    Recur,
 }
 
 impl InternalTag {
@@ -108,11 +117,20 @@ impl InternalTag {
 ///     let addr = <symbol 1 address>;
 ///     assert_eq!(ptr, addr);
 ///     ...
+///     let nil_tag = InternalTag::Nil;
+///     let nil = <nil address>;
+///     let code = <error 0 code>;
+///     let ptr = store(code, nil_tag, nil, nil_tag, nil);
+///     assert_eq!(ptr, code);
+///     ...
 ///     return
 /// }
 /// ```
+///
+/// The payload-less error records are allocated after the symbols, making each
+/// `EvalErr` code a pointer to its own record.
 pub fn preallocate_symbols<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
-    let mut ops = Vec::with_capacity(2 * digests.0.len());
+    let mut ops = Vec::with_capacity(4 * digests.0.len() + 3 * EvalErr::iter().len() + 2);
     let name = Ident::User("arr");
     let arr_var = Var {
         name,
@@ -127,6 +145,20 @@ pub fn preallocate_symbols<F: AbstractField>(digests: &SymbolsDigests<F>) -> Fun
         ops.push(OpE::Const(addr_var, addr));
         ops.push(OpE::AssertEq(ptr_var, addr_var, None));
     }
+    let nil_tag_var = Var::atom("nil_tag");
+    let nil_var = Var::atom("nil");
+    let code_var = Var::atom("code");
+    ops.push(OpE::Const(nil_tag_var, InternalTag::Nil.to_field()));
+    ops.push(OpE::Const(
+        nil_var,
+        digests.lurk_symbol_ptr("nil").to_field(),
+    ));
+    for err in EvalErr::iter() {
+        let args = [code_var, nil_tag_var, nil_var, nil_tag_var, nil_var];
+        ops.push(OpE::Const(code_var, err.to_field()));
+        ops.push(OpE::Store(ptr_var, args.into()));
+        ops.push(OpE::AssertEq(ptr_var, code_var, None));
+    }
     let ops = ops.into();
     let ctrl = CtrlE::return_vars([]);
     FuncE {
@@ -159,7 +191,7 @@ pub fn ingress<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                     let ptr = store(bytes);
                     return (tag, ptr)
                 }
-                Tag::U64 => {
+                Tag::U64, Tag::I64, Tag::Decimal => {
                     range_u8!(digest);
                     let ptr = store(digest);
                     return (tag, ptr)
@@ -186,7 +218,7 @@ pub fn ingress<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                     let ptr = store(digest);
                     return (tag, ptr)
                 }
-                Tag::Str => {
+                Tag::Str, Tag::Vec, Tag::Map => {
                     if !digest {
                         let zero = 0;
                         return (tag, zero)
@@ -231,6 +263,17 @@ pub fn ingress<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                     let ptr = store(var_tag, var_ptr, val_tag, val_ptr, env_ptr);
                     return (tag, ptr)
                 }
+                Tag::Err => {
+                    let (payload_tag_full: [8], payload_digest: [8],
+                         builtin_tag_full: [8], builtin_digest: [8],
+                                                code_digest:    [8]) = preimg(hash5, digest);
+                    let (payload_tag, payload_ptr) = call(ingress, payload_tag_full, payload_digest);
+                    let (builtin_tag, builtin_ptr) = call(ingress, builtin_tag_full, builtin_digest);
+                    let (code, rest: [7]) = code_digest;
+                    assert_eq!(rest, zeros);
+                    let ptr = store(code, payload_tag, payload_ptr, builtin_tag, builtin_ptr);
+                    return (tag, ptr)
+                }
             }
         }
     )
@@ -240,7 +283,7 @@ pub fn egress<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
     func!(
         fn egress(tag, val): [9] {
             match tag {
-                Tag::Num, Tag::Err => {
+                Tag::Num => {
                     let padding = [0; 7];
                     let digest: [8] = (val, padding);
                     return (tag, digest)
@@ -260,11 +303,11 @@ pub fn egress<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                     let digest = Array(digests.lurk_symbol_digest("t").clone());
                     return (sym_tag, digest)
                 }
-                Tag::Sym, Tag::Builtin, Tag::Coroutine, Tag::Key, Tag::U64, Tag::BigNum, Tag::Comm => {
+                Tag::Sym, Tag::Builtin, Tag::Coroutine, Tag::Key, Tag::U64, Tag::I64, Tag::Decimal, Tag::BigNum, Tag::Comm => {
                     let digest: [8] = load(val);
                     return (tag, digest)
                 }
-                Tag::Str => {
+                Tag::Str, Tag::Vec, Tag::Map => {
                     if !val {
                         let digest = [0; 8];
                         return (tag, digest)
@@ -319,6 +362,18 @@ pub fn egress<F: AbstractField>(digests: &SymbolsDigests<F>) -> FuncE<F> {
                     let digest: [8] = call(hash5, var_tag_full, var_digest, val_tag_full, val_digest, env_digest);
                     return (tag, digest)
                 }
+                Tag::Err => {
+                    let (code, payload_tag, payload_ptr, builtin_tag, builtin_ptr) = load(val);
+                    let (payload_tag, payload_digest: [8]) = call(egress, payload_tag, payload_ptr);
+                    let (builtin_tag, builtin_digest: [8]) = call(egress, builtin_tag, builtin_ptr);
+
+                    let padding = [0; 7];
+                    let payload_tag_full: [8] = (payload_tag, padding);
+                    let builtin_tag_full: [8] = (builtin_tag, padding);
+                    let code_digest: [8] = (code, padding);
+                    let digest: [8] = call(hash5, payload_tag_full, payload_digest, builtin_tag_full, builtin_digest, code_digest);
+                    return (tag, digest)
+                }
             }
         }
     )
@@ -0,0 +1,283 @@
+use std::array;
+use std::borrow::{Borrow, BorrowMut};
+
+use p3_air::AirBuilder;
+use p3_field::PrimeField32;
+
+use crate::{
+    air::builder::{LookupBuilder, Record, RequireRecord},
+    gadgets::{
+        bytes::{builder::BytesAirRecordWithContext, record::DummyBytesRecord},
+        keccak::{AbsorbWitness, RoundWitness, KECCAK_RATE_LANES},
+        unsigned::{Word, Word64},
+    },
+    lair::{chipset::Chipset, execute::QueryRecord},
+};
+
+/// The rounds of the Keccak-f[1600] permutation and the step of the sponge that absorbs blocks
+/// into its state. Lanes are passed around as their little-endian bytes, in the order
+/// of the state, which is the order in which they appear in messages and digests.
+#[derive(Clone)]
+pub enum Keccak {
+    /// Xors a block of 136 bytes into the first lanes of the state
+    Absorb,
+    /// Applies a round of the permutation to the state, given the round constant
+    Round,
+}
+
+/// The round constants of Keccak-f[1600]
+pub(crate) const KECCAK_RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+fn into_u64s<F: PrimeField32, const N: usize>(slice: &[F]) -> [u64; N] {
+    assert_eq!(slice.len(), 8 * N);
+    array::from_fn(|i| {
+        let buf: [u8; 8] =
+            array::from_fn(|j| slice[8 * i + j].as_canonical_u32().try_into().unwrap());
+        u64::from_le_bytes(buf)
+    })
+}
+
+fn from_u64s<F: PrimeField32>(lanes: &[u64]) -> Vec<F> {
+    lanes
+        .iter()
+        .flat_map(|lane| lane.to_le_bytes())
+        .map(F::from_canonical_u8)
+        .collect()
+}
+
+fn into_words<Expr: Clone, const N: usize>(ins: &[Expr]) -> [Word64<Expr>; N] {
+    assert_eq!(ins.len(), 8 * N);
+    array::from_fn(|i| Word::from_fn(|j| ins[8 * i + j].clone()))
+}
+
+fn from_words<Var: Into<Expr>, Expr>(words: impl IntoIterator<Item = Word64<Var>>) -> Vec<Expr> {
+    words
+        .into_iter()
+        .flat_map(|word| word.into_array().into_iter().map(Into::into))
+        .collect()
+}
+
+impl<F: PrimeField32> Chipset<F> for Keccak {
+    fn input_size(&self) -> usize {
+        match self {
+            Keccak::Absorb => 200 + 8 * KECCAK_RATE_LANES,
+            Keccak::Round => 208,
+        }
+    }
+
+    fn output_size(&self) -> usize {
+        200
+    }
+
+    fn witness_size(&self) -> usize {
+        match self {
+            Keccak::Absorb => AbsorbWitness::<F>::witness_size(),
+            Keccak::Round => RoundWitness::<F>::witness_size(),
+        }
+    }
+
+    fn require_size(&self) -> usize {
+        match self {
+            Keccak::Absorb => AbsorbWitness::<F>::num_requires(),
+            Keccak::Round => RoundWitness::<F>::num_requires(),
+        }
+    }
+
+    fn execute(
+        &self,
+        input: &[F],
+        nonce: u32,
+        queries: &mut QueryRecord<F>,
+        requires: &mut Vec<Record>,
+    ) -> Vec<F> {
+        let bytes = &mut queries.bytes.context(nonce, requires);
+        let state = into_u64s(&input[..200]);
+        let out = match self {
+            Keccak::Absorb => {
+                let mut witness = AbsorbWitness::<F>::default();
+                witness.populate(state, into_u64s(&input[200..]), bytes)
+            }
+            Keccak::Round => {
+                let mut witness = RoundWitness::<F>::default();
+                let [rc] = into_u64s(&input[200..]);
+                witness.populate(state, rc, bytes)
+            }
+        };
+        from_u64s(&out)
+    }
+
+    fn populate_witness(&self, input: &[F], witness: &mut [F]) -> Vec<F> {
+        let bytes = &mut DummyBytesRecord;
+        let state = into_u64s(&input[..200]);
+        let out = match self {
+            Keccak::Absorb => {
+                let witness: &mut AbsorbWitness<F> = witness.borrow_mut();
+                witness.populate(state, into_u64s(&input[200..]), bytes)
+            }
+            Keccak::Round => {
+                let witness: &mut RoundWitness<F> = witness.borrow_mut();
+                let [rc] = into_u64s(&input[200..]);
+                witness.populate(state, rc, bytes)
+            }
+        };
+        from_u64s(&out)
+    }
+
+    fn eval<AB: AirBuilder<F = F> + LookupBuilder>(
+        &self,
+        builder: &mut AB,
+        is_real: AB::Expr,
+        ins: Vec<AB::Expr>,
+        witness: &[AB::Var],
+        nonce: AB::Expr,
+        requires: &[RequireRecord<AB::Var>],
+    ) -> Vec<AB::Expr> {
+        let mut air_record = BytesAirRecordWithContext::default();
+        let state = into_words(&ins[..200]);
+        let out = match self {
+            Keccak::Absorb => {
+                let witness: &AbsorbWitness<AB::Var> = witness.borrow();
+                let block = into_words(&ins[200..]);
+                from_words(witness.eval(&state, &block, &mut air_record, is_real))
+            }
+            Keccak::Round => {
+                let witness: &RoundWitness<AB::Var> = witness.borrow();
+                let [rc] = into_words(&ins[200..]);
+                from_words(witness.eval(&state, &rc, &mut air_record, is_real))
+            }
+        };
+        air_record.require_all(builder, nonce, requires.iter().cloned());
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use p3_baby_bear::BabyBear as F;
+    use p3_field::AbstractField;
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig, StarkMachine};
+
+    use crate::{
+        air::debug::debug_chip_constraints_and_queries_with_sharding,
+        core::chipset::lurk_chip_map_native,
+        func,
+        lair::{
+            execute::{QueryRecord, Shard},
+            func_chip::FuncChip,
+            lair_chip::{build_chip_vector, build_lair_chip_vector, LairMachineProgram},
+            toplevel::Toplevel,
+        },
+    };
+
+    use super::KECCAK_RC;
+
+    #[test]
+    fn keccak_steps_test() {
+        sp1_core_machine::utils::setup_logger();
+
+        let steps_func = func!(
+        fn steps(state: [200], block: [136], rc: [8]): [400] {
+            let absorbed: [200] = extern_call(keccak_absorber, state, block);
+            let state: [200] = extern_call(keccak_permuter, absorbed, rc);
+            return (absorbed, state)
+        });
+        let lurk_chip_map = lurk_chip_map_native();
+        let toplevel = Toplevel::new(&[steps_func], lurk_chip_map);
+
+        let steps_chip = FuncChip::from_name("steps", &toplevel);
+        let mut queries = QueryRecord::new(&toplevel);
+        // the zero state and the padded empty message
+        let mut block = [0u8; 136];
+        block[0] = 0x01;
+        block[135] = 0x80;
+        let args = [0u8; 200]
+            .iter()
+            .chain(&block)
+            .chain(&KECCAK_RC[0].to_le_bytes())
+            .map(|byte| F::from_canonical_u8(*byte))
+            .collect::<Vec<_>>();
+        let out = toplevel
+            .execute_by_name("steps", &args, &mut queries, None)
+            .unwrap();
+        // the first round of the permutation after absorbing the block
+        let round: [u64; 25] = [
+            0x40000000001,
+            0x100000000000,
+            0x40000008000,
+            0,
+            0x100000008000,
+            0x8,
+            0x300000200000,
+            0x1000000000000008,
+            0x300000000000,
+            0x1000000000200000,
+            0x2,
+            0x220,
+            0x40000,
+            0x202,
+            0x40020,
+            0x10000400,
+            0x1000004000,
+            0x400,
+            0x10004000,
+            0x1000000000,
+            0x2000010000000000,
+            0x20000000000,
+            0x10000000004,
+            0x2000020000000000,
+            0x4,
+        ];
+        let expected = block
+            .iter()
+            .chain(&[0u8; 64])
+            .copied()
+            .chain(round.iter().flat_map(|lane| lane.to_le_bytes()))
+            .map(F::from_canonical_u8)
+            .collect::<Vec<_>>();
+        assert_eq!(out.as_ref(), expected);
+
+        let lair_chips = build_lair_chip_vector(&steps_chip);
+        let queries = Arc::new(queries);
+        debug_chip_constraints_and_queries_with_sharding(&queries, &lair_chips, None);
+
+        let config = BabyBearPoseidon2::new();
+        let machine = StarkMachine::new(
+            config,
+            build_chip_vector(&steps_chip),
+            queries.expect_public_values().len(),
+            true,
+        );
+
+        let (pk, _vk) = machine.setup(&LairMachineProgram);
+        let shard = Shard::new_arc(&queries);
+        let mut challenger = machine.config().challenger();
+        machine.debug_constraints(&pk, shard, &mut challenger);
+    }
+}
@@ -1,6 +1,19 @@
 use p3_field::AbstractField;
 
-use crate::{func, lair::expr::FuncE};
+use crate::{
+    func,
+    gadgets::edwards::EXT_DEGREE,
+    lair::{
+        expr::{BlockE, CaseType, CasesE, CtrlE, FuncE, Ident, OpE, Var},
+        List, Name,
+    },
+};
+
+use super::{
+    keccak::KECCAK_RC,
+    sha256::SHA256_K,
+    signature::{EDWARDS_BASES, EDWARDS_ORDER},
+};
 
 pub fn hash3<F>() -> FuncE<F> {
     func!(
@@ -100,6 +113,260 @@ pub fn u64_iszero<F>() -> FuncE<F> {
     )
 }
 
+pub fn u64_and<F>() -> FuncE<F> {
+    func!(
+        fn u64_and(a, b): [1] {
+            let a: [8] = load(a);
+            let b: [8] = load(b);
+            let c: [8] = extern_call(u64_and, a, b);
+            let c = store(c);
+            return c
+        }
+    )
+}
+
+pub fn u64_or<F>() -> FuncE<F> {
+    func!(
+        fn u64_or(a, b): [1] {
+            let a: [8] = load(a);
+            let b: [8] = load(b);
+            let c: [8] = extern_call(u64_or, a, b);
+            let c = store(c);
+            return c
+        }
+    )
+}
+
+pub fn u64_xor<F>() -> FuncE<F> {
+    func!(
+        fn u64_xor(a, b): [1] {
+            let a: [8] = load(a);
+            let b: [8] = load(b);
+            let c: [8] = extern_call(u64_xor, a, b);
+            let c = store(c);
+            return c
+        }
+    )
+}
+
+pub fn u64_not<F>() -> FuncE<F> {
+    func!(
+        fn u64_not(a): [1] {
+            let a: [8] = load(a);
+            let b: [8] = extern_call(u64_not, a);
+            let b = store(b);
+            return b
+        }
+    )
+}
+
+pub fn u64_shl<F>() -> FuncE<F> {
+    func!(
+        fn u64_shl(a, b): [1] {
+            let a: [8] = load(a);
+            let b: [8] = load(b);
+            let c: [8] = extern_call(u64_shl, a, b);
+            let c = store(c);
+            return c
+        }
+    )
+}
+
+pub fn u64_shr<F>() -> FuncE<F> {
+    func!(
+        fn u64_shr(a, b): [1] {
+            let a: [8] = load(a);
+            let b: [8] = load(b);
+            let c: [8] = extern_call(u64_shr, a, b);
+            let c = store(c);
+            return c
+        }
+    )
+}
+
+pub fn i64_isneg<F>() -> FuncE<F> {
+    func!(
+        fn i64_isneg(a): [1] {
+            let a: [8] = load(a);
+            let b = extern_call(i64_isneg, a);
+            return b
+        }
+    )
+}
+
+/// Negates a two's complement integer if `neg` is non-zero
+pub fn i64_neg_if<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn i64_neg_if(a, neg): [1] {
+            if neg {
+                let a: [8] = load(a);
+                let zero = [0; 8];
+                let b: [8] = extern_call(u64_sub, zero, a);
+                let b = store(b);
+                return b
+            }
+            return a
+        }
+    )
+}
+
+pub fn i64_lessthan<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn i64_lessthan(a, b): [1] {
+            let a_neg = call(i64_isneg, a);
+            let b_neg = call(i64_isneg, b);
+            let diff_signs = sub(a_neg, b_neg);
+            if diff_signs {
+                return a_neg
+            }
+            // the unsigned comparison is correct for operands with the same sign
+            let c = call(u64_lessthan, a, b);
+            return c
+        }
+    )
+}
+
+/// Truncated division, like Rust's `/` and `%` on `i64`: the quotient is rounded
+/// towards zero and the remainder has the sign of the dividend. The only overflowing
+/// case, `i64::MIN / -1`, wraps like `i64::wrapping_div` and `i64::wrapping_rem`, giving
+/// `i64::MIN` and `0`, consistently with the wrapping `+`, `-` and `*` on `i64`s
+pub fn i64_divrem<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn i64_divrem(a, b): [2] {
+            let a_neg = call(i64_isneg, a);
+            let b_neg = call(i64_isneg, b);
+            let a_abs = call(i64_neg_if, a, a_neg);
+            let b_abs = call(i64_neg_if, b, b_neg);
+            let (q, r) = call(u64_divrem, a_abs, b_abs);
+            let q_neg = sub(a_neg, b_neg);
+            let q = call(i64_neg_if, q, q_neg);
+            let r = call(i64_neg_if, r, a_neg);
+            return (q, r)
+        }
+    )
+}
+
+/// Adds `x` to the remainder `r` modulo `b`, carrying into the quotient `q`.
+/// Requires `r < b` and `x < b`, so the sum never overflows
+pub fn u64_add_mod<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn u64_add_mod(q, r, x, b): [2] {
+            let room = call(u64_sub, b, r);
+            let fits = call(u64_lessthan, x, room);
+            if fits {
+                let r = call(u64_add, r, x);
+                return (q, r)
+            }
+            let one = [1, 0, 0, 0, 0, 0, 0, 0];
+            let one = store(one);
+            let q = call(u64_add, q, one);
+            let r = call(u64_sub, x, room);
+            return (q, r)
+        }
+    )
+}
+
+/// Computes the quotient and remainder of `a * s` divided by `b` without
+/// overflowing on the product. Requires `a < b`, recursing over the bits of `s`
+pub fn u64_mul_divrem<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn u64_mul_divrem(a, s, b): [2] {
+            let s_zero = call(u64_iszero, s);
+            if s_zero {
+                return (s, s)
+            }
+            let two = [2, 0, 0, 0, 0, 0, 0, 0];
+            let two = store(two);
+            let (half, bit) = call(u64_divrem, s, two);
+            // `a * s = 2a * half + bit * a`, where `2a = carry * b + a2`
+            let zero = [0; 8];
+            let zero = store(zero);
+            let (carry, a2) = call(u64_add_mod, zero, a, a, b);
+            let (q, r) = call(u64_mul_divrem, a2, half, b);
+            let carried = call(u64_mul, carry, half);
+            let q = call(u64_add, q, carried);
+            let bit_zero = call(u64_iszero, bit);
+            if bit_zero {
+                return (q, r)
+            }
+            let (q, r) = call(u64_add_mod, q, r, a, b);
+            return (q, r)
+        }
+    )
+}
+
+/// Multiplies two decimals scaled by 10^6, truncating the result towards zero
+pub fn decimal_mul<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn decimal_mul(a, b): [1] {
+            let a_neg = call(i64_isneg, a);
+            let b_neg = call(i64_isneg, b);
+            let a_abs = call(i64_neg_if, a, a_neg);
+            let b_abs = call(i64_neg_if, b, b_neg);
+            let scale = [64, 66, 15, 0, 0, 0, 0, 0]; // 10^6
+            let scale = store(scale);
+            // splitting the operands avoids overflowing on the unscaled product
+            let (a_int, a_frac) = call(u64_divrem, a_abs, scale);
+            let (b_int, b_frac) = call(u64_divrem, b_abs, scale);
+            let int = call(u64_mul, a_int, b_int);
+            let int = call(u64_mul, int, scale);
+            let a_cross = call(u64_mul, a_int, b_frac);
+            let b_cross = call(u64_mul, a_frac, b_int);
+            let frac = call(u64_mul, a_frac, b_frac);
+            let (frac, _rem) = call(u64_divrem, frac, scale);
+            let c = call(u64_add, int, a_cross);
+            let c = call(u64_add, c, b_cross);
+            let c = call(u64_add, c, frac);
+            let c_neg = sub(a_neg, b_neg);
+            let c = call(i64_neg_if, c, c_neg);
+            return c
+        }
+    )
+}
+
+/// Divides two decimals scaled by 10^6, truncating the result towards zero.
+/// Requires a non-zero divisor
+pub fn decimal_div<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn decimal_div(a, b): [1] {
+            let a_neg = call(i64_isneg, a);
+            let b_neg = call(i64_isneg, b);
+            let a_abs = call(i64_neg_if, a, a_neg);
+            let b_abs = call(i64_neg_if, b, b_neg);
+            let scale = [64, 66, 15, 0, 0, 0, 0, 0]; // 10^6
+            let scale = store(scale);
+            let (q, r) = call(u64_divrem, a_abs, b_abs);
+            let int = call(u64_mul, q, scale);
+            let (frac, _rem) = call(u64_mul_divrem, r, scale, b_abs);
+            let c = call(u64_add, int, frac);
+            let c_neg = sub(a_neg, b_neg);
+            let c = call(i64_neg_if, c, c_neg);
+            return c
+        }
+    )
+}
+
+/// Returns the decimal with integer magnitude `abs`, negated if `neg` is non-zero, as
+/// `(ok, res)`. Decimals are `i64`s scaled by 10^6, so `ok` is zero if `abs` is above
+/// 9223372036854, in which case `res` is just `abs`
+pub fn decimal_from_int<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn decimal_from_int(abs, neg): [2] {
+            let bound = [247, 90, 208, 123, 99, 8, 0, 0]; // 9223372036855
+            let bound = store(bound);
+            let ok = call(u64_lessthan, abs, bound);
+            if ok {
+                let scale = [64, 66, 15, 0, 0, 0, 0, 0]; // 10^6
+                let scale = store(scale);
+                let res = call(u64_mul, abs, scale);
+                let res = call(i64_neg_if, res, neg);
+                return (ok, res)
+            }
+            return (ok, abs)
+        }
+    )
+}
+
 pub fn digest_equal<F: AbstractField>() -> FuncE<F> {
     func!(
         fn digest_equal(a, b): [1] {
@@ -126,3 +393,639 @@ pub fn big_num_lessthan<F>() -> FuncE<F> {
         }
     )
 }
+
+pub fn big_num_add<F>() -> FuncE<F> {
+    func!(
+        fn big_num_add(a, b): [1] {
+            let a: [8] = load(a);
+            let b: [8] = load(b);
+            let c: [8] = extern_call(big_num_add, a, b);
+            let c = store(c);
+            return c
+        }
+    )
+}
+
+pub fn big_num_sub<F>() -> FuncE<F> {
+    func!(
+        fn big_num_sub(a, b): [1] {
+            let a: [8] = load(a);
+            let b: [8] = load(b);
+            let c: [8] = extern_call(big_num_sub, a, b);
+            let c = store(c);
+            return c
+        }
+    )
+}
+
+pub fn big_num_mul<F>() -> FuncE<F> {
+    func!(
+        fn big_num_mul(a, b): [1] {
+            let a: [8] = load(a);
+            let b: [8] = load(b);
+            let c: [8] = extern_call(big_num_mul, a, b);
+            let c = store(c);
+            return c
+        }
+    )
+}
+
+pub fn big_num_divrem<F>() -> FuncE<F> {
+    func!(
+        fn big_num_divrem(a, b): [2] {
+            let a: [8] = load(a);
+            let b: [8] = load(b);
+            let (q: [8], r: [8]) = extern_call(big_num_divrem, a, b);
+            let q = store(q);
+            let r = store(r);
+            return (q, r)
+        }
+    )
+}
+
+pub fn big_num_mulmod<F>() -> FuncE<F> {
+    func!(
+        fn big_num_mulmod(a, b, m): [1] {
+            let a: [8] = load(a);
+            let b: [8] = load(b);
+            let m: [8] = load(m);
+            let c: [8] = extern_call(big_num_mulmod, a, b, m);
+            let c = store(c);
+            return c
+        }
+    )
+}
+
+/// Computes `base^exp % modulus` by square-and-multiply over the bits of the exponent,
+/// from the most significant one. The modulus must be non-zero
+pub fn big_num_modexp<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn big_num_modexp(base, exp, modulus): [1] {
+            let exp_digits: [8] = load(exp);
+            if exp_digits {
+                let two = [2, 0, 0, 0, 0, 0, 0, 0];
+                let two = store(two);
+                let (half, bit) = call(big_num_divrem, exp, two);
+                let res = call(big_num_modexp, base, half, modulus);
+                let res = call(big_num_mulmod, res, res, modulus);
+                let bit: [8] = load(bit);
+                if bit {
+                    let res = call(big_num_mulmod, res, base, modulus);
+                    return res
+                }
+                return res
+            }
+            // reduce one, which is the result unless the modulus is one
+            let one = [1, 0, 0, 0, 0, 0, 0, 0];
+            let one = store(one);
+            let res = call(big_num_mulmod, one, one, modulus);
+            return res
+        }
+    )
+}
+
+/// Computes `base^exp` in the field by square-and-multiply over the bits of the
+/// `u64` exponent, from the most significant one
+pub fn num_pow<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn num_pow(base, exp): [1] {
+            let exp_digits: [8] = load(exp);
+            if exp_digits {
+                let two = [2, 0, 0, 0, 0, 0, 0, 0];
+                let two = store(two);
+                let (half, bit) = call(u64_divrem, exp, two);
+                let res = call(num_pow, base, half);
+                let res = mul(res, res);
+                let bit: [8] = load(bit);
+                if bit {
+                    let res = mul(res, base);
+                    return res
+                }
+                return res
+            }
+            let one = 1;
+            return one
+        }
+    )
+}
+
+/// Converts a field element into the `u64` of its canonical representative, which
+/// always fits in the lower four bytes
+pub fn num_to_u64<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn num_to_u64(x): [1] {
+            let bytes: [4] = extern_call(num_to_bytes, x);
+            let padding = [0; 4];
+            let res = store(bytes, padding);
+            return res
+        }
+    )
+}
+
+/// Stores the `u64` with the given big-endian bytes
+pub fn u64_from_be_bytes<F>() -> FuncE<F> {
+    func!(
+        fn u64_from_be_bytes(b7, b6, b5, b4, b3, b2, b1, b0): [1] {
+            let res = store(b0, b1, b2, b3, b4, b5, b6, b7);
+            return res
+        }
+    )
+}
+
+/// Returns the round constant `K_t` of SHA-256 as big-endian bytes
+///
+/// ```ignore
+/// fn sha256_k(t): [4] {
+///     match t {
+///         0 => {
+///             let k = [0x42, 0x8a, 0x2f, 0x98];
+///             return k
+///         }
+///         ...
+///         63 => {
+///             let k = [0xc6, 0x71, 0x78, 0xf2];
+///             return k
+///         }
+///     }
+/// }
+/// ```
+pub fn sha256_k<F: AbstractField>() -> FuncE<F> {
+    let t = Var::atom("t");
+    let k = Var {
+        name: Ident::User("k"),
+        size: 4,
+    };
+    let branches = SHA256_K
+        .iter()
+        .enumerate()
+        .map(|(i, k_i)| {
+            let bytes = k_i.to_be_bytes().map(F::from_canonical_u8);
+            let block = BlockE {
+                ops: [OpE::Array(k, bytes.into())].into(),
+                ctrl: CtrlE::return_vars([k]),
+            };
+            (
+                [F::from_canonical_usize(i)].into(),
+                (block, CaseType::Constrained),
+            )
+        })
+        .collect();
+    FuncE {
+        name: Name("sha256_k"),
+        invertible: false,
+        partial: false,
+        input_params: [t].into(),
+        output_size: 4,
+        body: BlockE::no_op(CtrlE::Match(t, CasesE::no_default(branches))),
+    }
+}
+
+/// Prepends `n` zero bytes to a list of bytes
+pub fn sha256_zeros<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn sha256_zeros(n, tail): [1] {
+            if n {
+                let zero = 0;
+                let one = 1;
+                let tail = store(zero, tail);
+                let n = sub(n, one);
+                let tail = call(sha256_zeros, n, tail);
+                return tail
+            }
+            return tail
+        }
+    )
+}
+
+/// Returns the SHA-256 padding of a message of `len` bytes as a list of bytes: a
+/// `0x80` marker, the zeros that leave room for 8 bytes before the end of a block
+/// and the big-endian bit length of the message. Lengths are assumed to be small
+/// enough for the bit length to fit in a field element
+pub fn sha256_padding<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn sha256_padding(len): [1] {
+            let len_u64 = call(num_to_u64, len);
+            let block_size = [64, 0, 0, 0, 0, 0, 0, 0];
+            let block_size = store(block_size);
+            let (_blocks, rem) = call(u64_divrem, len_u64, block_size);
+            let limit = [56, 0, 0, 0, 0, 0, 0, 0];
+            let limit = store(limit);
+            let fits = call(u64_lessthan, rem, limit);
+            let (rem, _rem_high: [7]) = load(rem);
+            // the padding spills into the next block if the bit length doesn't fit
+            // after the marker
+            let max_zeros = 119;
+            let num_zeros = sub(max_zeros, rem);
+            let block_size = 64;
+            let spared = mul(block_size, fits);
+            let num_zeros = sub(num_zeros, spared);
+            let eight = 8;
+            let bit_len = mul(len, eight);
+            let (l0, l1, l2, l3) = extern_call(num_to_bytes, bit_len);
+            let zero = 0;
+            let padding = store(l0, zero);
+            let padding = store(l1, padding);
+            let padding = store(l2, padding);
+            let padding = store(l3, padding);
+            let padding = store(zero, padding);
+            let padding = store(zero, padding);
+            let padding = store(zero, padding);
+            let padding = store(zero, padding);
+            let padding = call(sha256_zeros, num_zeros, padding);
+            let marker = 0x80;
+            let padding = store(marker, padding);
+            return padding
+        }
+    )
+}
+
+/// Reads a big-endian word from a list of bytes, returning the rest of the list
+pub fn sha256_word<F>() -> FuncE<F> {
+    func!(
+        fn sha256_word(msg): [5] {
+            let (b0, msg) = load(msg);
+            let (b1, msg) = load(msg);
+            let (b2, msg) = load(msg);
+            let (b3, msg) = load(msg);
+            return (b0, b1, b2, b3, msg)
+        }
+    )
+}
+
+/// Applies the rounds from `t` on to the state, where the window holds the message
+/// schedule words `w[t..t+16]`
+pub fn sha256_rounds<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn sha256_rounds(state: [32], window: [64], t): [32] {
+            let num_rounds = 64;
+            let rounds_left = sub(num_rounds, t);
+            if rounds_left {
+                let k: [4] = call(sha256_k, t);
+                let (w0: [4], w1: [4], w2_8: [28], w9: [4], w10_13: [16], w14: [4], w15: [4]) = window;
+                let (new_a: [4], new_e: [4]) = extern_call(sha256_round, state, k, w0);
+                let (a: [4], b: [4], c: [4], _d: [4], e: [4], f: [4], g: [4], _h: [4]) = state;
+                let state: [32] = (new_a, a, b, c, new_e, e, f, g);
+                let w16: [4] = extern_call(sha256_schedule, w0, w1, w9, w14);
+                let window: [64] = (w1, w2_8, w9, w10_13, w14, w15, w16);
+                let one = 1;
+                let t = add(t, one);
+                let state: [32] = call(sha256_rounds, state, window, t);
+                return state
+            }
+            return state
+        }
+    )
+}
+
+/// Compresses the blocks of a padded message, given as a list of bytes, into the state
+pub fn sha256_blocks<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn sha256_blocks(state: [32], msg): [32] {
+            if msg {
+                let (w0: [4], msg) = call(sha256_word, msg);
+                let (w1: [4], msg) = call(sha256_word, msg);
+                let (w2: [4], msg) = call(sha256_word, msg);
+                let (w3: [4], msg) = call(sha256_word, msg);
+                let (w4: [4], msg) = call(sha256_word, msg);
+                let (w5: [4], msg) = call(sha256_word, msg);
+                let (w6: [4], msg) = call(sha256_word, msg);
+                let (w7: [4], msg) = call(sha256_word, msg);
+                let (w8: [4], msg) = call(sha256_word, msg);
+                let (w9: [4], msg) = call(sha256_word, msg);
+                let (w10: [4], msg) = call(sha256_word, msg);
+                let (w11: [4], msg) = call(sha256_word, msg);
+                let (w12: [4], msg) = call(sha256_word, msg);
+                let (w13: [4], msg) = call(sha256_word, msg);
+                let (w14: [4], msg) = call(sha256_word, msg);
+                let (w15: [4], msg) = call(sha256_word, msg);
+                let window: [64] = (w0, w1, w2, w3, w4, w5, w6, w7, w8, w9, w10, w11, w12, w13, w14, w15);
+                let t = 0;
+                let compressed: [32] = call(sha256_rounds, state, window, t);
+                let state: [32] = extern_call(sha256_add_state, state, compressed);
+                let state: [32] = call(sha256_blocks, state, msg);
+                return state
+            }
+            return state
+        }
+    )
+}
+
+/// Returns the round constant of Keccak-f[1600] for round `i` as little-endian bytes
+///
+/// ```ignore
+/// fn keccak_rc(i): [8] {
+///     match i {
+///         0 => {
+///             let rc = [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+///             return rc
+///         }
+///         ...
+///         23 => {
+///             let rc = [0x08, 0x80, 0x00, 0x80, 0x00, 0x00, 0x00, 0x80];
+///             return rc
+///         }
+///     }
+/// }
+/// ```
+pub fn keccak_rc<F: AbstractField>() -> FuncE<F> {
+    let i = Var::atom("i");
+    let rc = Var {
+        name: Ident::User("rc"),
+        size: 8,
+    };
+    let branches = KECCAK_RC
+        .iter()
+        .enumerate()
+        .map(|(j, rc_j)| {
+            let bytes = rc_j.to_le_bytes().map(F::from_canonical_u8);
+            let block = BlockE {
+                ops: [OpE::Array(rc, bytes.into())].into(),
+                ctrl: CtrlE::return_vars([rc]),
+            };
+            (
+                [F::from_canonical_usize(j)].into(),
+                (block, CaseType::Constrained),
+            )
+        })
+        .collect();
+    FuncE {
+        name: Name("keccak_rc"),
+        invertible: false,
+        partial: false,
+        input_params: [i].into(),
+        output_size: 8,
+        body: BlockE::no_op(CtrlE::Match(i, CasesE::no_default(branches))),
+    }
+}
+
+/// Returns the Keccak padding of a message of `len` bytes as a list of bytes: a `0x01`
+/// marker, the zeros that fill the block and a final `0x80` marker. Both markers share
+/// a single `0x81` byte if there's only room for one byte left in the block
+pub fn keccak_padding<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn keccak_padding(len): [1] {
+            let len_u64 = call(num_to_u64, len);
+            let rate = [136, 0, 0, 0, 0, 0, 0, 0];
+            let rate = store(rate);
+            let (_blocks, rem) = call(u64_divrem, len_u64, rate);
+            let (rem, _rem_high: [7]) = load(rem);
+            let max_room = 135;
+            let room = sub(max_room, rem);
+            let zero = 0;
+            if room {
+                let end = 0x80;
+                let padding = store(end, zero);
+                let one = 1;
+                let num_zeros = sub(room, one);
+                let padding = call(sha256_zeros, num_zeros, padding);
+                let start = 0x01;
+                let padding = store(start, padding);
+                return padding
+            }
+            let markers = 0x81;
+            let padding = store(markers, zero);
+            return padding
+        }
+    )
+}
+
+/// Returns the padding of a message of `len` bytes for Keccak-256 if `keccak` is set,
+/// or for SHA-256 otherwise
+pub fn message_padding<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn message_padding(len, keccak): [1] {
+            if keccak {
+                let padding = call(keccak_padding, len);
+                return padding
+            }
+            let padding = call(sha256_padding, len);
+            return padding
+        }
+    )
+}
+
+/// Reads a little-endian lane from a list of bytes, returning the rest of the list
+pub fn keccak_lane<F>() -> FuncE<F> {
+    func!(
+        fn keccak_lane(msg): [9] {
+            let (b0, msg) = load(msg);
+            let (b1, msg) = load(msg);
+            let (b2, msg) = load(msg);
+            let (b3, msg) = load(msg);
+            let (b4, msg) = load(msg);
+            let (b5, msg) = load(msg);
+            let (b6, msg) = load(msg);
+            let (b7, msg) = load(msg);
+            return (b0, b1, b2, b3, b4, b5, b6, b7, msg)
+        }
+    )
+}
+
+/// Xors a block of 136 bytes into the state. The witness of the chip lives in this
+/// function's own AIR, instead of widening the sponge that drives it
+pub fn keccak_absorb<F>() -> FuncE<F> {
+    func!(
+        fn keccak_absorb(state: [200], block: [136]): [200] {
+            let state: [200] = extern_call(keccak_absorber, state, block);
+            return state
+        }
+    )
+}
+
+/// Applies a round of Keccak-f[1600] to the state, given the round constant. The
+/// witness of the round lives in this function's own AIR, instead of widening the
+/// loop over the rounds
+pub fn keccak_round<F>() -> FuncE<F> {
+    func!(
+        fn keccak_round(state: [200], rc: [8]): [200] {
+            let state: [200] = extern_call(keccak_permuter, state, rc);
+            return state
+        }
+    )
+}
+
+/// Applies the rounds of Keccak-f[1600] from `i` on to the state
+pub fn keccak_rounds<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn keccak_rounds(state: [200], i): [200] {
+            let num_rounds = 24;
+            let rounds_left = sub(num_rounds, i);
+            if rounds_left {
+                let rc: [8] = call(keccak_rc, i);
+                let state: [200] = call(keccak_round, state, rc);
+                let one = 1;
+                let i = add(i, one);
+                let state: [200] = call(keccak_rounds, state, i);
+                return state
+            }
+            return state
+        }
+    )
+}
+
+/// Absorbs the blocks of a padded message, given as a list of bytes, into the state,
+/// applying the permutation after each block
+pub fn keccak_blocks<F: AbstractField>() -> FuncE<F> {
+    func!(
+        fn keccak_blocks(state: [200], msg): [200] {
+            if msg {
+                let (l0: [8], msg) = call(keccak_lane, msg);
+                let (l1: [8], msg) = call(keccak_lane, msg);
+                let (l2: [8], msg) = call(keccak_lane, msg);
+                let (l3: [8], msg) = call(keccak_lane, msg);
+                let (l4: [8], msg) = call(keccak_lane, msg);
+                let (l5: [8], msg) = call(keccak_lane, msg);
+                let (l6: [8], msg) = call(keccak_lane, msg);
+                let (l7: [8], msg) = call(keccak_lane, msg);
+                let (l8: [8], msg) = call(keccak_lane, msg);
+                let (l9: [8], msg) = call(keccak_lane, msg);
+                let (l10: [8], msg) = call(keccak_lane, msg);
+                let (l11: [8], msg) = call(keccak_lane, msg);
+                let (l12: [8], msg) = call(keccak_lane, msg);
+                let (l13: [8], msg) = call(keccak_lane, msg);
+                let (l14: [8], msg) = call(keccak_lane, msg);
+                let (l15: [8], msg) = call(keccak_lane, msg);
+                let (l16: [8], msg) = call(keccak_lane, msg);
+                let block: [136] = (l0, l1, l2, l3, l4, l5, l6, l7, l8, l9, l10, l11, l12, l13, l14, l15, l16);
+                let state: [200] = call(keccak_absorb, state, block);
+                let i = 0;
+                let state: [200] = call(keccak_rounds, state, i);
+                let state: [200] = call(keccak_blocks, state, msg);
+                return state
+            }
+            return state
+        }
+    )
+}
+
+/// Returns `2 * acc + bit * point` on the Edwards curve, with points as the
+/// coefficients of their coordinates
+pub fn edwards_double_add<F>() -> FuncE<F> {
+    func!(
+        fn edwards_double_add(acc: [14], point: [14], bit): [14] {
+            let acc: [14] = extern_call(edwards_add, acc, acc);
+            if bit {
+                let acc: [14] = extern_call(edwards_add, acc, point);
+                return acc
+            }
+            return acc
+        }
+    )
+}
+
+/// Returns `256 * acc + byte * point`, doubling and adding from the most significant
+/// bit of the byte
+pub fn edwards_mul_byte<F>() -> FuncE<F> {
+    func!(
+        fn edwards_mul_byte(acc: [14], point: [14], byte): [14] {
+            let (b0, b1, b2, b3, b4, b5, b6, b7) = extern_call(byte_to_bits, byte);
+            let acc: [14] = call(edwards_double_add, acc, point, b7);
+            let acc: [14] = call(edwards_double_add, acc, point, b6);
+            let acc: [14] = call(edwards_double_add, acc, point, b5);
+            let acc: [14] = call(edwards_double_add, acc, point, b4);
+            let acc: [14] = call(edwards_double_add, acc, point, b3);
+            let acc: [14] = call(edwards_double_add, acc, point, b2);
+            let acc: [14] = call(edwards_double_add, acc, point, b1);
+            let acc: [14] = call(edwards_double_add, acc, point, b0);
+            return acc
+        }
+    )
+}
+
+/// Returns `2^32 * acc + n * point`, where `n` is the canonical representative of a
+/// field element
+pub fn edwards_mul_num<F>() -> FuncE<F> {
+    func!(
+        fn edwards_mul_num(acc: [14], point: [14], n): [14] {
+            let (b0, b1, b2, b3) = extern_call(num_to_bytes, n);
+            let acc: [14] = call(edwards_mul_byte, acc, point, b3);
+            let acc: [14] = call(edwards_mul_byte, acc, point, b2);
+            let acc: [14] = call(edwards_mul_byte, acc, point, b1);
+            let acc: [14] = call(edwards_mul_byte, acc, point, b0);
+            return acc
+        }
+    )
+}
+
+/// Verifies a Schnorr signature `(R, s)` of a value by the public key `A`, where the
+/// coordinates of the points and the scalar `s` are pointers to BigNums. Returns one
+/// if the points are on the curve, if `s` is smaller than the order of the generator
+/// `B` and if `[s]B = R + [h]A`, where the challenge `h` is computed from the hash of
+/// `R`, `A` and the value. Returns zero otherwise.
+pub fn schnorr_verify<F: AbstractField>() -> FuncE<F> {
+    let bases: [List<F>; EXT_DEGREE] =
+        EDWARDS_BASES.map(|base| base.map(F::from_canonical_u32).into());
+    let order: List<F> = EDWARDS_ORDER.map(F::from_canonical_u32).into();
+    func!(
+        fn schnorr_verify(a_x, a_y, msg_tag, msg, r_x, r_y, s): [1] {
+            let zero = 0;
+            let one = 1;
+            // the coordinates are coefficients of the extension, with nothing beyond
+            let (a_x: [7], a_x_top) = load(a_x);
+            let (a_y: [7], a_y_top) = load(a_y);
+            let (r_x: [7], r_x_top) = load(r_x);
+            let (r_y: [7], r_y_top) = load(r_y);
+            let tops: [4] = (a_x_top, a_y_top, r_x_top, r_y_top);
+            if tops {
+                return zero
+            }
+            let residual: [7] = extern_call(edwards_residual, a_x, a_y);
+            if residual {
+                return zero
+            }
+            let residual: [7] = extern_call(edwards_residual, r_x, r_y);
+            if residual {
+                return zero
+            }
+            let order = Array(order.clone());
+            let order = store(order);
+            let in_range = call(big_num_lessthan, s, order);
+            if !in_range {
+                return zero
+            }
+            // the challenge is made of the first 6 elements of the hash, as 32-bit digits
+            let padding = [0; 4];
+            let points: [8] = call(hash4, r_x, r_y, a_x, a_y, padding);
+            let (msg_tag, msg_digest: [8]) = call(egress, msg_tag, msg);
+            let padding = [0; 7];
+            let (h0, h1, h2, h3, h4, h5, _h6, _h7) = call(hash3, points, msg_tag, padding, msg_digest);
+            let identity = [0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+            let a: [14] = (a_x, a_y);
+            let h_a: [14] = call(edwards_mul_num, identity, a, h5);
+            let h_a: [14] = call(edwards_mul_num, h_a, a, h4);
+            let h_a: [14] = call(edwards_mul_num, h_a, a, h3);
+            let h_a: [14] = call(edwards_mul_num, h_a, a, h2);
+            let h_a: [14] = call(edwards_mul_num, h_a, a, h1);
+            let h_a: [14] = call(edwards_mul_num, h_a, a, h0);
+            let r: [14] = (r_x, r_y);
+            let rhs: [14] = extern_call(edwards_add, r, h_a);
+            // `[s]B` is the sum of the `[s_i]B_i`, and `s_7` is zero since `s` is in range
+            let (s0, s1, s2, s3, s4, s5, s6, _s7) = load(s);
+            let base = Array(bases[0].clone());
+            let lhs: [14] = call(edwards_mul_num, identity, base, s0);
+            let base = Array(bases[1].clone());
+            let term: [14] = call(edwards_mul_num, identity, base, s1);
+            let lhs: [14] = extern_call(edwards_add, lhs, term);
+            let base = Array(bases[2].clone());
+            let term: [14] = call(edwards_mul_num, identity, base, s2);
+            let lhs: [14] = extern_call(edwards_add, lhs, term);
+            let base = Array(bases[3].clone());
+            let term: [14] = call(edwards_mul_num, identity, base, s3);
+            let lhs: [14] = extern_call(edwards_add, lhs, term);
+            let base = Array(bases[4].clone());
+            let term: [14] = call(edwards_mul_num, identity, base, s4);
+            let lhs: [14] = extern_call(edwards_add, lhs, term);
+            let base = Array(bases[5].clone());
+            let term: [14] = call(edwards_mul_num, identity, base, s5);
+            let lhs: [14] = extern_call(edwards_add, lhs, term);
+            let base = Array(bases[6].clone());
+            let term: [14] = call(edwards_mul_num, identity, base, s6);
+            let lhs: [14] = extern_call(edwards_add, lhs, term);
+            let diff = sub(lhs, rhs);
+            if diff {
+                return zero
+            }
+            return one
+        }
+    )
+}
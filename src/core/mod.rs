@@ -7,12 +7,17 @@ pub mod compile;
 pub mod error;
 pub mod eval_compiled;
 pub mod eval_direct;
+pub mod i64;
 pub mod ingress;
+pub mod keccak;
 pub mod lang;
 pub mod misc;
+pub mod num;
 pub mod package;
 pub mod parser;
 pub mod poseidon;
+pub mod sha256;
+pub mod signature;
 pub mod stark_machine;
 pub mod state;
 pub mod symbol;
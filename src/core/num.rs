@@ -0,0 +1,217 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, PrimeField32};
+use sp1_derive::AlignedBorrow;
+
+use crate::{
+    air::builder::{LookupBuilder, Record, RequireRecord},
+    gadgets::{
+        bytes::{builder::BytesAirRecordWithContext, record::DummyBytesRecord},
+        unsigned::field::FieldToWord32,
+    },
+    lair::{chipset::Chipset, execute::QueryRecord},
+};
+
+/// Operations on field elements that require witnesses to be expressed in Lair
+#[derive(Clone)]
+pub enum Num {
+    /// Decomposes a field element into the little-endian bytes of its canonical
+    /// representative
+    ToBytes,
+    /// Decomposes a byte into its little-endian bits
+    ByteToBits,
+}
+
+/// Witness for the bits of a byte, which must be boolean and recompose it
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+struct ByteBits<T> {
+    bits: [T; 8],
+}
+
+impl<F: PrimeField32> ByteBits<F> {
+    fn populate(&mut self, byte: &F) -> Vec<F> {
+        let byte: u8 = byte.as_canonical_u32().try_into().expect("Not a byte");
+        self.bits = std::array::from_fn(|i| F::from_bool((byte >> i) & 1 == 1));
+        self.bits.to_vec()
+    }
+}
+
+impl<Var> ByteBits<Var> {
+    fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        byte: &AB::Expr,
+        is_real: AB::Expr,
+    ) -> Vec<AB::Expr>
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let mut recomposed = AB::Expr::zero();
+        for bit in self.bits.iter().rev() {
+            builder.when(is_real.clone()).assert_bool(*bit);
+            recomposed = recomposed * AB::Expr::two() + (*bit).into();
+        }
+        builder.when(is_real).assert_eq(byte.clone(), recomposed);
+        self.bits.iter().map(|&bit| bit.into()).collect()
+    }
+}
+
+impl<T> ByteBits<T> {
+    const fn witness_size() -> usize {
+        size_of::<ByteBits<u8>>()
+    }
+}
+
+impl<F: PrimeField32> Chipset<F> for Num {
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn output_size(&self) -> usize {
+        match self {
+            Num::ToBytes => 4,
+            Num::ByteToBits => 8,
+        }
+    }
+
+    fn witness_size(&self) -> usize {
+        match self {
+            Num::ToBytes => FieldToWord32::<F>::witness_size(),
+            Num::ByteToBits => ByteBits::<F>::witness_size(),
+        }
+    }
+
+    fn require_size(&self) -> usize {
+        match self {
+            Num::ToBytes => FieldToWord32::<F>::num_requires(),
+            Num::ByteToBits => 0, // the booleanity of the bits bounds the byte
+        }
+    }
+
+    fn execute(
+        &self,
+        input: &[F],
+        nonce: u32,
+        queries: &mut QueryRecord<F>,
+        requires: &mut Vec<Record>,
+    ) -> Vec<F> {
+        match self {
+            Num::ToBytes => {
+                let bytes = &mut queries.bytes.context(nonce, requires);
+                let mut witness = FieldToWord32::<F>::default();
+                witness.populate(&input[0].as_canonical_u32(), bytes);
+                witness.iter_result().into_iter().collect()
+            }
+            Num::ByteToBits => ByteBits::<F>::default().populate(&input[0]),
+        }
+    }
+
+    fn populate_witness(&self, input: &[F], witness: &mut [F]) -> Vec<F> {
+        match self {
+            Num::ToBytes => {
+                let bytes = &mut DummyBytesRecord;
+                let witness: &mut FieldToWord32<F> = witness.borrow_mut();
+                witness.populate(&input[0].as_canonical_u32(), bytes);
+                witness.iter_result().into_iter().collect()
+            }
+            Num::ByteToBits => {
+                let witness: &mut ByteBits<F> = witness.borrow_mut();
+                witness.populate(&input[0])
+            }
+        }
+    }
+
+    fn eval<AB: AirBuilder<F = F> + LookupBuilder>(
+        &self,
+        builder: &mut AB,
+        is_real: AB::Expr,
+        ins: Vec<AB::Expr>,
+        witness: &[AB::Var],
+        nonce: AB::Expr,
+        requires: &[RequireRecord<AB::Var>],
+    ) -> Vec<AB::Expr> {
+        match self {
+            Num::ToBytes => {
+                let mut air_record = BytesAirRecordWithContext::default();
+                let witness: &FieldToWord32<AB::Var> = witness.borrow();
+                let out = witness.eval(builder, &ins[0], &mut air_record, is_real);
+                air_record.require_all(builder, nonce, requires.iter().cloned());
+                out.into_iter().map(Into::into).collect()
+            }
+            Num::ByteToBits => {
+                let witness: &ByteBits<AB::Var> = witness.borrow();
+                witness.eval(builder, &ins[0], is_real)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use p3_baby_bear::BabyBear as F;
+    use p3_field::{AbstractField, PrimeField32};
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig, StarkMachine};
+
+    use crate::{
+        air::debug::debug_chip_constraints_and_queries_with_sharding,
+        core::chipset::lurk_chip_map_native,
+        func,
+        lair::{
+            execute::{QueryRecord, Shard},
+            func_chip::FuncChip,
+            lair_chip::{build_chip_vector, build_lair_chip_vector, LairMachineProgram},
+            toplevel::Toplevel,
+        },
+    };
+
+    #[test]
+    fn num_to_bytes_and_bits_test() {
+        sp1_core_machine::utils::setup_logger();
+
+        let bits_func = func!(
+        fn bits(a): [12] {
+            let (b0, b1, b2, b3) = extern_call(num_to_bytes, a);
+            let bits: [8] = extern_call(byte_to_bits, b3);
+            return (b0, b1, b2, b3, bits)
+        });
+        let lurk_chip_map = lurk_chip_map_native();
+        let toplevel = Toplevel::new(&[bits_func], lurk_chip_map);
+
+        let bits_chip = FuncChip::from_name("bits", &toplevel);
+        let mut queries = QueryRecord::new(&toplevel);
+        let f = F::from_canonical_u32;
+        // the largest field element, 0x78000000
+        let args = &[F::neg_one()];
+        let out = toplevel
+            .execute_by_name("bits", args, &mut queries, None)
+            .unwrap();
+        let expected_bits = (0..8).map(|i| f((0x78 >> i) & 1));
+        let expected = [f(0), f(0), f(0), f(0x78)]
+            .into_iter()
+            .chain(expected_bits)
+            .collect::<Vec<_>>();
+        assert_eq!(out.as_ref(), expected);
+        assert_eq!(F::neg_one().as_canonical_u32(), 0x78000000);
+
+        let lair_chips = build_lair_chip_vector(&bits_chip);
+        let queries = Arc::new(queries);
+        debug_chip_constraints_and_queries_with_sharding(&queries, &lair_chips, None);
+
+        let config = BabyBearPoseidon2::new();
+        let machine = StarkMachine::new(
+            config,
+            build_chip_vector(&bits_chip),
+            queries.expect_public_values().len(),
+            true,
+        );
+
+        let (pk, _vk) = machine.setup(&LairMachineProgram);
+        let shard = Shard::new_arc(&queries);
+        let mut challenger = machine.config().challenger();
+        machine.debug_constraints(&pk, shard, &mut challenger);
+    }
+}
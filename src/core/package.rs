@@ -1,17 +1,34 @@
 use anyhow::{bail, Result};
 use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use super::symbol::Symbol;
+use super::{symbol::Symbol, zstore::DIGEST_SIZE};
 
 pub(crate) type SymbolRef = Arc<Symbol>;
 
+/// The digest of a Lurk pointer as canonical `u32`s, which keeps packages
+/// agnostic to the field
+pub(crate) type Digest = [u32; DIGEST_SIZE];
+
 #[derive(Debug)]
 pub struct Package {
     name: SymbolRef,
     symbols: FxHashMap<String, SymbolRef>,
     names: FxHashMap<SymbolRef, String>,
     local: FxHashSet<SymbolRef>,
+    /// The functions of the macros defined in the package, indexed by the
+    /// digests of their symbols
+    macros: FxHashMap<Digest, Digest>,
+}
+
+/// A serializable snapshot of a `Package`
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PackageData {
+    name: Symbol,
+    symbols: Vec<(String, Symbol)>,
+    local: Vec<Symbol>,
+    macros: Vec<(Digest, Digest)>,
 }
 
 impl Package {
@@ -22,6 +39,7 @@ impl Package {
             symbols: Default::default(),
             names: Default::default(),
             local: Default::default(),
+            macros: Default::default(),
         }
     }
 
@@ -82,10 +100,65 @@ impl Package {
         self.import(&package.local.iter().cloned().collect::<Vec<_>>())
     }
 
+    /// Defines (or redefines) the macro whose symbol has the digest `symbol`
+    #[inline]
+    pub(crate) fn add_macro(&mut self, symbol: Digest, fun: Digest) {
+        self.macros.insert(symbol, fun);
+    }
+
+    #[inline]
+    pub(crate) fn get_macro(&self, symbol: &Digest) -> Option<&Digest> {
+        self.macros.get(symbol)
+    }
+
+    /// Iterates over the pairs of symbol and function digests of the macros
+    #[inline]
+    pub(crate) fn macros(&self) -> impl Iterator<Item = (&Digest, &Digest)> {
+        self.macros.iter()
+    }
+
     pub fn fmt_to_string(&self, symbol: &Symbol) -> String {
         match self.names.get(symbol) {
             None => symbol.fmt_to_string(),
             Some(name) => Symbol::fmt_path_component_to_string(name),
         }
     }
+
+    pub(crate) fn to_data(&self) -> PackageData {
+        PackageData {
+            name: (*self.name).clone(),
+            symbols: self
+                .symbols
+                .iter()
+                .map(|(name, symbol)| (name.clone(), (**symbol).clone()))
+                .collect(),
+            local: self.local.iter().map(|symbol| (**symbol).clone()).collect(),
+            macros: self.macros.iter().map(|(s, f)| (*s, *f)).collect(),
+        }
+    }
+
+    pub(crate) fn from_data(data: PackageData) -> Self {
+        let PackageData {
+            name,
+            symbols,
+            local,
+            macros,
+        } = data;
+        let mut package = Self::new(name.into());
+        package.macros.extend(macros);
+        for (symbol_name, symbol) in symbols {
+            let symbol = SymbolRef::new(symbol);
+            package.names.insert(symbol.clone(), symbol_name.clone());
+            package.symbols.insert(symbol_name, symbol);
+        }
+        // reuse the `Arc`s from the symbols map
+        for symbol in local {
+            let symbol = match package.names.get_key_value(&symbol) {
+                Some((symbol, _)) => symbol.clone(),
+                None => symbol.into(),
+            };
+            package.local.insert(symbol);
+        }
+        package
+    }
 }
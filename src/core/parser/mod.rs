@@ -3,6 +3,7 @@ use thiserror;
 pub mod base;
 pub mod error;
 pub mod position;
+pub mod source;
 pub mod string;
 pub mod syntax;
 
@@ -0,0 +1,193 @@
+//! A comment-preserving parser that keeps Lurk code as written in the source,
+//! meant for tooling such as the formatter
+
+use anyhow::{bail, Result};
+use nom::{character::complete::multispace0, Slice};
+use p3_field::Field;
+
+use crate::core::{
+    parser::{
+        error::ParseError,
+        syntax::{parse_line_comment, parse_syntax},
+        Span,
+    },
+    state::StateRcCell,
+    syntax::Syntax,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceNode<'a> {
+    /// A line comment, including the leading `;`. `trailing` tells whether it
+    /// shares its line with the code that precedes it
+    Comment { text: &'a str, trailing: bool },
+    /// One or more empty lines between two nodes
+    BlankLine,
+    /// Code that's kept verbatim, such as numbers, symbols, strings and chars
+    Atom(&'a str),
+    /// A node preceded by a quoting prefix: 'x, `x, ,x or ,@x
+    Quote(&'a str, Box<SourceNode<'a>>),
+    /// A list, possibly improper, or a meta command when `meta` is set
+    List {
+        meta: bool,
+        nodes: Vec<SourceNode<'a>>,
+    },
+    /// The dot of an improper list
+    Dot,
+}
+
+/// Consumes whitespaces and comments, pushing `Comment` and `BlankLine` nodes
+fn parse_gap<'a>(mut i: Span<'a>, nodes: &mut Vec<SourceNode<'a>>) -> Span<'a> {
+    // whether the current line already has code on it
+    let mut code_on_line = !nodes.is_empty();
+    loop {
+        let (rest, space) =
+            multispace0::<_, ParseError<Span<'a>>>(i).expect("multispace0 can't fail");
+        let num_newlines = space.fragment().matches('\n').count();
+        if num_newlines > 0 {
+            code_on_line = false;
+        }
+        if num_newlines > 1 && !matches!(nodes.last(), None | Some(SourceNode::BlankLine)) {
+            nodes.push(SourceNode::BlankLine);
+        }
+        i = rest;
+        let Ok((rest, _)) = parse_line_comment(i) else {
+            return i;
+        };
+        let fragment: &'a str = i.fragment();
+        let text = &fragment[..rest.location_offset() - i.location_offset()];
+        nodes.push(SourceNode::Comment {
+            text: text.trim_end(),
+            trailing: code_on_line,
+        });
+        i = rest;
+    }
+}
+
+fn parse_list_nodes<'a, F: Field>(
+    open: Span<'a>,
+    state: &StateRcCell,
+) -> Result<(Span<'a>, Vec<SourceNode<'a>>)> {
+    let mut nodes = vec![];
+    let mut i = open;
+    loop {
+        i = parse_gap(i, &mut nodes);
+        let fragment: &str = i.fragment();
+        if fragment.starts_with(')') {
+            return Ok((i.slice(1..), nodes));
+        }
+        if fragment.is_empty() {
+            bail!(
+                "Missing `)` for the list opened at line {}:{}",
+                open.location_line(),
+                open.get_utf8_column().saturating_sub(1)
+            );
+        }
+        match parse_node::<F>(i, state) {
+            Ok((rest, node)) => {
+                nodes.push(node);
+                i = rest;
+            }
+            // the dot of improper lists is only parsed when a node can't be
+            Err(_) if fragment.starts_with('.') => {
+                nodes.push(SourceNode::Dot);
+                i = i.slice(1..);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn parse_node<'a, F: Field>(
+    i: Span<'a>,
+    state: &StateRcCell,
+) -> Result<(Span<'a>, SourceNode<'a>)> {
+    let fragment: &'a str = i.fragment();
+    if fragment.starts_with("!(") {
+        let (rest, nodes) = parse_list_nodes::<F>(i.slice(2..), state)?;
+        return Ok((rest, SourceNode::List { meta: true, nodes }));
+    }
+    if fragment.starts_with('(') {
+        let (rest, nodes) = parse_list_nodes::<F>(i.slice(1..), state)?;
+        return Ok((rest, SourceNode::List { meta: false, nodes }));
+    }
+    let prefix = ["'", "`", ",@", ","]
+        .into_iter()
+        .find(|prefix| fragment.starts_with(prefix));
+    match (parse_syntax::<F>(state.clone(), true)(i), prefix) {
+        // chars like 'a' are atoms, but quoted nodes need to be traversed
+        (
+            Ok((
+                _,
+                Syntax::Quote(..)
+                | Syntax::Quasiquote(..)
+                | Syntax::Unquote(..)
+                | Syntax::UnquoteSplicing(..),
+            ))
+            | Err(_),
+            Some(prefix),
+        ) => {
+            let (rest, node) = parse_node::<F>(i.slice(prefix.len()..), state)?;
+            Ok((rest, SourceNode::Quote(prefix, Box::new(node))))
+        }
+        (Ok((rest, _)), _) => {
+            let text = &fragment[..rest.location_offset() - i.location_offset()];
+            Ok((rest, SourceNode::Atom(text)))
+        }
+        (Err(nom::Err::Error(e) | nom::Err::Failure(e)), None) => bail!("{e}"),
+        (Err(nom::Err::Incomplete(_)), None) => bail!("Incomplete input"),
+    }
+}
+
+/// Parses Lurk code into `SourceNode`s, retaining comments and blank lines
+pub fn parse_source<F: Field>(input: &str, state: StateRcCell) -> Result<Vec<SourceNode<'_>>> {
+    let mut nodes = vec![];
+    let mut i = Span::new(input);
+    loop {
+        i = parse_gap(i, &mut nodes);
+        if i.fragment().is_empty() {
+            return Ok(nodes);
+        }
+        let (rest, node) = parse_node::<F>(i, &state)?;
+        nodes.push(node);
+        i = rest;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use p3_baby_bear::BabyBear;
+
+    use crate::core::state::State;
+
+    use super::{parse_source, SourceNode};
+
+    #[test]
+    fn test_comments_are_retained() {
+        let state = State::init_lurk_state().rccell();
+        let input = "; header\n\n(a ; trailing\n 'b . c)";
+        let nodes = parse_source::<BabyBear>(input, state).unwrap();
+        assert_eq!(
+            nodes,
+            [
+                SourceNode::Comment {
+                    text: "; header",
+                    trailing: false
+                },
+                SourceNode::BlankLine,
+                SourceNode::List {
+                    meta: false,
+                    nodes: vec![
+                        SourceNode::Atom("a"),
+                        SourceNode::Comment {
+                            text: "; trailing",
+                            trailing: true
+                        },
+                        SourceNode::Quote("'", Box::new(SourceNode::Atom("b"))),
+                        SourceNode::Dot,
+                        SourceNode::Atom("c"),
+                    ]
+                }
+            ]
+        );
+    }
+}
@@ -22,11 +22,11 @@ use crate::core::{
     },
     state::{meta_package_symbol, StateRcCell},
     symbol,
-    syntax::Syntax,
+    syntax::{i64_from_sign_magnitude, Syntax, DECIMAL_PLACES, DECIMAL_SCALE},
     zstore::DIGEST_SIZE,
 };
 
-fn parse_line_comment(i: Span<'_>) -> ParseResult<'_, Span<'_>> {
+pub(crate) fn parse_line_comment(i: Span<'_>) -> ParseResult<'_, Span<'_>> {
     let (i, _) = tag(";")(i)?;
     let (i, com) = take_till(|c| c == '\n')(i)?;
     Ok((i, com))
@@ -123,7 +123,7 @@ fn parse_relative_symbol(
     create_unknown_packages: bool,
 ) -> impl Fn(Span<'_>) -> ParseResult<'_, SymbolRef> {
     move |from: Span<'_>| {
-        let (i, _) = peek(none_of(",~#(){}[]1234567890."))(from)?;
+        let (i, _) = peek(none_of(",`~#(){}[]1234567890."))(from)?;
         let (upto, path) = parse_symbol_limbs()(i)?;
         intern_path(&state, upto, &path, None, create_unknown_packages)
     }
@@ -203,6 +203,35 @@ fn parse_numeric_suffix() -> impl Fn(Span<'_>) -> ParseResult<'_, Span<'_>> {
     }
 }
 
+/// Scales the integer and fractional digits of a decimal literal by `DECIMAL_SCALE`.
+/// Errors are failures rather than backtracking ones so that invalid decimals
+/// aren't read as symbols instead
+fn decimal_from_digits<'a>(
+    neg: bool,
+    int_digits: &str,
+    frac_digits: &str,
+    from: Span<'a>,
+) -> ParseResult<'a, i64> {
+    let fail = |msg: String| nom::Err::Failure(ParseError::new(from, ParseErrorKind::Custom(msg)));
+    let literal = format!("{}{int_digits}.{frac_digits}", if neg { "-" } else { "" });
+    if frac_digits.len() > DECIMAL_PLACES {
+        return Err(fail(format!(
+            "Decimal literal {literal} has more than {DECIMAL_PLACES} fractional digits"
+        )));
+    }
+    let frac_digits = format!("{frac_digits:0<width$}", width = DECIMAL_PLACES);
+    let x = int_digits
+        .parse::<u64>()
+        .ok()
+        .zip(frac_digits.parse::<u64>().ok())
+        .and_then(|(int, frac)| int.checked_mul(DECIMAL_SCALE)?.checked_add(frac))
+        .and_then(|x| i64_from_sign_magnitude(neg, x));
+    match x {
+        Some(x) => Ok((from, x)),
+        None => Err(fail(format!("Decimal literal {literal} out of range"))),
+    }
+}
+
 fn parse_numeric<F: Field>() -> impl Fn(Span<'_>) -> ParseResult<'_, Syntax<F>> {
     move |from: Span<'_>| {
         let (i, neg) = opt(tag("-"))(from)?;
@@ -211,6 +240,14 @@ fn parse_numeric<F: Field>() -> impl Fn(Span<'_>) -> ParseResult<'_, Syntax<F>>
             success(base::LitBase::Dec),
         ))(i)?;
         let (i, digits) = base::parse_litbase_digits(base)(i)?;
+        if base == base::LitBase::Dec {
+            let (upto, frac) = opt(preceded(char('.'), base::parse_dec_digits()))(i)?;
+            if let Some(frac) = frac {
+                let (_, x) = decimal_from_digits(neg.is_some(), &digits, &frac, from)?;
+                let pos = Pos::from_upto(from, upto);
+                return Ok((upto, Syntax::Decimal(pos, x)));
+            }
+        }
         // when more uint types are supported we can do:
         let (upto, suffix) = opt(parse_numeric_suffix())(i)?;
         let suffix = suffix.map(|x| *x.fragment());
@@ -449,6 +486,35 @@ fn parse_char_or_quote<F: Field>(
     }
 }
 
+fn parse_quasiquote<F: Field>(
+    state: StateRcCell,
+    create_unknown_packages: bool,
+) -> impl Fn(Span<'_>) -> ParseResult<'_, Syntax<F>> {
+    move |from: Span<'_>| {
+        let (i, _) = tag("`")(from)?;
+        let (upto, s) = parse_syntax(state.clone(), create_unknown_packages)(i)?;
+        let pos = Pos::from_upto(from, upto);
+        Ok((upto, Syntax::Quasiquote(pos, Box::new(s))))
+    }
+}
+
+fn parse_unquote<F: Field>(
+    state: StateRcCell,
+    create_unknown_packages: bool,
+) -> impl Fn(Span<'_>) -> ParseResult<'_, Syntax<F>> {
+    move |from: Span<'_>| {
+        let (i, _) = tag(",")(from)?;
+        let (i, splicing) = opt(tag("@"))(i)?;
+        let (upto, s) = parse_syntax(state.clone(), create_unknown_packages)(i)?;
+        let pos = Pos::from_upto(from, upto);
+        if splicing.is_some() {
+            Ok((upto, Syntax::UnquoteSplicing(pos, Box::new(s))))
+        } else {
+            Ok((upto, Syntax::Unquote(pos, Box::new(s))))
+        }
+    }
+}
+
 fn parse_env_pair<F: Field>(
     state: StateRcCell,
     create_unknown_packages: bool,
@@ -484,7 +550,7 @@ fn parse_env<F: Field>(
     }
 }
 
-fn parse_syntax<F: Field>(
+pub(crate) fn parse_syntax<F: Field>(
     state: StateRcCell,
     create_unknown_packages: bool,
 ) -> impl Fn(Span<'_>) -> ParseResult<'_, Syntax<F>> {
@@ -498,6 +564,8 @@ fn parse_syntax<F: Field>(
             parse_symbol_syntax(state.clone(), create_unknown_packages),
             parse_string(),
             parse_char_or_quote(state.clone(), create_unknown_packages),
+            parse_quasiquote(state.clone(), create_unknown_packages),
+            parse_unquote(state.clone(), create_unknown_packages),
             parse_hash_char(),
             parse_env(state.clone(), create_unknown_packages),
         ))(from)
@@ -539,7 +607,7 @@ pub fn parse<F: Field>(
 mod test {
     use p3_baby_bear::BabyBear;
 
-    use crate::core::{parser::syntax::parse_syntax, state::State};
+    use crate::core::{parser::syntax::parse_syntax, state::State, syntax::Syntax};
 
     #[test]
     fn test_digest() {
@@ -560,4 +628,43 @@ mod test {
         assert!(rest.is_empty());
         assert_eq!(format!("{syn}"), "#0x123456789");
     }
+
+    #[test]
+    fn test_quasiquote() {
+        let state = State::init_lurk_state().rccell();
+        let (rest, syn) =
+            parse_syntax::<BabyBear>(state, false)("`(a ,b ,@(c d) . ,e)".into()).unwrap();
+        assert!(rest.is_empty());
+        let Syntax::Quasiquote(_, list) = &syn else {
+            panic!("expected a quasiquote")
+        };
+        let Syntax::Improper(_, xs, end) = list.as_ref() else {
+            panic!("expected an improper list")
+        };
+        assert!(matches!(xs[1], Syntax::Unquote(..)));
+        assert!(matches!(xs[2], Syntax::UnquoteSplicing(..)));
+        assert!(matches!(end.as_ref(), Syntax::Unquote(..)));
+        assert_eq!(format!("{syn}"), "`(a ,b ,@(c d) . ,e)");
+    }
+
+    #[test]
+    fn test_decimal() {
+        let state = State::init_lurk_state().rccell();
+        for (input, scaled, output) in [
+            ("1.5", 1_500_000, "1.5"),
+            ("-0.25", -250_000, "-0.25"),
+            ("3.000", 3_000_000, "3.0"),
+            ("0.000001", 1, "0.000001"),
+            ("1_000.5", 1_000_500_000, "1000.5"),
+        ] {
+            let (rest, syn) = parse_syntax::<BabyBear>(state.clone(), false)(input.into()).unwrap();
+            assert!(rest.is_empty());
+            assert!(matches!(syn, Syntax::Decimal(_, x) if x == scaled));
+            assert_eq!(format!("{syn}"), output);
+        }
+        // more than six fractional digits
+        assert!(parse_syntax::<BabyBear>(state.clone(), false)("0.0000001".into()).is_err());
+        // out of the `i64` range once scaled
+        assert!(parse_syntax::<BabyBear>(state, false)("10000000000000.0".into()).is_err());
+    }
 }
@@ -0,0 +1,277 @@
+use std::array;
+use std::borrow::{Borrow, BorrowMut};
+
+use p3_air::AirBuilder;
+use p3_field::PrimeField32;
+
+use crate::{
+    air::builder::{LookupBuilder, Record, RequireRecord},
+    gadgets::{
+        bytes::{builder::BytesAirRecordWithContext, record::DummyBytesRecord},
+        sha256::{RoundWitness, ScheduleWitness, StateSum},
+        unsigned::{Word, Word32},
+    },
+    lair::{chipset::Chipset, execute::QueryRecord},
+};
+
+/// The steps of the SHA-256 compression function. Words are passed around as their
+/// big-endian bytes, which is the order in which they appear in messages and digests.
+#[derive(Clone)]
+pub enum Sha256 {
+    /// Computes the next word of the message schedule from `w[t-16]`, `w[t-15]`,
+    /// `w[t-7]` and `w[t-2]`
+    Schedule,
+    /// Applies a round to the state `[a, b, c, d, e, f, g, h]`, given the round
+    /// constant and the message schedule word, returning the new `a` and `e`
+    Round,
+    /// Adds the result of compressing a block to the previous state
+    AddState,
+}
+
+/// The round constants of SHA-256
+pub(crate) const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// The initial state of SHA-256
+pub(crate) const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+fn into_u32s<F: PrimeField32, const N: usize>(slice: &[F]) -> [u32; N] {
+    assert_eq!(slice.len(), 4 * N);
+    array::from_fn(|i| {
+        let buf: [u8; 4] =
+            array::from_fn(|j| slice[4 * i + j].as_canonical_u32().try_into().unwrap());
+        u32::from_be_bytes(buf)
+    })
+}
+
+fn from_u32s<F: PrimeField32>(words: &[u32]) -> Vec<F> {
+    words
+        .iter()
+        .flat_map(|word| word.to_be_bytes())
+        .map(F::from_canonical_u8)
+        .collect()
+}
+
+fn into_words<Expr: Clone, const N: usize>(ins: &[Expr]) -> [Word32<Expr>; N] {
+    assert_eq!(ins.len(), 4 * N);
+    array::from_fn(|i| Word::from_fn(|j| ins[4 * i + 3 - j].clone()))
+}
+
+fn from_words<Var: Into<Expr>, Expr>(words: impl IntoIterator<Item = Word32<Var>>) -> Vec<Expr> {
+    words
+        .into_iter()
+        .flat_map(|word| word.into_array().into_iter().rev().map(Into::into))
+        .collect()
+}
+
+impl<F: PrimeField32> Chipset<F> for Sha256 {
+    fn input_size(&self) -> usize {
+        match self {
+            Sha256::Schedule => 16,
+            Sha256::Round => 40,
+            Sha256::AddState => 64,
+        }
+    }
+
+    fn output_size(&self) -> usize {
+        match self {
+            Sha256::Schedule => 4,
+            Sha256::Round => 8,
+            Sha256::AddState => 32,
+        }
+    }
+
+    fn witness_size(&self) -> usize {
+        match self {
+            Sha256::Schedule => ScheduleWitness::<F>::witness_size(),
+            Sha256::Round => RoundWitness::<F>::witness_size(),
+            Sha256::AddState => StateSum::<F>::witness_size(),
+        }
+    }
+
+    fn require_size(&self) -> usize {
+        match self {
+            Sha256::Schedule => ScheduleWitness::<F>::num_requires(),
+            Sha256::Round => RoundWitness::<F>::num_requires(),
+            Sha256::AddState => StateSum::<F>::num_requires(),
+        }
+    }
+
+    fn execute(
+        &self,
+        input: &[F],
+        nonce: u32,
+        queries: &mut QueryRecord<F>,
+        requires: &mut Vec<Record>,
+    ) -> Vec<F> {
+        let bytes = &mut queries.bytes.context(nonce, requires);
+        match self {
+            Sha256::Schedule => {
+                let mut witness = ScheduleWitness::<F>::default();
+                let out = witness.populate(into_u32s(input), bytes);
+                from_u32s(&[out])
+            }
+            Sha256::Round => {
+                let mut witness = RoundWitness::<F>::default();
+                let [k, w] = into_u32s(&input[32..]);
+                let (a, e) = witness.populate(into_u32s(&input[..32]), k, w, bytes);
+                from_u32s(&[a, e])
+            }
+            Sha256::AddState => {
+                let mut witness = StateSum::<F>::default();
+                let (state, compressed) = (into_u32s(&input[..32]), into_u32s(&input[32..]));
+                from_u32s(&witness.populate(state, compressed, bytes))
+            }
+        }
+    }
+
+    fn populate_witness(&self, input: &[F], witness: &mut [F]) -> Vec<F> {
+        let bytes = &mut DummyBytesRecord;
+        match self {
+            Sha256::Schedule => {
+                let witness: &mut ScheduleWitness<F> = witness.borrow_mut();
+                let out = witness.populate(into_u32s(input), bytes);
+                from_u32s(&[out])
+            }
+            Sha256::Round => {
+                let witness: &mut RoundWitness<F> = witness.borrow_mut();
+                let [k, w] = into_u32s(&input[32..]);
+                let (a, e) = witness.populate(into_u32s(&input[..32]), k, w, bytes);
+                from_u32s(&[a, e])
+            }
+            Sha256::AddState => {
+                let witness: &mut StateSum<F> = witness.borrow_mut();
+                let (state, compressed) = (into_u32s(&input[..32]), into_u32s(&input[32..]));
+                from_u32s(&witness.populate(state, compressed, bytes))
+            }
+        }
+    }
+
+    fn eval<AB: AirBuilder<F = F> + LookupBuilder>(
+        &self,
+        builder: &mut AB,
+        is_real: AB::Expr,
+        ins: Vec<AB::Expr>,
+        witness: &[AB::Var],
+        nonce: AB::Expr,
+        requires: &[RequireRecord<AB::Var>],
+    ) -> Vec<AB::Expr> {
+        let mut air_record = BytesAirRecordWithContext::default();
+        let out = match self {
+            Sha256::Schedule => {
+                let witness: &ScheduleWitness<AB::Var> = witness.borrow();
+                let words = into_words(&ins);
+                let out = witness.eval(builder, &words, &mut air_record, is_real);
+                from_words([out])
+            }
+            Sha256::Round => {
+                let witness: &RoundWitness<AB::Var> = witness.borrow();
+                let state = into_words(&ins[..32]);
+                let [k, w] = into_words(&ins[32..]);
+                let (a, e) = witness.eval(builder, &state, &k, &w, &mut air_record, is_real);
+                from_words([a, e])
+            }
+            Sha256::AddState => {
+                let witness: &StateSum<AB::Var> = witness.borrow();
+                let state = into_words(&ins[..32]);
+                let compressed = into_words(&ins[32..]);
+                let out = witness.eval(builder, &state, &compressed, &mut air_record, is_real);
+                from_words(out)
+            }
+        };
+        air_record.require_all(builder, nonce, requires.iter().cloned());
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use p3_baby_bear::BabyBear as F;
+    use p3_field::AbstractField;
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig, StarkMachine};
+
+    use crate::{
+        air::debug::debug_chip_constraints_and_queries_with_sharding,
+        core::chipset::lurk_chip_map_native,
+        func,
+        lair::{
+            execute::{QueryRecord, Shard},
+            func_chip::FuncChip,
+            lair_chip::{build_chip_vector, build_lair_chip_vector, LairMachineProgram},
+            toplevel::Toplevel,
+        },
+    };
+
+    use super::{SHA256_IV, SHA256_K};
+
+    #[test]
+    fn sha256_steps_test() {
+        sp1_core_machine::utils::setup_logger();
+
+        let steps_func = func!(
+        fn steps(state: [32], k: [4], window: [16]): [44] {
+            let (w0: [4], _rest: [12]) = window;
+            let (new_a, new_e) = extern_call(sha256_round, state, k, w0);
+            let w = extern_call(sha256_schedule, window);
+            let sum = extern_call(sha256_add_state, state, state);
+            return (new_a, new_e, w, sum)
+        });
+        let lurk_chip_map = lurk_chip_map_native();
+        let toplevel = Toplevel::new(&[steps_func], lurk_chip_map);
+
+        let steps_chip = FuncChip::from_name("steps", &toplevel);
+        let mut queries = QueryRecord::new(&toplevel);
+        // the initial state, and the first words of the padded empty message
+        let state = SHA256_IV;
+        let window = [0x80000000u32, 0, 0, 0];
+        let args = state
+            .iter()
+            .chain(&[SHA256_K[0]])
+            .chain(&window)
+            .flat_map(|word| word.to_be_bytes())
+            .map(F::from_canonical_u8)
+            .collect::<Vec<_>>();
+        let out = toplevel
+            .execute_by_name("steps", &args, &mut queries, None)
+            .unwrap();
+        // the first round of hashing the empty message gives `a = 7c08884d` and
+        // `e = 18c7e2a2`, and the next schedule word is `w[t-16]` for a zero window
+        let sum = state.map(|word| word.wrapping_add(word));
+        let expected = [0x7c08884d, 0x18c7e2a2, 0x80000000]
+            .iter()
+            .chain(&sum)
+            .flat_map(|word| word.to_be_bytes())
+            .map(F::from_canonical_u8)
+            .collect::<Vec<_>>();
+        assert_eq!(out.as_ref(), expected);
+
+        let lair_chips = build_lair_chip_vector(&steps_chip);
+        let queries = Arc::new(queries);
+        debug_chip_constraints_and_queries_with_sharding(&queries, &lair_chips, None);
+
+        let config = BabyBearPoseidon2::new();
+        let machine = StarkMachine::new(
+            config,
+            build_chip_vector(&steps_chip),
+            queries.expect_public_values().len(),
+            true,
+        );
+
+        let (pk, _vk) = machine.setup(&LairMachineProgram);
+        let shard = Shard::new_arc(&queries);
+        let mut challenger = machine.config().challenger();
+        machine.debug_constraints(&pk, shard, &mut challenger);
+    }
+}
@@ -0,0 +1,364 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use num_bigint::BigUint;
+use p3_air::AirBuilder;
+use p3_field::PrimeField32;
+
+use crate::{
+    air::builder::{LookupBuilder, RequireRecord},
+    gadgets::edwards::{AddWitness, Point, ResidualWitness, EXT_DEGREE},
+    lair::chipset::Chipset,
+};
+
+use super::{
+    big_num::{biguint_to_field_elts, field_elts_to_biguint},
+    zstore::{ZPtr, ZStore, DIGEST_SIZE, HASH3_SIZE, HASH4_SIZE, HASH5_SIZE},
+};
+
+/// Operations on the points of the Edwards curve of `gadgets::edwards`, whose
+/// coordinates are passed around as their coefficients, `x` before `y`
+#[derive(Clone)]
+pub enum Edwards {
+    /// Adds two points
+    Add,
+    /// Evaluates the curve equation at a point, returning zero if and only if the point
+    /// is on the curve
+    Residual,
+}
+
+/// The generator `B` of the signature scheme multiplied by the powers `p^i` of the
+/// field order, as the coefficients of their coordinates. Scalars are BigNums, whose
+/// digits are in base `p`, so `[s]B` is the sum of the `[s_i]B_i`. `B` itself is a
+/// multiple of a point with `y = z + 2` by the cofactor.
+pub(crate) const EDWARDS_BASES: [[u32; 2 * EXT_DEGREE]; EXT_DEGREE] = [
+    [
+        133319257, 557589012, 1941653890, 812862691, 1975566944, 1422021201, 942940366, 269704925,
+        907390707, 2000607361, 612999609, 1710964308, 820806967, 864281447,
+    ],
+    [
+        1217776375, 669626521, 810501624, 1251101245, 422425868, 453173752, 1942987867, 1029931567,
+        620210583, 1338118650, 39548821, 1397528016, 995400703, 406481195,
+    ],
+    [
+        1218642777, 1892684789, 768076175, 912006253, 832546943, 1712016569, 817865199, 419226099,
+        1325150493, 964961941, 1132830035, 1976491479, 367902703, 1204616179,
+    ],
+    [
+        1669530347, 476815083, 326391568, 6547763, 1002261673, 805934219, 175545735, 1200172271,
+        704020512, 1748651382, 1450278250, 1873614440, 73328445, 1590670530,
+    ],
+    [
+        149452449, 1376344036, 1663909671, 1165447387, 264099200, 1651947372, 878836083, 543874511,
+        609464842, 110955763, 1938029689, 1510931287, 139155597, 1045265128,
+    ],
+    [
+        1110888862, 150750586, 68287408, 1163475270, 1184149167, 1448025502, 505737292, 504882710,
+        1901150269, 1076384047, 1504098180, 1733401448, 1782421060, 197119857,
+    ],
+    [
+        829144453, 758489282, 1586063136, 806805464, 693562718, 1972356772, 939151485, 192418244,
+        484601956, 503949051, 1287877340, 791877977, 118354468, 360982372,
+    ],
+];
+
+/// The digits of the prime order `l` of `B`, which has 186 bits. The curve has
+/// `8 * 251655109` points over the base field, hence `8 * 251655109 * l` points over
+/// the extension. Generic attacks on discrete logarithms take about `sqrt(l)` steps,
+/// so signatures offer roughly 93 bits of security.
+pub(crate) const EDWARDS_ORDER: [u32; DIGEST_SIZE] = [
+    42399787, 722762207, 1241337260, 1696966930, 627460207, 25049, 1, 0,
+];
+
+fn generator<F: PrimeField32>() -> Point<F> {
+    Point::from_canonical_u32s(&EDWARDS_BASES[0])
+}
+
+fn order<F: PrimeField32>() -> BigUint {
+    field_elts_to_biguint(&EDWARDS_ORDER.map(F::from_canonical_u32))
+}
+
+/// The scalar of a secret key, which can be any BigNum
+fn secret_scalar<F: PrimeField32>(sk: &[F; DIGEST_SIZE]) -> BigUint {
+    field_elts_to_biguint(sk) % order::<F>()
+}
+
+/// Reduces a digest to a secret key
+pub(crate) fn secret_key<F: PrimeField32>(digest: &[F; DIGEST_SIZE]) -> [F; DIGEST_SIZE] {
+    biguint_to_field_elts(&secret_scalar(digest))
+}
+
+pub(crate) fn public_key<F: PrimeField32>(sk: &[F; DIGEST_SIZE]) -> Point<F> {
+    generator().mul(&secret_scalar(sk))
+}
+
+/// The challenge `h` of a signature, from the first 6 elements of the hash of `R`, the
+/// public key and the signed value. Each element is taken as a 32-bit digit.
+fn challenge<F: PrimeField32, C: Chipset<F>>(
+    zstore: &mut ZStore<F, C>,
+    r: &Point<F>,
+    pk: &Point<F>,
+    msg: &ZPtr<F>,
+) -> BigUint {
+    let mut preimg = [F::zero(); HASH4_SIZE];
+    for (elt, coord) in preimg
+        .iter_mut()
+        .zip(r.to_vec().into_iter().chain(pk.to_vec()))
+    {
+        *elt = coord;
+    }
+    let points = zstore.hash4(preimg);
+    let mut preimg = [F::zero(); HASH3_SIZE];
+    preimg[..DIGEST_SIZE].copy_from_slice(&points);
+    preimg[DIGEST_SIZE..].copy_from_slice(&msg.flatten());
+    let digest = zstore.hash3(preimg);
+    digest[..6].iter().rev().fold(BigUint::ZERO, |acc, elt| {
+        (acc << 32) + elt.as_canonical_u32()
+    })
+}
+
+/// Signs a value, returning `(R, s)`. The nonce is derived from the secret key, the
+/// public key and the value, so signing the same value twice gives the same signature.
+pub(crate) fn sign<F: PrimeField32, C: Chipset<F>>(
+    zstore: &mut ZStore<F, C>,
+    sk: &[F; DIGEST_SIZE],
+    msg: &ZPtr<F>,
+) -> (Point<F>, [F; DIGEST_SIZE]) {
+    let order = order::<F>();
+    let a = secret_scalar(sk);
+    let pk = generator().mul(&a);
+    let mut preimg = [F::zero(); HASH5_SIZE];
+    preimg[..DIGEST_SIZE].copy_from_slice(sk);
+    preimg[DIGEST_SIZE..DIGEST_SIZE + 2 * EXT_DEGREE].copy_from_slice(&pk.to_vec());
+    preimg[3 * DIGEST_SIZE..].copy_from_slice(&msg.flatten());
+    let nonce = field_elts_to_biguint(&zstore.hash5(preimg)) % &order;
+    let r = generator().mul(&nonce);
+    let h = challenge(zstore, &r, &pk, msg);
+    let s = (nonce + h * a) % order;
+    (r, biguint_to_field_elts(&s))
+}
+
+/// Checks that `[s]B = R + [h]A`, as the `verify-signature` builtin does
+pub(crate) fn verify<F: PrimeField32, C: Chipset<F>>(
+    zstore: &mut ZStore<F, C>,
+    pk: &Point<F>,
+    msg: &ZPtr<F>,
+    (r, s): &(Point<F>, [F; DIGEST_SIZE]),
+) -> bool {
+    let s = field_elts_to_biguint(s);
+    if !pk.is_on_curve() || !r.is_on_curve() || s >= order::<F>() {
+        return false;
+    }
+    let h = challenge(zstore, r, pk, msg);
+    generator().mul(&s) == r.add(&pk.mul(&h))
+}
+
+/// Interns a point as the pair of its coordinates, each as a BigNum
+pub(crate) fn intern_point<F: PrimeField32, C: Chipset<F>>(
+    zstore: &mut ZStore<F, C>,
+    point: &Point<F>,
+) -> ZPtr<F> {
+    let [x, y] = [point.x, point.y].map(|coord| {
+        let mut digest = [F::zero(); DIGEST_SIZE];
+        digest[..EXT_DEGREE].copy_from_slice(&coord);
+        zstore.intern_big_num(digest)
+    });
+    zstore.intern_cons(x, y)
+}
+
+/// Interns a signature as the pair of `R` and `s`
+pub(crate) fn intern_signature<F: PrimeField32, C: Chipset<F>>(
+    zstore: &mut ZStore<F, C>,
+    (r, s): &(Point<F>, [F; DIGEST_SIZE]),
+) -> ZPtr<F> {
+    let r = intern_point(zstore, r);
+    let s = zstore.intern_big_num(*s);
+    zstore.intern_cons(r, s)
+}
+
+impl<F: PrimeField32> Chipset<F> for Edwards {
+    fn input_size(&self) -> usize {
+        match self {
+            Edwards::Add => 4 * EXT_DEGREE,
+            Edwards::Residual => 2 * EXT_DEGREE,
+        }
+    }
+
+    fn output_size(&self) -> usize {
+        match self {
+            Edwards::Add => 2 * EXT_DEGREE,
+            Edwards::Residual => EXT_DEGREE,
+        }
+    }
+
+    fn witness_size(&self) -> usize {
+        match self {
+            Edwards::Add => AddWitness::<F>::witness_size(),
+            Edwards::Residual => ResidualWitness::<F>::witness_size(),
+        }
+    }
+
+    fn require_size(&self) -> usize {
+        0
+    }
+
+    fn execute_simple(&self, input: &[F]) -> Vec<F> {
+        match self {
+            Edwards::Add => {
+                let (p1, p2) = input.split_at(2 * EXT_DEGREE);
+                let (p1, p2) = (Point::from_slice(p1), Point::from_slice(p2));
+                AddWitness::<F>::default().populate(&p1, &p2).to_vec()
+            }
+            Edwards::Residual => {
+                let p = Point::from_slice(input);
+                ResidualWitness::<F>::default().populate(&p).to_vec()
+            }
+        }
+    }
+
+    fn populate_witness(&self, input: &[F], witness: &mut [F]) -> Vec<F> {
+        match self {
+            Edwards::Add => {
+                let (p1, p2) = input.split_at(2 * EXT_DEGREE);
+                let (p1, p2) = (Point::from_slice(p1), Point::from_slice(p2));
+                let witness: &mut AddWitness<F> = witness.borrow_mut();
+                witness.populate(&p1, &p2).to_vec()
+            }
+            Edwards::Residual => {
+                let p = Point::from_slice(input);
+                let witness: &mut ResidualWitness<F> = witness.borrow_mut();
+                witness.populate(&p).to_vec()
+            }
+        }
+    }
+
+    fn eval<AB: AirBuilder<F = F> + LookupBuilder>(
+        &self,
+        builder: &mut AB,
+        is_real: AB::Expr,
+        ins: Vec<AB::Expr>,
+        witness: &[AB::Var],
+        _nonce: AB::Expr,
+        _requires: &[RequireRecord<AB::Var>],
+    ) -> Vec<AB::Expr> {
+        match self {
+            Edwards::Add => {
+                let (p1, p2) = ins.split_at(2 * EXT_DEGREE);
+                let (p1, p2) = (Point::from_slice(p1), Point::from_slice(p2));
+                let witness: &AddWitness<AB::Var> = witness.borrow();
+                let sum = witness.eval(builder, &p1, &p2, is_real);
+                sum.to_vec().into_iter().map(Into::into).collect()
+            }
+            Edwards::Residual => {
+                let p = Point::from_slice(&ins);
+                let witness: &ResidualWitness<AB::Var> = witness.borrow();
+                witness.eval(builder, &p, is_real).to_vec()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use num_bigint::BigUint;
+    use p3_baby_bear::BabyBear as F;
+    use p3_field::{AbstractField, PrimeField32};
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig, StarkMachine};
+
+    use crate::{
+        air::debug::debug_chip_constraints_and_queries_with_sharding,
+        core::{chipset::lurk_chip_map_native, zstore::lurk_zstore},
+        func,
+        gadgets::edwards::Point,
+        lair::{
+            execute::{QueryRecord, Shard},
+            func_chip::FuncChip,
+            lair_chip::{build_chip_vector, build_lair_chip_vector, LairMachineProgram},
+            toplevel::Toplevel,
+        },
+    };
+
+    use super::{generator, order, public_key, sign, verify, EDWARDS_BASES};
+
+    #[test]
+    fn edwards_constants_test() {
+        let g = generator::<F>();
+        assert!(g.is_on_curve());
+        assert_eq!(g.mul(&order::<F>()), Point::identity());
+        let p = BigUint::from(F::ORDER_U32);
+        for (base, next) in EDWARDS_BASES.iter().zip(&EDWARDS_BASES[1..]) {
+            let base = Point::<F>::from_canonical_u32s(base);
+            assert_eq!(base.mul(&p), Point::from_canonical_u32s(next));
+        }
+    }
+
+    #[test]
+    fn sign_and_verify_test() {
+        let zstore = &mut lurk_zstore();
+        let sk = [1, 2, 3, 4, 5, 6, 0, 0].map(F::from_canonical_u32);
+        let pk = public_key(&sk);
+        let msg = zstore.intern_string("hello");
+        let sig = sign(zstore, &sk, &msg);
+        assert!(verify(zstore, &pk, &msg, &sig));
+        assert_eq!(sign(zstore, &sk, &msg), sig);
+
+        let other = zstore.intern_string("bye");
+        assert!(!verify(zstore, &pk, &other, &sig));
+        let other_pk = generator().add(&pk);
+        assert!(!verify(zstore, &other_pk, &msg, &sig));
+        let (r, mut s) = sig;
+        s[0] += F::one();
+        assert!(!verify(zstore, &pk, &msg, &(r, s)));
+    }
+
+    #[test]
+    fn edwards_ops_test() {
+        sp1_core_machine::utils::setup_logger();
+
+        let ops_func = func!(
+        fn ops(p: [14], q: [14]): [35] {
+            let sum: [14] = extern_call(edwards_add, p, q);
+            let double: [14] = extern_call(edwards_add, sum, sum);
+            let residual: [7] = extern_call(edwards_residual, double);
+            return (sum, double, residual)
+        });
+        let lurk_chip_map = lurk_chip_map_native();
+        let toplevel = Toplevel::new(&[ops_func], lurk_chip_map);
+
+        let ops_chip = FuncChip::from_name("ops", &toplevel);
+        let mut queries = QueryRecord::new(&toplevel);
+        let g = generator::<F>();
+        let h = g.mul(&BigUint::from(3u32));
+        let args = g.to_vec().into_iter().chain(h.to_vec()).collect::<Vec<_>>();
+        let out = toplevel
+            .execute_by_name("ops", &args, &mut queries, None)
+            .unwrap();
+        let sum = g.mul(&BigUint::from(4u32));
+        let double = g.mul(&BigUint::from(8u32));
+        let expected = sum
+            .to_vec()
+            .into_iter()
+            .chain(double.to_vec())
+            .chain([F::zero(); 7])
+            .collect::<Vec<_>>();
+        assert_eq!(out.as_ref(), expected);
+
+        let lair_chips = build_lair_chip_vector(&ops_chip);
+        let queries = Arc::new(queries);
+        debug_chip_constraints_and_queries_with_sharding(&queries, &lair_chips, None);
+
+        let config = BabyBearPoseidon2::new();
+        let machine = StarkMachine::new(
+            config,
+            build_chip_vector(&ops_chip),
+            queries.expect_public_values().len(),
+            true,
+        );
+
+        let (pk, _vk) = machine.setup(&LairMachineProgram);
+        let shard = Shard::new_arc(&queries);
+        let mut challenger = machine.config().challenger();
+        machine.debug_constraints(&pk, shard, &mut challenger);
+    }
+}
@@ -5,11 +5,12 @@
 
 use anyhow::{bail, Result};
 use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use super::symbol::Symbol;
 
-use super::package::{Package, SymbolRef};
+use super::package::{Digest, Package, PackageData, SymbolRef};
 
 /// Keeps track of the current package for symbol resolution when reading and printing
 #[derive(Debug)]
@@ -18,6 +19,13 @@ pub struct State {
     symbol_packages: HashMap<SymbolRef, Package>,
 }
 
+/// A serializable snapshot of a `State`
+#[derive(Serialize, Deserialize)]
+pub(crate) struct StateData {
+    current_package: Symbol,
+    packages: Vec<PackageData>,
+}
+
 /// Alias for `Rc<RefCell<State>>`
 pub type StateRcCell = Rc<RefCell<State>>;
 
@@ -100,6 +108,21 @@ impl State {
         self.get_current_package_mut().use_package(package)
     }
 
+    /// Defines a macro in the current package
+    pub(crate) fn add_macro(&mut self, symbol: Digest, fun: Digest) {
+        self.get_current_package_mut().add_macro(symbol, fun)
+    }
+
+    /// Returns the function of a macro defined in the current package
+    pub(crate) fn get_macro(&self, symbol: &Digest) -> Option<&Digest> {
+        self.get_current_package().get_macro(symbol)
+    }
+
+    /// Iterates over the macros defined in all packages
+    pub(crate) fn macros(&self) -> impl Iterator<Item = (&Digest, &Digest)> {
+        self.symbol_packages.values().flat_map(Package::macros)
+    }
+
     /// Formats a symbol to string w.r.t. the current package
     pub fn fmt_to_string(&self, symbol: &Symbol) -> String {
         self.get_current_package().fmt_to_string(symbol)
@@ -180,8 +203,8 @@ impl State {
 
         // bootstrap the lurk package
         let mut lurk_package = Package::new(root_package.intern(LURK_PACKAGE_NAME));
-        for symbol_name in LURK_SYMBOLS {
-            lurk_package.intern(symbol_name);
+        for symbol_name in LURK_SYMBOLS.iter().chain(&READER_SYMBOLS) {
+            lurk_package.intern(*symbol_name);
         }
 
         // bootstrap the builtin package
@@ -214,6 +237,32 @@ impl State {
         state.add_package(meta_package);
         state
     }
+
+    /// Takes a serializable snapshot of the state
+    pub(crate) fn to_data(&self) -> StateData {
+        StateData {
+            current_package: (*self.current_package).clone(),
+            packages: self
+                .symbol_packages
+                .values()
+                .map(Package::to_data)
+                .collect(),
+        }
+    }
+
+    /// Reconstructs a state from a snapshot
+    pub(crate) fn from_data(data: StateData) -> Result<Self> {
+        let StateData {
+            current_package,
+            packages,
+        } = data;
+        let mut state = Self::default();
+        for package in packages {
+            state.add_package(Package::from_data(package));
+        }
+        state.set_current_package(current_package.into())?;
+        Ok(state)
+    }
 }
 
 impl Default for State {
@@ -269,7 +318,11 @@ const USER_PACKAGE_NAME: &str = "lurk-user";
 
 pub(crate) const LURK_SYMBOLS: [&str; 3] = ["nil", "t", "&rest"];
 
-pub(crate) const BUILTIN_SYMBOLS: [&str; 43] = [
+/// Symbols produced by the reader for quasiquoted expressions. They are only
+/// understood by the REPL's macro expander, so the evaluator doesn't know them.
+pub(crate) const READER_SYMBOLS: [&str; 3] = ["quasiquote", "unquote", "unquote-splicing"];
+
+pub(crate) const BUILTIN_SYMBOLS: [&str; 83] = [
     "atom",
     "apply",
     "begin",
@@ -296,6 +349,8 @@ pub(crate) const BUILTIN_SYMBOLS: [&str; 43] = [
     "let",
     "letrec",
     "u64",
+    "i64",
+    "decimal",
     "open",
     "quote",
     "secret",
@@ -311,23 +366,65 @@ pub(crate) const BUILTIN_SYMBOLS: [&str; 43] = [
     ">",
     "<=",
     ">=",
+    "logand",
+    "logor",
+    "logxor",
+    "lognot",
+    "shl",
+    "shr",
+    "modexp",
+    "string-length",
+    "string-append",
+    "substring",
+    "string->list",
+    "list->string",
+    "string=",
+    "string<",
+    "number->string",
+    "string->u64",
+    "vector",
+    "vector-ref",
+    "vector-length",
+    "vector-set",
+    "empty-map",
+    "map-get",
+    "map-put",
+    "map-remove",
+    "map-keys",
+    "num-inv",
+    "num-pow",
+    "num->bits",
+    "bits->num",
+    "num->u64",
+    "sha256",
+    "keccak256",
+    "verify-signature",
+    "merkle-verify",
+    "catch",
+    "match",
+    "loop",
+    "recur",
     "breakpoint",
     "fail",
 ];
 
-pub(crate) const META_SYMBOLS: [&str; 39] = [
+pub(crate) const META_SYMBOLS: [&str; 48] = [
     "def",
     "defq",
     "defrec",
+    "defmacro",
     "update",
     "load",
     "assert",
     "assert-eq",
     "assert-emitted",
+    "emitted",
     "assert-error",
     "debug",
     "hide",
     "rand",
+    "keygen",
+    "sign",
     "commit",
     "open",
     "clear",
@@ -355,4 +452,9 @@ pub(crate) const META_SYMBOLS: [&str; 39] = [
     "microchain-verify",
     "load-ocaml",
     "load-ocaml-expr",
+    "watch",
+    "save-session",
+    "restore-session",
+    "time",
+    "set-fuel",
 ];
@@ -15,6 +15,9 @@ pub enum Syntax<F> {
     U64(Pos, u64),
     /// A i64 integer: -1, -0xff, 1i64, 0xffi64, -1i64, -0xffi64
     I64(Pos, bool, u64),
+    /// A decimal with `DECIMAL_PLACES` fractional digits, stored scaled by
+    /// `DECIMAL_SCALE`: 1.5, -0.25
+    Decimal(Pos, i64),
     /// A big numeric type stored in little-endian
     BigNum(Pos, [F; DIGEST_SIZE]),
     /// A commitment hash digest stored in little-endian
@@ -27,6 +30,12 @@ pub enum Syntax<F> {
     Char(Pos, char),
     /// A quoted expression: 'a, '(1 2)
     Quote(Pos, Box<Syntax<F>>),
+    /// A quasiquoted expression: `a, `(1 ,x)
+    Quasiquote(Pos, Box<Syntax<F>>),
+    /// An unquoted expression inside a quasiquote: ,x
+    Unquote(Pos, Box<Syntax<F>>),
+    /// A list spliced into a quasiquoted list: ,@xs
+    UnquoteSplicing(Pos, Box<Syntax<F>>),
     /// A nil-terminated cons-list of expressions: (1 2 3)
     List(Pos, Vec<Syntax<F>>),
     /// An improper cons-list of expressions: (1 2 . 3)
@@ -44,12 +53,16 @@ impl<F> Syntax<F> {
             Self::Num(pos, _)
             | Self::U64(pos, _)
             | Self::I64(pos, ..)
+            | Self::Decimal(pos, _)
             | Self::BigNum(pos, _)
             | Self::Comm(pos, _)
             | Self::Symbol(pos, _)
             | Self::String(pos, _)
             | Self::Char(pos, _)
             | Self::Quote(pos, _)
+            | Self::Quasiquote(pos, _)
+            | Self::Unquote(pos, _)
+            | Self::UnquoteSplicing(pos, _)
             | Self::List(pos, _)
             | Self::Improper(pos, ..)
             | Self::Meta(pos, ..)
@@ -58,12 +71,43 @@ impl<F> Syntax<F> {
     }
 }
 
+/// Returns the `i64` with a given sign and magnitude, as parsed in `Syntax::I64`,
+/// or `None` if it's out of range
+pub(crate) fn i64_from_sign_magnitude(neg: bool, magnitude: u64) -> Option<i64> {
+    if neg {
+        0i64.checked_sub_unsigned(magnitude)
+    } else {
+        i64::try_from(magnitude).ok()
+    }
+}
+
+/// The number of fractional digits carried by decimals
+pub(crate) const DECIMAL_PLACES: usize = 6;
+
+/// The factor by which decimals are scaled to be stored as `i64`s
+pub(crate) const DECIMAL_SCALE: u64 = 10u64.pow(DECIMAL_PLACES as u32);
+
+/// Formats a decimal stored scaled by `DECIMAL_SCALE`, without trailing zeros
+/// in the fractional part: 1.5, -0.25, 3.0
+pub(crate) fn fmt_decimal(x: i64) -> String {
+    let sign = if x < 0 { "-" } else { "" };
+    let (int, frac) = (
+        x.unsigned_abs() / DECIMAL_SCALE,
+        x.unsigned_abs() % DECIMAL_SCALE,
+    );
+    let frac = format!("{frac:0width$}", width = DECIMAL_PLACES);
+    let frac = frac.trim_end_matches('0');
+    let frac = if frac.is_empty() { "0" } else { frac };
+    format!("{sign}{int}.{frac}")
+}
+
 impl<F: fmt::Display + PrimeField> fmt::Display for Syntax<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Num(_, x) => write!(f, "{x}"),
             Self::U64(_, x) => write!(f, "{x}u64"),
             Self::I64(_, sign, x) => write!(f, "{}{x}i64", if *sign { "-" } else { "" }),
+            Self::Decimal(_, x) => write!(f, "{}", fmt_decimal(*x)),
             Self::BigNum(_, c) => write!(f, "#{:#x}", field_elts_to_biguint(c)),
             Self::Comm(_, c) => write!(f, "#c{:#x}", field_elts_to_biguint(c)),
             Self::Symbol(_, x) => write!(f, "{x}"),
@@ -76,6 +120,9 @@ impl<F: fmt::Display + PrimeField> fmt::Display for Syntax<F> {
                 }
             }
             Self::Quote(_, x) => write!(f, "'{x}"),
+            Self::Quasiquote(_, x) => write!(f, "`{x}"),
+            Self::Unquote(_, x) => write!(f, ",{x}"),
+            Self::UnquoteSplicing(_, x) => write!(f, ",@{x}"),
             Self::List(_, xs) => {
                 let mut iter = xs.iter().peekable();
                 write!(f, "(")?;
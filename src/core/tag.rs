@@ -36,6 +36,10 @@ pub enum Tag {
     Env,
     Fix,
     Err,
+    I64,
+    Vec,
+    Map,
+    Decimal,
 }
 
 impl Tag {
@@ -66,7 +70,7 @@ mod test {
 
     #[test]
     fn test_strum() {
-        assert_eq!(15, Tag::COUNT);
+        assert_eq!(19, Tag::COUNT);
         assert_eq!(Tag::COUNT, Tag::iter().count());
     }
 
@@ -12,12 +12,13 @@ use crate::{
         chipset::{lurk_hasher, LurkChip},
         error::EvalErr,
         eval_compiled::build_lurk_toplevel_native,
+        eval_direct,
         state::{builtin_sym, user_sym},
         symbol::Symbol,
         tag::Tag,
         zstore::{ZPtr, ZStore},
     },
-    lair::{chipset::NoChip, toplevel::Toplevel},
+    lair::{chipset::NoChip, execute::QueryRecord, toplevel::Toplevel},
 };
 
 use super::run_tests;
@@ -63,7 +64,7 @@ fn test_case_raw(
 fn test_case(input_code: &'static str, expected_cloj: fn(&mut ZStore<F, LurkChip>) -> ZPtr<F>) {
     let (toplevel, zstore, config) = test_setup_data();
     let mut zstore = zstore.clone();
-    let zptr = zstore.read(input_code, &Default::default());
+    let zptr = zstore.read(input_code, &Default::default()).unwrap();
     run_tests(
         &zptr,
         &ZPtr::null(Tag::Env),
@@ -140,6 +141,46 @@ fn uint(u: u64) -> ZPtr<F> {
     ZPtr::u64(u)
 }
 
+fn unbound_var(zstore: &mut ZStore<F, LurkChip>, name: &str) -> ZPtr<F> {
+    let var = zstore.intern_symbol_no_lang(&user_sym(name));
+    let nil = *zstore.nil();
+    zstore.intern_error_with_payload(EvalErr::UnboundVar, var, nil)
+}
+
+/// An error raised by a unary builtin, carrying the offending value
+fn unop_err(zstore: &mut ZStore<F, LurkChip>, err: EvalErr, name: &str, val: ZPtr<F>) -> ZPtr<F> {
+    let builtin = zstore.intern_symbol_no_lang(&builtin_sym(name));
+    zstore.intern_error_with_payload(err, val, builtin)
+}
+
+/// An error raised by a binary builtin, carrying the list of arguments
+fn binop_err(
+    zstore: &mut ZStore<F, LurkChip>,
+    err: EvalErr,
+    name: &str,
+    args: &[ZPtr<F>],
+) -> ZPtr<F> {
+    let builtin = zstore.intern_symbol_no_lang(&builtin_sym(name));
+    let args = zstore.intern_list(args.iter().copied());
+    zstore.intern_error_with_payload(err, args, builtin)
+}
+
+/// An error carrying a payload given as Lurk source, raised by the builtin `name` or
+/// outside of any builtin if `None`
+fn read_err(
+    zstore: &mut ZStore<F, LurkChip>,
+    err: EvalErr,
+    name: Option<&str>,
+    payload: &str,
+) -> ZPtr<F> {
+    let payload = zstore.read(payload, &Default::default()).unwrap();
+    let builtin = match name {
+        Some(name) => zstore.intern_symbol_no_lang(&builtin_sym(name)),
+        None => *zstore.nil(),
+    };
+    zstore.intern_error_with_payload(err, payload, builtin)
+}
+
 // self-evaluating
 test!(test_num, "1", |_| uint(1));
 test!(test_char, "'a'", |_| ZPtr::char('a'));
@@ -188,12 +229,10 @@ test!(
     "((lambda (x &rest y) (car (cdr y))) 1 2 3 4)",
     |_| uint(3)
 );
-test!(test_app_err, "(a)", |_| ZPtr::err(EvalErr::UnboundVar));
-test!(test_app_err2, "((lambda () a) 2)", |_| ZPtr::err(
-    EvalErr::UnboundVar
-));
+test!(test_app_err, "(a)", |z| unbound_var(z, "a"));
+test!(test_app_err2, "((lambda () a) 2)", |z| unbound_var(z, "a"));
 // TODO FIXME
-// test!(test_app_err3, "(apply (lambda (x) x) 1)", |_| ZPtr::err(
+// test!(test_app_err3, "(apply (lambda (x) x) 1)", |z| z.intern_error(
 //     EvalErr::ArgsNotList
 // ));
 
@@ -430,7 +469,7 @@ test!(
               (x a)
               (even? (lambda (n) (if (= n 0) t (odd? (- n 1))))))
        (odd? 1))",
-    |_| ZPtr::err(EvalErr::UnboundVar)
+    |z| unbound_var(z, "a")
 );
 test!(
     test_fib,
@@ -470,9 +509,13 @@ test!(test_commit, "(commit 123)", |_| {
 //     preimg.extend(uint(123).flatten());
 //     ZPtr::comm(hasher.hash(&preimg).try_into().unwrap())
 // });
-test!(test_hide2, "(hide (commit 321) 123)", |_| ZPtr::err(
-    EvalErr::NotBigNum
-));
+test!(test_hide2, "(hide (commit 321) 123)", |z| {
+    let mut preimg = Vec::with_capacity(24);
+    preimg.extend([F::zero(); 8]);
+    preimg.extend(uint(321).flatten());
+    let comm = ZPtr::comm(lurk_hasher().hash(&preimg).try_into().unwrap());
+    unop_err(z, EvalErr::NotBigNum, "hide", comm)
+});
 test!(test_open_roundtrip, "(open (commit 123))", |_| uint(123));
 test!(
     test_open_raw_roundtrip,
@@ -597,7 +640,7 @@ test!(
 );
 
 // errors
-test!(test_unbound_var, "a", |_| ZPtr::err(EvalErr::UnboundVar));
+test!(test_unbound_var, "a", |z| unbound_var(z, "a"));
 test_raw!(
     test_unbound_var2,
     |z| {
@@ -613,69 +656,203 @@ test_raw!(
         let bindings = z.intern_list([binding]);
         z.intern_list([let_, bindings, cons_sym])
     },
-    |_| ZPtr::err(EvalErr::UnboundVar)
+    |z| {
+        let cons = z.intern_symbol_no_lang(&builtin_sym("cons"));
+        let cons_sym = ZPtr {
+            tag: Tag::Sym,
+            ..cons
+        };
+        let nil = *z.nil();
+        z.intern_error_with_payload(EvalErr::UnboundVar, cons_sym, nil)
+    }
 );
 
-test!(invalid_form_let, "(let ((a 1)))", |_| ZPtr::err(
-    EvalErr::InvalidForm
+test!(invalid_form_let, "(let ((a 1)))", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("let"),
+    "(((a 1)))"
 ));
-test!(invalid_form_letrec, "(letrec ((a 1)))", |_| ZPtr::err(
-    EvalErr::InvalidForm
+test!(invalid_form_letrec, "(letrec ((a 1)))", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("letrec"),
+    "(((a 1)))"
 ));
-test!(invalid_form_lambda, "(lambda (x))", |_| ZPtr::err(
-    EvalErr::InvalidForm
+test!(invalid_form_lambda, "(lambda (x))", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("lambda"),
+    "((x))"
 ));
 
-test!(test_div_by_zero_fel, "(/ 1n 0n)", |_| ZPtr::err(
-    EvalErr::DivByZero
+test!(test_div_by_zero_fel, "(/ 1n 0n)", |z| binop_err(
+    z,
+    EvalErr::DivByZero,
+    "/",
+    &[ZPtr::num(F::one()), ZPtr::num(F::zero())]
 ));
-test!(test_div_by_zero, "(/ 1 0)", |_| ZPtr::err(
-    EvalErr::DivByZero
+test!(test_div_by_zero, "(/ 1 0)", |z| binop_err(
+    z,
+    EvalErr::DivByZero,
+    "/",
+    &[uint(1), uint(0)]
 ));
-test!(test_equal_non_num, "(= 'a 'a)", |_| ZPtr::err(
-    EvalErr::InvalidArg
+test!(test_equal_non_num, "(= 'a 'a)", |z| binop_err(
+    z,
+    EvalErr::InvalidArg,
+    "=",
+    &[ZPtr::char('a'); 2]
 ));
-test!(test_equal_non_num2, "(= (comm #0x0) (comm #0x0))", |_| {
-    ZPtr::err(EvalErr::InvalidArg)
+test!(test_equal_non_num2, "(= (comm #0x0) (comm #0x0))", |z| {
+    binop_err(
+        z,
+        EvalErr::InvalidArg,
+        "=",
+        &[ZPtr::comm([F::zero(); 8]); 2],
+    )
+});
+
+// match
+test!(test_match_var, "(match (+ 1 2) (x (* x x)))", |_| uint(9));
+test!(
+    test_match_cons,
+    "(match '(1 2) ((list a b) (+ a b)) (_ 0))",
+    |_| uint(3)
+);
+test!(
+    test_match_guard,
+    "(match 5 (x (< x 3) 0) (x (< x 10) 1) (_ 2))",
+    |_| uint(1)
+);
+test!(
+    test_match_nested,
+    "(match '(1 (2 . 3) b) ((list _ (cons x y) 'b) (+ x y)) (_ 0))",
+    |_| uint(5)
+);
+test!(
+    test_match_type,
+    "(match nil ((type-eqq a s) s) (_ 0))",
+    |z| *z.nil()
+);
+test!(test_match_no_match, "(match 3 (1 0) (2 0))", |z| {
+    read_err(z, EvalErr::NoMatch, Some("match"), "3")
+});
+test!(test_match_invalid_pattern, "(match 1 ((foo x) x))", |z| {
+    read_err(z, EvalErr::InvalidForm, Some("match"), "(foo x)")
 });
+test!(test_match_invalid_clause, "(match 1 (x))", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("match"),
+    "(x)"
+));
 // TODO FIXME
 // test!(
 //     test_shadow_err1,
 //     "(let ((nil 1)) (+ nil 1))",
-//     |_| ZPtr::err(EvalErr::IllegalBindingVar)
+//     |z| z.intern_error(EvalErr::IllegalBindingVar)
 // );
-// test!(test_shadow_err2, "(letrec ((nil 1)) (+ nil 1))", |_| {
-//     ZPtr::err(EvalErr::IllegalBindingVar)
+// test!(test_shadow_err2, "(letrec ((nil 1)) (+ nil 1))", |z| {
+//     z.intern_error(EvalErr::IllegalBindingVar)
 // });
-// test!(test_shadow_err3, "((lambda (nil) (+ nil 1)) 1)", |_| {
-//     ZPtr::err(EvalErr::IllegalBindingVar)
+// test!(test_shadow_err3, "((lambda (nil) (+ nil 1)) 1)", |z| {
+//     z.intern_error(EvalErr::IllegalBindingVar)
 // });
-// test!(test_shadow_err4, "(let ((t 1)) (+ t 1))", |_| ZPtr::err(
+// test!(test_shadow_err4, "(let ((t 1)) (+ t 1))", |z| z.intern_error(
 //     EvalErr::IllegalBindingVar
 // ));
-// test!(test_shadow_err5, "(letrec ((t 1)) (+ t 1))", |_| ZPtr::err(
+// test!(test_shadow_err5, "(letrec ((t 1)) (+ t 1))", |z| z.intern_error(
 //     EvalErr::IllegalBindingVar
 // ));
-// test!(test_shadow_err6, "((lambda (t) (+ t 1)) 1)", |_| ZPtr::err(
+// test!(test_shadow_err6, "((lambda (t) (+ t 1)) 1)", |z| z.intern_error(
 //     EvalErr::IllegalBindingVar
 // ));
-// test!(test_shadow_err7, "((lambda (x &rest t) (+ x 1)) 1)", |_| {
-//     ZPtr::err(EvalErr::IllegalBindingVar)
+// test!(test_shadow_err7, "((lambda (x &rest t) (+ x 1)) 1)", |z| {
+//     z.intern_error(EvalErr::IllegalBindingVar)
 // });
 // test!(
 //     test_shadow_err8,
 //     "((lambda (x &rest nil) (+ x 1)) 1)",
-//     |_| ZPtr::err(EvalErr::IllegalBindingVar)
+//     |z| z.intern_error(EvalErr::IllegalBindingVar)
 // );
-// test!(test_rest_err1, "((lambda (x &rest) x) 1)", |_| ZPtr::err(
+// test!(test_rest_err1, "((lambda (x &rest) x) 1)", |z| z.intern_error(
 //     EvalErr::ParamInvalidRest
 // ));
-// test!(test_rest_err2, "((lambda (x &rest y z) x) 1)", |_| {
-//     ZPtr::err(EvalErr::ParamInvalidRest)
+// test!(test_rest_err2, "((lambda (x &rest y z) x) 1)", |z| {
+//     z.intern_error(EvalErr::ParamInvalidRest)
 // });
-// test!(test_rest_err3, "((lambda (&rest y z) z) 1)", |_| ZPtr::err(
+// test!(test_rest_err3, "((lambda (&rest y z) z) 1)", |z| z.intern_error(
 //     EvalErr::ParamInvalidRest
 // ));
-// test!(test_rest_err4, "((lambda (&rest) &rest) 1)", |_| {
-//     ZPtr::err(EvalErr::ParamInvalidRest)
+// test!(test_rest_err4, "((lambda (&rest) &rest) 1)", |z| {
+//     z.intern_error(EvalErr::ParamInvalidRest)
 // });
+
+#[allow(clippy::type_complexity)]
+static DIRECT_SETUP_DATA: OnceCell<(Arc<Toplevel<F, LurkChip, NoChip>>, ZStore<F, LurkChip>)> =
+    OnceCell::new();
+
+/// Executes `lurk_main` on `code` without proving it
+fn execute(
+    toplevel: &Arc<Toplevel<F, LurkChip, NoChip>>,
+    zstore: &ZStore<F, LurkChip>,
+    code: &str,
+) -> Vec<F> {
+    let mut zstore = zstore.clone();
+    let zptr = zstore.read(code, &Default::default()).unwrap();
+    let mut record = QueryRecord::new(toplevel);
+    let hashes3 = std::mem::take(&mut zstore.hashes3_diff);
+    let hashes4 = std::mem::take(&mut zstore.hashes4_diff);
+    let hashes5 = std::mem::take(&mut zstore.hashes5_diff);
+    record.inject_inv_queries_owned("hash3", toplevel, hashes3);
+    record.inject_inv_queries_owned("hash4", toplevel, hashes4);
+    record.inject_inv_queries_owned("hash5", toplevel, hashes5);
+    let mut input = [F::zero(); 24];
+    input[..16].copy_from_slice(&zptr.flatten());
+    let result = toplevel
+        .execute_by_name("lurk_main", &input, &mut record, None)
+        .unwrap();
+    result.to_vec()
+}
+
+/// Checks that both evaluators raise the same error, payload and builtin included
+fn test_case_same_err(input_code: &'static str) {
+    let (toplevel, zstore, _) = test_setup_data();
+    let (direct_toplevel, direct_zstore) = DIRECT_SETUP_DATA.get_or_init(|| {
+        let (toplevel, zstore, _) = eval_direct::build_lurk_toplevel_native();
+        (toplevel, zstore)
+    });
+    let compiled = execute(toplevel, zstore, input_code);
+    let direct = execute(direct_toplevel, direct_zstore, input_code);
+    assert_eq!(compiled[0], Tag::Err.to_field());
+    assert_eq!(compiled, direct);
+}
+
+macro_rules! test_same_err {
+    ($test_func:ident, $input_code:expr) => {
+        #[test]
+        fn $test_func() {
+            test_case_same_err($input_code)
+        }
+    };
+}
+
+// errors shared with `eval_direct`
+test_same_err!(test_same_err_unbound, "(+ a 1)");
+test_same_err!(test_same_err_apply_non_func, "(1 2)");
+test_same_err!(test_same_err_if, "(if)");
+test_same_err!(test_same_err_quote, "(quote 1 2)");
+test_same_err!(test_same_err_car, "(car 1)");
+test_same_err!(test_same_err_u64, "(u64 'a)");
+test_same_err!(test_same_err_add, "(+ 1 'a)");
+test_same_err!(test_same_err_div, "(/ 1 0)");
+test_same_err!(test_same_err_strcons, "(strcons 1 \"a\")");
+test_same_err!(test_same_err_hide, "(hide (commit 1) 2)");
+test_same_err!(test_same_err_list, "(list 1 . 2)");
+test_same_err!(test_same_err_let_bind, "(let (x) x)");
+test_same_err!(test_same_err_let_var, "(let ((1 2)) 1)");
+test_same_err!(test_same_err_let_nil, "(let ((nil 1)) (+ nil 1))");
+test_same_err!(test_same_err_letrec_var, "(letrec ((t 1)) (+ t 1))");
+test_same_err!(test_same_err_lambda_var, "((lambda (x 1) x) 1 2)");
+test_same_err!(test_same_err_match, "(match 3 (1 0))");
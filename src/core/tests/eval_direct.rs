@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use once_cell::sync::OnceCell;
 use p3_baby_bear::BabyBear as F;
-use p3_field::AbstractField;
+use p3_field::{AbstractField, PrimeField32};
 use sp1_stark::baby_bear_poseidon2::BabyBearPoseidon2;
 
 use crate::{
@@ -12,12 +12,13 @@ use crate::{
         chipset::{lurk_hasher, LurkChip},
         error::EvalErr,
         eval_direct::build_lurk_toplevel_native,
+        signature,
         state::{builtin_sym, user_sym},
         symbol::Symbol,
         tag::Tag,
         zstore::{ZPtr, ZStore},
     },
-    lair::{chipset::NoChip, toplevel::Toplevel},
+    lair::{chipset::NoChip, execute::QueryRecord, provenance::DEPTH_W, toplevel::Toplevel},
 };
 
 use super::run_tests;
@@ -63,7 +64,7 @@ fn test_case_raw(
 fn test_case(input_code: &'static str, expected_cloj: fn(&mut ZStore<F, LurkChip>) -> ZPtr<F>) {
     let (toplevel, zstore, config) = test_setup_data();
     let mut zstore = zstore.clone();
-    let zptr = zstore.read(input_code, &Default::default());
+    let zptr = zstore.read(input_code, &Default::default()).unwrap();
     run_tests(
         &zptr,
         &ZPtr::null(Tag::Env),
@@ -81,7 +82,7 @@ fn test_case_env(
 ) {
     let (toplevel, zstore, config) = test_setup_data();
     let mut zstore = zstore.clone();
-    let zptr = zstore.read(input_code, &Default::default());
+    let zptr = zstore.read(input_code, &Default::default()).unwrap();
     let env = env_cloj(&mut zstore);
     run_tests(
         &zptr,
@@ -138,6 +139,47 @@ fn uint(u: u64) -> ZPtr<F> {
     ZPtr::u64(u)
 }
 
+fn unbound_var(zstore: &mut ZStore<F, LurkChip>, name: &str) -> ZPtr<F> {
+    let var = zstore.intern_symbol_no_lang(&user_sym(name));
+    let nil = *zstore.nil();
+    zstore.intern_error_with_payload(EvalErr::UnboundVar, var, nil)
+}
+
+/// An error raised by a unary builtin, carrying the offending value
+fn unop_err(zstore: &mut ZStore<F, LurkChip>, err: EvalErr, name: &str, val: ZPtr<F>) -> ZPtr<F> {
+    let builtin = zstore.intern_symbol_no_lang(&builtin_sym(name));
+    zstore.intern_error_with_payload(err, val, builtin)
+}
+
+/// An error raised by a binary builtin, carrying the list of arguments. Overflow
+/// errors carry no arguments
+fn binop_err(
+    zstore: &mut ZStore<F, LurkChip>,
+    err: EvalErr,
+    name: &str,
+    args: &[ZPtr<F>],
+) -> ZPtr<F> {
+    let builtin = zstore.intern_symbol_no_lang(&builtin_sym(name));
+    let args = zstore.intern_list(args.iter().copied());
+    zstore.intern_error_with_payload(err, args, builtin)
+}
+
+/// An error carrying a payload given as Lurk source, raised by the builtin `name` or
+/// outside of any builtin if `None`
+fn read_err(
+    zstore: &mut ZStore<F, LurkChip>,
+    err: EvalErr,
+    name: Option<&str>,
+    payload: &str,
+) -> ZPtr<F> {
+    let payload = zstore.read(payload, &Default::default()).unwrap();
+    let builtin = match name {
+        Some(name) => zstore.intern_symbol_no_lang(&builtin_sym(name)),
+        None => *zstore.nil(),
+    };
+    zstore.intern_error_with_payload(err, payload, builtin)
+}
+
 // self-evaluating
 test!(test_num, "1", |_| uint(1));
 test!(test_char, "'a'", |_| ZPtr::char('a'));
@@ -145,6 +187,8 @@ test!(test_str, "\"abc\"", |z| z.intern_string("abc"));
 test!(test_key, ":hi", |z| z
     .intern_symbol_no_lang(&Symbol::key(&["hi"])));
 test!(test_u64, "1u64", |_| ZPtr::u64(1));
+test!(test_i64, "-1", |_| ZPtr::i64(-1));
+test!(test_decimal, "-1.5", |_| ZPtr::decimal(-1_500_000));
 test!(test_field_elem, "1n", |_| ZPtr::num(F::one()));
 test!(test_t, "t", |z| *z.t());
 test!(test_nil, "nil", |z| *z.nil());
@@ -184,12 +228,13 @@ test!(
     "((lambda (x &rest y) (car (cdr y))) 1 2 3 4)",
     |_| uint(3)
 );
-test!(test_app_err, "(a)", |_| ZPtr::err(EvalErr::UnboundVar));
-test!(test_app_err2, "((lambda () a) 2)", |_| ZPtr::err(
-    EvalErr::UnboundVar
-));
-test!(test_app_err3, "(apply (lambda (x) x) 1)", |_| ZPtr::err(
-    EvalErr::ArgsNotList
+test!(test_app_err, "(a)", |z| unbound_var(z, "a"));
+test!(test_app_err2, "((lambda () a) 2)", |z| unbound_var(z, "a"));
+test!(test_app_err3, "(apply (lambda (x) x) 1)", |z| read_err(
+    z,
+    EvalErr::ArgsNotList,
+    None,
+    "1"
 ));
 
 // builtins
@@ -241,6 +286,118 @@ test!(
     "(<= 3844955657946763191 3844955657946763191)",
     |z| *z.t()
 );
+test!(test_i64_add, "(+ -5 3i64)", |_| ZPtr::i64(-2));
+test!(test_i64_sub, "(- 2i64 5i64)", |_| ZPtr::i64(-3));
+test!(test_i64_mul, "(* -4 -3)", |_| ZPtr::i64(12));
+test!(test_i64_div, "(/ -7 2i64)", |_| ZPtr::i64(-3));
+test!(test_i64_div2, "(/ -7 -2)", |_| ZPtr::i64(3));
+test!(test_i64_mod, "(% -7 2i64)", |_| ZPtr::i64(-1));
+test!(test_i64_mod2, "(% 7i64 -2)", |_| ZPtr::i64(1));
+test!(test_i64_div_overflow, "(/ -9223372036854775808 -1)", |_| {
+    ZPtr::i64(i64::MIN.wrapping_div(-1))
+});
+test!(test_i64_mod_overflow, "(% -9223372036854775808 -1)", |_| {
+    ZPtr::i64(i64::MIN.wrapping_rem(-1))
+});
+test!(test_i64_order1, "(< -1 0i64)", |z| *z.t());
+test!(test_i64_order2, "(< -2 -1)", |z| *z.t());
+test!(test_i64_order3, "(> -1 1i64)", |z| *z.nil());
+test!(
+    test_i64_order4,
+    "(<= -9223372036854775808 9223372036854775807i64)",
+    |z| *z.t()
+);
+test!(test_i64_order5, "(>= -1 -1)", |z| *z.t());
+test!(test_i64_eq, "(= -1 -1)", |z| *z.t());
+test!(test_i64_mixed, "(+ -1 1)", |z| binop_err(
+    z,
+    EvalErr::InvalidArg,
+    "+",
+    &[ZPtr::i64(-1), uint(1)]
+));
+test!(test_i64_div_by_zero, "(/ -1 0i64)", |z| binop_err(
+    z,
+    EvalErr::DivByZero,
+    "/",
+    &[ZPtr::i64(-1), ZPtr::i64(0)]
+));
+test!(test_decimal_add, "(+ 1.5 2.25)", |_| ZPtr::decimal(
+    3_750_000
+));
+test!(test_decimal_sub, "(- 1.5 2.25)", |_| ZPtr::decimal(
+    -750_000
+));
+test!(test_decimal_mul, "(* 1.5 -2.25)", |_| ZPtr::decimal(
+    -3_375_000
+));
+test!(test_decimal_mul2, "(* 1000000.0 1000000.0)", |_| {
+    ZPtr::decimal(1_000_000_000_000_000_000)
+});
+test!(test_decimal_mul_truncate, "(* -0.000001 0.5)", |_| {
+    ZPtr::decimal(0)
+});
+test!(test_decimal_div, "(/ 1.0 3.0)", |_| ZPtr::decimal(333_333));
+test!(test_decimal_div2, "(/ -7.5 2.0)", |_| ZPtr::decimal(
+    -3_750_000
+));
+test!(
+    test_decimal_div3,
+    "(/ 9000000000000.0 7000000000000.0)",
+    |_| ZPtr::decimal(1_285_714)
+);
+test!(test_decimal_mod, "(% -7.5 2.0)", |_| ZPtr::decimal(
+    -1_500_000
+));
+test!(test_decimal_order1, "(< -0.5 0.25)", |z| *z.t());
+test!(test_decimal_order2, "(>= 0.1 0.25)", |z| *z.nil());
+test!(test_decimal_eq, "(= 1.50 1.5)", |z| *z.t());
+test!(test_decimal_mixed, "(+ 1.5 1)", |z| binop_err(
+    z,
+    EvalErr::InvalidArg,
+    "+",
+    &[ZPtr::decimal(1_500_000), uint(1)]
+));
+test!(test_decimal_div_by_zero, "(/ 1.5 0.0)", |z| binop_err(
+    z,
+    EvalErr::DivByZero,
+    "/",
+    &[ZPtr::decimal(1_500_000), ZPtr::decimal(0)]
+));
+test!(test_decimal_bitwise, "(logand 1.0 1.0)", |z| binop_err(
+    z,
+    EvalErr::NotU64,
+    "logand",
+    &[ZPtr::decimal(1_000_000); 2]
+));
+test!(test_u64_logand, "(logand 12 10)", |_| uint(8));
+test!(test_u64_logor, "(logor 12 10)", |_| uint(14));
+test!(test_u64_logxor, "(logxor 12 10)", |_| uint(6));
+test!(test_u64_lognot, "(lognot 0)", |_| uint(u64::MAX));
+test!(test_u64_shl, "(shl 1 3)", |_| uint(8));
+test!(test_u64_shl2, "(shl 1 64)", |_| uint(1));
+test!(test_u64_shl3, "(shl 18446744073709551615 4)", |_| uint(
+    u64::MAX << 4
+));
+test!(test_u64_shr, "(shr 256 4)", |_| uint(16));
+test!(test_u64_shr2, "(shr 18446744073709551615 63)", |_| uint(1));
+test!(test_u64_bitwise_num, "(logand 1n 1n)", |z| binop_err(
+    z,
+    EvalErr::NotU64,
+    "logand",
+    &[ZPtr::num(F::one()); 2]
+));
+test!(test_u64_bitwise_i64, "(shl -1 1i64)", |z| binop_err(
+    z,
+    EvalErr::NotU64,
+    "shl",
+    &[ZPtr::i64(-1), ZPtr::i64(1)]
+));
+test!(test_u64_lognot_num, "(lognot 1n)", |z| unop_err(
+    z,
+    EvalErr::NotU64,
+    "lognot",
+    ZPtr::num(F::one())
+));
 test!(test_begin_empty, "(begin)", |z| *z.nil());
 test!(test_begin, "(begin 1 2 3)", |_| uint(3));
 test!(test_list, "(list)", |z| *z.nil());
@@ -260,13 +417,128 @@ test!(test_eval2, "(eval 'x (let ((x 1)) (current-env)))", |_| {
     uint(1)
 });
 test!(test_eval3, "(let ((a '(+ 1 1))) (eval a))", |_| uint(2));
+test!(test_eval_not_env, "(eval 1 2)", |z| unop_err(
+    z,
+    EvalErr::NotEnv,
+    "eval",
+    uint(2)
+));
 test!(test_cons, "(cons 0n 1n)", |z| {
     z.intern_cons(ZPtr::num(F::zero()), ZPtr::num(F::one()))
 });
 test!(test_car, "(car (cons 0 1))", |_| uint(0));
 test!(test_cdr, "(cdr (cons 0 1))", |_| uint(1));
+test!(test_car_invalid_form, "(car 1 2)", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("car"),
+    "(1 2)"
+));
 test!(test_strcons, "(strcons 'a' \"bc\")", |z| z
     .intern_string("abc"));
+test!(test_string_length, "(string-length \"abc\")", |_| uint(3));
+test!(test_string_length_empty, "(string-length \"\")", |_| uint(
+    0
+));
+test!(test_string_append, "(string-append \"ab\" \"cd\")", |z| z
+    .intern_string("abcd"));
+test!(
+    test_string_append_empty,
+    "(string-append \"\" \"x\")",
+    |z| z.intern_string("x")
+);
+test!(test_substring, "(substring \"hello\" 1 4)", |z| z
+    .intern_string("ell"));
+test!(test_substring_full, "(substring \"hello\" 0 5)", |z| z
+    .intern_string("hello"));
+test!(
+    test_substring_out_of_bounds,
+    "(substring \"hello\" 3 6)",
+    |z| read_err(z, EvalErr::InvalidArg, Some("substring"), "(\"hello\" 3 6)")
+);
+test!(test_substring_reversed, "(substring \"hello\" 3 1)", |z| {
+    read_err(z, EvalErr::InvalidArg, Some("substring"), "(\"hello\" 3 1)")
+});
+test!(test_substring_not_u64, "(substring \"hello\" 1n 2)", |z| {
+    let hello = z.intern_string("hello");
+    binop_err(
+        z,
+        EvalErr::NotU64,
+        "substring",
+        &[hello, ZPtr::num(F::one()), uint(2)],
+    )
+});
+test!(
+    test_substring_not_string,
+    "(substring 1 0 1)",
+    |z| unop_err(z, EvalErr::NotString, "substring", uint(1))
+);
+test!(
+    test_substring_invalid_form,
+    "(substring \"hello\" 1)",
+    |z| { read_err(z, EvalErr::InvalidForm, Some("substring"), "(\"hello\" 1)") }
+);
+test!(test_string_to_list, "(string->list \"ab\")", |z| {
+    let (a, b) = (z.intern_char('a'), z.intern_char('b'));
+    z.intern_list([a, b])
+});
+test!(test_string_to_list_empty, "(string->list \"\")", |z| *z
+    .nil());
+test!(test_list_to_string, "(list->string (list 'a' 'b'))", |z| z
+    .intern_string("ab"));
+test!(
+    test_list_to_string_not_char,
+    "(list->string (list 'a' 1))",
+    |z| read_err(z, EvalErr::NotChar, Some("list->string"), "('a' 1)")
+);
+test!(test_list_to_string_not_list, "(list->string 1)", |z| {
+    read_err(z, EvalErr::NotCons, Some("list->string"), "1")
+});
+test!(test_string_eq1, "(string= \"ab\" \"ab\")", |z| *z.t());
+test!(test_string_eq2, "(string= \"ab\" \"ac\")", |z| *z.nil());
+test!(test_string_eq_not_string, "(string= \"a\" 'a')", |z| {
+    let a = z.intern_string("a");
+    binop_err(z, EvalErr::NotString, "string=", &[a, ZPtr::char('a')])
+});
+test!(test_string_less1, "(string< \"ab\" \"ac\")", |z| *z.t());
+test!(test_string_less2, "(string< \"ab\" \"ab\")", |z| *z.nil());
+test!(test_string_less3, "(string< \"ab\" \"a\")", |z| *z.nil());
+test!(test_string_less4, "(string< \"\" \"a\")", |z| *z.t());
+test!(test_number_to_string, "(number->string 1230)", |z| z
+    .intern_string("1230"));
+test!(test_number_to_string_zero, "(number->string 0)", |z| z
+    .intern_string("0"));
+test!(test_number_to_string_neg, "(number->string -45)", |z| z
+    .intern_string("-45"));
+test!(
+    test_number_to_string_max,
+    "(number->string 18446744073709551615)",
+    |z| z.intern_string("18446744073709551615")
+);
+test!(test_string_to_u64, "(string->u64 \"1230\")", |_| uint(1230));
+test!(
+    test_string_to_u64_max,
+    "(string->u64 \"18446744073709551615\")",
+    |_| uint(u64::MAX)
+);
+test!(
+    test_string_to_u64_overflow,
+    "(string->u64 \"18446744073709551616\")",
+    |z| read_err(
+        z,
+        EvalErr::Overflow,
+        Some("string->u64"),
+        "\"18446744073709551616\""
+    )
+);
+test!(test_string_to_u64_not_digit, "(string->u64 \"12a\")", |z| {
+    read_err(z, EvalErr::InvalidArg, Some("string->u64"), "\"12a\"")
+});
+test!(
+    test_string_to_u64_empty,
+    "(string->u64 \"\")",
+    |z| unop_err(z, EvalErr::InvalidArg, "string->u64", ZPtr::null(Tag::Str))
+);
 test!(test_eq1, "(eq (cons 1 2) '(1 . 2))", |z| *z.t());
 test!(test_eq2, "(eq (cons 1 3) '(1 . 2))", |z| *z.nil());
 test!(test_eq3, "(eq :a :a)", |z| *z.t());
@@ -322,6 +594,12 @@ test!(test_eq23, "(eq 1n 1n)", |z| *z.t());
 
 test!(test_eqq, "(eqq (1 . 2) (cons 1 2))", |z| *z.t());
 test!(test_eqq2, "(eqq (cons 1 2) (cons 1 2))", |z| *z.nil());
+test!(test_eq_invalid_form, "(eq 1)", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("eq"),
+    "(1)"
+));
 
 test!(
     test_misc1,
@@ -345,6 +623,55 @@ test!(test_char1, "(char 'a')", |z| z.intern_char('a'));
 test!(test_char2, "(char 97)", |z| z.intern_char('a'));
 test!(test_u64_1, "(u64 97)", |_| uint(97));
 test!(test_u64_2, "(u64 'a')", |_| uint(97));
+test!(test_u64_3, "(u64 -1)", |_| uint(u64::MAX));
+test!(test_i64_1, "(i64 18446744073709551615)", |_| ZPtr::i64(-1));
+test!(test_i64_2, "(i64 -1)", |_| ZPtr::i64(-1));
+test!(test_i64_3, "(i64 'a')", |z| unop_err(
+    z,
+    EvalErr::CantCastToI64,
+    "i64",
+    ZPtr::char('a')
+));
+test!(test_decimal_1, "(decimal 3)", |_| ZPtr::decimal(3_000_000));
+test!(test_decimal_2, "(decimal -2)", |_| ZPtr::decimal(
+    -2_000_000
+));
+test!(test_decimal_3, "(decimal 1.5)", |_| ZPtr::decimal(
+    1_500_000
+));
+test!(test_decimal_4, "(decimal 'a')", |z| unop_err(
+    z,
+    EvalErr::CantCastToDecimal,
+    "decimal",
+    ZPtr::char('a')
+));
+test!(test_decimal_max, "(decimal 9223372036854)", |_| {
+    ZPtr::decimal(9_223_372_036_854_000_000)
+});
+test!(test_decimal_min, "(decimal -9223372036854)", |_| {
+    ZPtr::decimal(-9_223_372_036_854_000_000)
+});
+test!(test_decimal_too_large, "(decimal 9223372036855)", |z| {
+    unop_err(
+        z,
+        EvalErr::CantCastToDecimal,
+        "decimal",
+        uint(9_223_372_036_855),
+    )
+});
+test!(test_decimal_too_small, "(decimal -9223372036855)", |z| {
+    unop_err(
+        z,
+        EvalErr::CantCastToDecimal,
+        "decimal",
+        ZPtr::i64(-9_223_372_036_855),
+    )
+});
+test!(
+    test_decimal_u64_max,
+    "(decimal 18446744073709551615)",
+    |z| unop_err(z, EvalErr::CantCastToDecimal, "decimal", uint(u64::MAX))
+);
 
 // environment
 test!(
@@ -371,6 +698,15 @@ test!(test_env_builtin3, "(env (list 'a 1 2))", |z| {
     let val = z.intern_list([one, two]);
     z.intern_env(a, val, empty_env)
 });
+test!(test_env_builtin_not_cons, "(env 1)", |z| unop_err(
+    z,
+    EvalErr::NotCons,
+    "env",
+    uint(1)
+));
+test!(test_env_builtin_illegal_var, "(env (cons 1 2))", |z| {
+    unop_err(z, EvalErr::IllegalBindingVar, "env", uint(1))
+});
 test!(test_env_literal, "{ b: 2, a: 1 }", |z| {
     let empty_env = z.intern_empty_env();
     let b = z.intern_symbol_no_lang(&user_sym("b"));
@@ -400,8 +736,9 @@ test!(
     "(eval 'a '{ a: (1 1), b: 2 })",
     |z| { z.intern_list([uint(1), uint(1)]) }
 );
-test!(test_env_literal_err, "{ a: (1 1), b: 2 }", |_| {
-    ZPtr::err(EvalErr::ApplyNonFunc)
+test!(test_env_literal_err, "{ a: (1 1), b: 2 }", |z| {
+    let nil = *z.nil();
+    z.intern_error_with_payload(EvalErr::ApplyNonFunc, uint(1), nil)
 });
 test!(
     test_env_literal_ordering,
@@ -413,6 +750,18 @@ test!(
     "(bind 'a (- 2 1) (current-env))",
     trivial_a_1_env
 );
+test!(test_bind_builtin_not_env, "(bind 'a 1 2)", |z| unop_err(
+    z,
+    EvalErr::NotEnv,
+    "bind",
+    uint(2)
+));
+test!(test_bind_builtin_invalid_form, "(bind 'a 1)", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("bind"),
+    "('a 1)"
+));
 
 // heavier computations
 test!(
@@ -455,7 +804,7 @@ test!(
               (x a)
               (even? (lambda (n) (if (= n 0) t (odd? (- n 1))))))
        (odd? 1))",
-    |_| ZPtr::err(EvalErr::UnboundVar)
+    |z| unbound_var(z, "a")
 );
 test!(
     test_fib,
@@ -493,9 +842,13 @@ test!(test_hide, "(hide (bignum (commit 321)) 123)", |_| {
     preimg.extend(uint(123).flatten());
     ZPtr::comm(hasher.hash(&preimg).try_into().unwrap())
 });
-test!(test_hide2, "(hide (commit 321) 123)", |_| ZPtr::err(
-    EvalErr::NotBigNum
-));
+test!(test_hide2, "(hide (commit 321) 123)", |z| {
+    let mut preimg = Vec::with_capacity(24);
+    preimg.extend([F::zero(); 8]);
+    preimg.extend(uint(321).flatten());
+    let comm = ZPtr::comm(lurk_hasher().hash(&preimg).try_into().unwrap());
+    unop_err(z, EvalErr::NotBigNum, "hide", comm)
+});
 test!(test_open_roundtrip, "(open (commit 123))", |_| uint(123));
 test!(
     test_open_raw_roundtrip,
@@ -590,6 +943,923 @@ test!(test_big_num_order14, "(<= #0x17084a3b94580234614c1ebde7dbb24bc3cb26ba2a84
 test!(test_big_num_order15, "(eq #0x17084a3b94580234614c1ebde7dbb24bc3cb26ba2a84d1355c06cca90b8fb7 #0x7b4dd31c2678ef3c257cda6a06f0c830aaeab011c2c4e7fa9a27c699550539)", |z| *z.nil());
 test!(test_big_num_order16, "(eq #0x17084a3b94580234614c1ebde7dbb24bc3cb26ba2a84d1355c06cca90b8fb7 #0x17084a3b94580234614c1ebde7dbb24bc3cb26ba2a84d1355c06cca90b8fb7)", |z| *z.t());
 
+fn big_num(digits: &[u32]) -> ZPtr<F> {
+    let mut digest = [F::zero(); 8];
+    for (d, &x) in digest.iter_mut().zip(digits) {
+        *d = F::from_canonical_u32(x);
+    }
+    ZPtr::big_num(digest)
+}
+
+test!(test_big_num_add, "(+ #0x1 #0x2)", |_| big_num(&[3]));
+test!(test_big_num_add_carry, "(+ #0x78000000 #0x1)", |_| big_num(
+    &[0, 1]
+));
+test!(
+    test_big_num_add_overflow,
+    "(+ #0x98c29b8b2f1b6f4c0a66714470a403612c6005c490000627000003c0000000 #0x1)",
+    |z| binop_err(z, EvalErr::Overflow, "+", &[])
+);
+test!(test_big_num_sub, "(- #0x5 #0x2)", |_| big_num(&[3]));
+test!(
+    test_big_num_sub_borrow,
+    "(- #0x78000001 #0x1)",
+    |_| big_num(&[0x78000000])
+);
+test!(test_big_num_sub_underflow, "(- #0x2 #0x5)", |z| binop_err(
+    z,
+    EvalErr::Underflow,
+    "-",
+    &[]
+));
+test!(test_big_num_mul, "(* #0x6 #0x7)", |_| big_num(&[42]));
+test!(test_big_num_mul_zero, "(* #0x6 #0x0)", |_| big_num(&[]));
+test!(
+    test_big_num_mul_overflow,
+    "(* #0x98c29b8b2f1b6f4c0a66714470a403612c6005c490000627000003c0000000 #0x2)",
+    |z| binop_err(z, EvalErr::Overflow, "*", &[])
+);
+test!(
+    test_big_num_mul_wide_overflow,
+    "(* #0x100000000000000000000000000000000000000000000000000 #0x100000000000000000000000000000000000000000000000000)",
+    |z| binop_err(z, EvalErr::Overflow, "*", &[])
+);
+test!(test_big_num_div, "(/ #0x2a #0x5)", |_| big_num(&[8]));
+test!(test_big_num_mod, "(% #0x2a #0x5)", |_| big_num(&[2]));
+test!(test_big_num_div_by_zero, "(/ #0x1 #0x0)", |z| binop_err(
+    z,
+    EvalErr::DivByZero,
+    "/",
+    &[big_num(&[1]), big_num(&[])]
+));
+test!(
+    test_big_num_modexp,
+    "(modexp #0x1234567890abcdef #0x10001 #0x1a5e0000a8c0000168000008)",
+    |_| big_num(&[1632169466, 1729067336, 1149772229])
+);
+test!(
+    test_big_num_modexp_zero_exp,
+    "(modexp #0x2 #0x0 #0x1)",
+    |_| { big_num(&[]) }
+);
+test!(
+    test_big_num_modexp_mod_zero,
+    "(modexp #0x2 #0x3 #0x0)",
+    |z| binop_err(
+        z,
+        EvalErr::DivByZero,
+        "modexp",
+        &[big_num(&[2]), big_num(&[3]), big_num(&[])]
+    )
+);
+test!(test_big_num_modexp_not_big_num, "(modexp 2 3 5)", |z| {
+    binop_err(
+        z,
+        EvalErr::NotBigNum,
+        "modexp",
+        &[uint(2), uint(3), uint(5)],
+    )
+});
+
+// field elements
+test!(test_num_inv, "(* 2n (num-inv 2n))", |_| ZPtr::num(F::one()));
+test!(test_num_inv_zero, "(num-inv 0n)", |z| unop_err(
+    z,
+    EvalErr::DivByZero,
+    "num-inv",
+    ZPtr::num(F::zero())
+));
+test!(test_num_pow, "(num-pow 3n 4)", |_| ZPtr::num(
+    F::from_canonical_u32(81)
+));
+test!(test_num_pow_zero_exp, "(num-pow 3n 0)", |_| ZPtr::num(
+    F::one()
+));
+test!(test_num_pow_not_num, "(num-pow 3 4)", |z| binop_err(
+    z,
+    EvalErr::InvalidArg,
+    "num-pow",
+    &[uint(3), uint(4)]
+));
+test!(test_num_to_u64, "(num->u64 (- 0n 1n))", |_| uint(
+    2013265920
+));
+test!(test_num_to_bits, "(num->bits 6n)", |z| {
+    let bits = (0..32).map(|i| ZPtr::num(F::from_bool(i == 1 || i == 2)));
+    z.intern_list(bits)
+});
+test!(test_num_to_bits_not_num, "(num->bits 1)", |z| unop_err(
+    z,
+    EvalErr::InvalidArg,
+    "num->bits",
+    uint(1)
+));
+test!(
+    test_bits_to_num_roundtrip,
+    "(bits->num (num->bits 123456n))",
+    |_| ZPtr::num(F::from_canonical_u32(123456))
+);
+test!(test_bits_to_num_short, "(bits->num '(1n 0n 1n))", |_| {
+    ZPtr::num(F::from_canonical_u32(5))
+});
+test!(test_bits_to_num_not_bit, "(bits->num '(2n))", |z| {
+    let bits = z.intern_list([ZPtr::num(F::two())]);
+    unop_err(z, EvalErr::InvalidArg, "bits->num", bits)
+});
+test!(
+    test_bits_to_num_not_canonical,
+    "(bits->num '(1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n 1n))",
+    |z| {
+        let bits = z.intern_list([ZPtr::num(F::one()); 32]);
+        unop_err(z, EvalErr::InvalidArg, "bits->num", bits)
+    }
+);
+
+// sha256
+fn digest(zstore: &mut ZStore<F, LurkChip>, words: [u64; 4]) -> ZPtr<F> {
+    zstore.intern_list(words.map(uint))
+}
+
+test!(test_sha256, "(sha256 \"abc\")", |z| digest(
+    z,
+    [
+        0xba7816bf8f01cfea,
+        0x414140de5dae2223,
+        0xb00361a396177a9c,
+        0xb410ff61f20015ad
+    ]
+));
+test!(test_sha256_empty, "(sha256 \"\")", |z| digest(
+    z,
+    [
+        0xe3b0c44298fc1c14,
+        0x9afbf4c8996fb924,
+        0x27ae41e4649b934c,
+        0xa495991b7852b855
+    ]
+));
+test!(
+    test_sha256_two_blocks,
+    "(sha256 \"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq\")",
+    |z| digest(
+        z,
+        [
+            0x248d6a61d20638b8,
+            0xe5c026930c3e6039,
+            0xa33ce45964ff2167,
+            0xf6ecedd419db06c1
+        ]
+    )
+);
+test!(test_sha256_utf8, "(sha256 \"λ→😀\")", |z| digest(
+    z,
+    [
+        0x4682f301c8890f5e,
+        0xeaa62d7787efdbe1,
+        0xcf210d0e673f6803,
+        0x494316da545e1cd1
+    ]
+));
+test!(test_sha256_bytes, "(sha256 '(97 98 99))", |z| digest(
+    z,
+    [
+        0xba7816bf8f01cfea,
+        0x414140de5dae2223,
+        0xb00361a396177a9c,
+        0xb410ff61f20015ad
+    ]
+));
+test!(test_sha256_not_byte, "(sha256 '(97 256))", |z| {
+    let list = z.intern_list([uint(97), uint(256)]);
+    unop_err(z, EvalErr::InvalidArg, "sha256", list)
+});
+test!(test_sha256_invalid, "(sha256 'a')", |z| unop_err(
+    z,
+    EvalErr::InvalidArg,
+    "sha256",
+    ZPtr::char('a')
+));
+
+// keccak256
+test!(test_keccak256, "(keccak256 \"abc\")", |z| digest(
+    z,
+    [
+        0x4e03657aea45a94f,
+        0xc7d47ba826c8d667,
+        0xc0d1e6e33a64a036,
+        0xec44f58fa12d6c45
+    ]
+));
+test!(test_keccak256_empty, "(keccak256 \"\")", |z| digest(
+    z,
+    [
+        0xc5d2460186f7233c,
+        0x927e7db2dcc703c0,
+        0xe500b653ca82273b,
+        0x7bfad8045d85a470
+    ]
+));
+// a single byte of padding is left in the block
+test_raw!(
+    test_keccak256_one_byte_padding,
+    |z| z
+        .read(
+            &format!("(keccak256 \"{}\")", "a".repeat(135)),
+            &Default::default()
+        )
+        .unwrap(),
+    |z| digest(
+        z,
+        [
+            0x34367dc248bbd832,
+            0xf4e3e69dfaac2f92,
+            0x638bd0bbd18f2912,
+            0xba4ef454919cf446
+        ]
+    )
+);
+test_raw!(
+    test_keccak256_two_blocks,
+    |z| z
+        .read(
+            &format!("(keccak256 \"{}\")", "a".repeat(200)),
+            &Default::default()
+        )
+        .unwrap(),
+    |z| digest(
+        z,
+        [
+            0x96ea54061def936c,
+            0x4be90b518992fdc6,
+            0xf12f535068a25622,
+            0x9aca54267b4d084d
+        ]
+    )
+);
+test!(test_keccak256_utf8, "(keccak256 \"λ\")", |z| digest(
+    z,
+    [
+        0xef2cf1649642c0e3,
+        0x9d5008e0fd81e1cc,
+        0x22ff26b2fb434ed0,
+        0x2c86e940d1620bd5
+    ]
+));
+test!(test_keccak256_bytes, "(keccak256 '(97 98 99))", |z| digest(
+    z,
+    [
+        0x4e03657aea45a94f,
+        0xc7d47ba826c8d667,
+        0xc0d1e6e33a64a036,
+        0xec44f58fa12d6c45
+    ]
+));
+test!(test_keccak256_invalid, "(keccak256 'a')", |z| unop_err(
+    z,
+    EvalErr::InvalidArg,
+    "keccak256",
+    ZPtr::char('a')
+));
+
+// signatures
+fn verify_signature_expr(z: &mut ZStore<F, LurkChip>, signed: &str, checked: &str) -> ZPtr<F> {
+    let sk = signature::secret_key(&std::array::from_fn(|i| F::from_canonical_usize(i + 1)));
+    let pk = signature::public_key(&sk);
+    let pk = signature::intern_point(z, &pk);
+    let signed = z.intern_string(signed);
+    let sig = signature::sign(z, &sk, &signed);
+    let sig = signature::intern_signature(z, &sig);
+    let checked = z.intern_string(checked);
+    let quote = z.intern_symbol_no_lang(&builtin_sym("quote"));
+    let [pk, sig] = [pk, sig].map(|arg| z.intern_list([quote, arg]));
+    let verify = z.intern_symbol_no_lang(&builtin_sym("verify-signature"));
+    z.intern_list([verify, pk, checked, sig])
+}
+test_raw!(
+    test_verify_signature,
+    |z| verify_signature_expr(z, "hello", "hello"),
+    |z| *z.t()
+);
+test_raw!(
+    test_verify_signature_wrong_msg,
+    |z| verify_signature_expr(z, "hello", "bye"),
+    |z| *z.nil()
+);
+test!(
+    test_verify_signature_invalid,
+    "(verify-signature '(#0x1 . #0x2) 42 '(#0x3 . #0x4))",
+    |z| read_err(
+        z,
+        EvalErr::InvalidArg,
+        Some("verify-signature"),
+        "((#0x1 . #0x2) 42 (#0x3 . #0x4))"
+    )
+);
+test!(
+    test_verify_signature_not_pairs,
+    "(verify-signature 1 2 3)",
+    |z| read_err(z, EvalErr::InvalidArg, Some("verify-signature"), "(1 2 3)")
+);
+test!(
+    test_verify_signature_off_curve,
+    "(verify-signature '(#0x1 . #0x2) 42 '((#0x3 . #0x4) . #0x5))",
+    |z| *z.nil()
+);
+
+// Merkle paths
+fn merkle_verify_expr(z: &mut ZStore<F, LurkChip>, leaf: ZPtr<F>, idx: usize) -> ZPtr<F> {
+    let leaves = z.intern_list([uint(1), uint(2), uint(3)]);
+    let root = z.intern_merkle_root(&leaves);
+    let path = z.intern_merkle_path(&leaves, idx).unwrap();
+    let path = z.intern_quoted(path);
+    let merkle_verify = z.intern_symbol_no_lang(&builtin_sym("merkle-verify"));
+    z.intern_list([merkle_verify, root, leaf, path])
+}
+test_raw!(
+    test_merkle_verify,
+    |z| merkle_verify_expr(z, uint(3), 2),
+    |z| *z.t()
+);
+test_raw!(
+    test_merkle_verify_left,
+    |z| merkle_verify_expr(z, uint(1), 0),
+    |z| *z.t()
+);
+test_raw!(
+    test_merkle_verify_wrong_leaf,
+    |z| merkle_verify_expr(z, uint(2), 2),
+    |z| *z.nil()
+);
+test_raw!(
+    test_merkle_verify_single_leaf,
+    |z| {
+        let leaves = z.intern_list([uint(1)]);
+        let root = z.intern_merkle_root(&leaves);
+        let nil = *z.nil();
+        let merkle_verify = z.intern_symbol_no_lang(&builtin_sym("merkle-verify"));
+        z.intern_list([merkle_verify, root, uint(1), nil])
+    },
+    |z| *z.t()
+);
+test!(
+    test_merkle_verify_invalid_path,
+    "(merkle-verify #0x1 1 '(#0x2))",
+    |z| read_err(z, EvalErr::InvalidArg, Some("merkle-verify"), "(#0x2)")
+);
+test!(
+    test_merkle_verify_invalid_root,
+    "(merkle-verify 1 1 nil)",
+    |z| unop_err(z, EvalErr::InvalidArg, "merkle-verify", uint(1))
+);
+
+// vectors
+test!(test_vector, "(vector 1 2 3)", |z| z.intern_vector(&[
+    uint(1),
+    uint(2),
+    uint(3)
+]));
+test!(test_vector_empty, "(vector)", |z| z.intern_vector(&[]));
+test!(test_vector_nested, "(vector (vector) 'a')", |z| {
+    let empty = z.intern_vector(&[]);
+    z.intern_vector(&[empty, ZPtr::char('a')])
+});
+test!(test_vector_ref, "(vector-ref (vector 1 2 3 4 5) 4)", |_| {
+    uint(5)
+});
+test!(
+    test_vector_ref2,
+    "(vector-ref (vector 1 2 3 4 5) 2)",
+    |_| uint(3)
+);
+test!(test_vector_ref_single, "(vector-ref (vector 1) 0)", |_| {
+    uint(1)
+});
+test!(
+    test_vector_ref_out_of_bounds,
+    "(vector-ref (vector 1 2 3) 3)",
+    |z| { unop_err(z, EvalErr::InvalidArg, "vector-ref", uint(3)) }
+);
+test!(test_vector_ref_empty, "(vector-ref (vector) 0)", |z| {
+    unop_err(z, EvalErr::InvalidArg, "vector-ref", uint(0))
+});
+test!(test_vector_ref_not_vector, "(vector-ref '(1 2) 0)", |z| {
+    let list = z.intern_list([uint(1), uint(2)]);
+    unop_err(z, EvalErr::NotVector, "vector-ref", list)
+});
+test!(test_vector_length, "(vector-length (vector 1 2 3))", |_| {
+    uint(3)
+});
+test!(test_vector_length_empty, "(vector-length (vector))", |_| {
+    uint(0)
+});
+test!(
+    test_vector_length_not_vector,
+    "(vector-length \"ab\")",
+    |z| {
+        let ab = z.intern_string("ab");
+        unop_err(z, EvalErr::NotVector, "vector-length", ab)
+    }
+);
+test!(test_vector_set, "(vector-set (vector 1 2 3) 2 'a')", |z| z
+    .intern_vector(&[uint(1), uint(2), ZPtr::char('a')]));
+test!(
+    test_vector_set_ref,
+    "(let ((v (vector-set (vector 1 2 3 4 5 6) 5 7))) (vector-ref v 5))",
+    |_| uint(7)
+);
+test!(
+    test_vector_set_out_of_bounds,
+    "(vector-set (vector 1 2 3) 5 0)",
+    |z| { unop_err(z, EvalErr::InvalidArg, "vector-set", uint(5)) }
+);
+test!(
+    test_vector_set_not_u64,
+    "(vector-set (vector 1 2 3) 1n 0)",
+    |z| { unop_err(z, EvalErr::NotU64, "vector-set", ZPtr::num(F::one())) }
+);
+test!(
+    test_vector_eq,
+    "(eq (vector 1 2) (vector-set (vector 1 3) 1 2))",
+    |z| *z.t()
+);
+
+// maps
+fn key(z: &mut ZStore<F, LurkChip>, name: &str) -> ZPtr<F> {
+    z.intern_symbol_no_lang(&Symbol::key(&[name]))
+}
+
+test!(test_empty_map, "(empty-map)", |z| z.intern_map(&[]));
+test!(
+    test_map_put,
+    "(map-put (map-put (empty-map) :a 1) :b 2)",
+    |z| {
+        let (a, b) = (key(z, "a"), key(z, "b"));
+        z.intern_map(&[(a, uint(1)), (b, uint(2))])
+    }
+);
+test!(
+    test_map_put_override,
+    "(map-put (map-put (empty-map) 'x' 1) 'x' 2)",
+    |z| z.intern_map(&[(ZPtr::char('x'), uint(2))])
+);
+test!(
+    test_map_put_many,
+    "(letrec ((build (lambda (n m) (if (= n 0) m (build (- n 1) (map-put m n (* n n)))))))
+       (build 10 (empty-map)))",
+    |z| z.intern_map(&(1..=10).map(|n| (uint(n), uint(n * n))).collect::<Vec<_>>())
+);
+test!(
+    test_map_put_order,
+    "(eq (map-put (map-put (empty-map) :a 1) :b 2) (map-put (map-put (empty-map) :b 2) :a 1))",
+    |z| *z.t()
+);
+test!(
+    test_map_get,
+    "(let ((m (map-put (map-put (map-put (empty-map) :a 1) :b 2) :c 3))) (map-get m :b))",
+    |_| uint(2)
+);
+test!(
+    test_map_get_many,
+    "(letrec ((build (lambda (n m) (if (= n 0) m (build (- n 1) (map-put m n (* n n)))))))
+       (map-get (build 10 (empty-map)) 7))",
+    |_| uint(49)
+);
+test!(
+    test_map_get_missing,
+    "(map-get (map-put (empty-map) :a 1) :b)",
+    |z| *z.nil()
+);
+test!(test_map_get_empty, "(map-get (empty-map) 1)", |z| *z.nil());
+test!(test_map_get_not_map, "(map-get 1 2)", |z| {
+    unop_err(z, EvalErr::NotMap, "map-get", uint(1))
+});
+test!(
+    test_map_remove,
+    "(map-remove (map-put (map-put (empty-map) :a 1) :b 2) :a)",
+    |z| {
+        let b = key(z, "b");
+        z.intern_map(&[(b, uint(2))])
+    }
+);
+test!(
+    test_map_remove_last,
+    "(map-remove (map-put (empty-map) :a 1) :a)",
+    |z| z.intern_map(&[])
+);
+test!(
+    test_map_remove_missing,
+    "(map-remove (map-put (empty-map) :a 1) :b)",
+    |z| {
+        let a = key(z, "a");
+        z.intern_map(&[(a, uint(1))])
+    }
+);
+test!(
+    test_map_remove_many,
+    "(letrec ((build (lambda (n m) (if (= n 0) m (build (- n 1) (map-put m n (* n n)))))))
+       (map-remove (map-remove (build 4 (empty-map)) 2) 3))",
+    |z| z.intern_map(&[(uint(1), uint(1)), (uint(4), uint(16))])
+);
+test!(
+    test_map_keys,
+    "(map-keys (map-put (empty-map) \"k\" nil))",
+    |z| {
+        let k = z.intern_string("k");
+        z.intern_list([k])
+    }
+);
+test!(test_map_keys_empty, "(map-keys (empty-map))", |z| *z.nil());
+test!(
+    test_map_keys_many,
+    "(map-keys (map-put (map-put (map-put (empty-map) :a 1) :b 2) :c 3))",
+    |z| {
+        let (a, b, c) = (key(z, "a"), key(z, "b"), key(z, "c"));
+        let map = z.intern_map(&[(a, uint(1)), (b, uint(2)), (c, uint(3))]);
+        let keys: Vec<_> = z.fetch_map(&map).into_iter().map(|(k, _)| *k).collect();
+        z.intern_list(keys)
+    }
+);
+test!(test_map_keys_not_map, "(map-keys nil)", |z| {
+    let nil = *z.nil();
+    unop_err(z, EvalErr::NotMap, "map-keys", nil)
+});
+test!(test_map_put_not_map, "(map-put (vector) 1 2)", |z| {
+    let vec = z.intern_vector(&[]);
+    unop_err(z, EvalErr::NotMap, "map-put", vec)
+});
+
+// quasiquote
+test!(
+    test_quasiquote,
+    "(let ((b 1) (c '(2 3))) `(a ,b ,@c d))",
+    |z| z.read("(a 1 2 3 d)", &Default::default()).unwrap()
+);
+test!(test_quasiquote_constant, "`(a (b . c))", |z| {
+    z.read("(a (b . c))", &Default::default()).unwrap()
+});
+test!(test_quasiquote_dotted, "(let ((x 1)) `(a . ,x))", |z| {
+    z.read("(a . 1)", &Default::default()).unwrap()
+});
+test!(
+    test_quasiquote_splice,
+    "(let ((xs '(1 2))) `(,@xs ,@xs))",
+    |z| z.read("(1 2 1 2)", &Default::default()).unwrap()
+);
+test!(
+    test_quasiquote_nested,
+    "(let ((x 1)) `(a `(b ,(c ,x))))",
+    |z| {
+        let [a, b, c] = ["a", "b", "c"].map(|name| z.intern_symbol_no_lang(&user_sym(name)));
+        let c_x = z.intern_list([c, uint(1)]);
+        let unquote = z.intern_reader_form("unquote", c_x);
+        let b_list = z.intern_list([b, unquote]);
+        let quasiquote = z.intern_reader_form("quasiquote", b_list);
+        z.intern_list([a, quasiquote])
+    }
+);
+test!(test_quasiquote_quoted, "'`(a ,b)", |z| {
+    let [a, b] = ["a", "b"].map(|name| z.intern_symbol_no_lang(&user_sym(name)));
+    let unquote = z.intern_reader_form("unquote", b);
+    let a_list = z.intern_list([a, unquote]);
+    z.intern_reader_form("quasiquote", a_list)
+});
+
+// emit
+test!(test_emit, "(emit 1)", |_| uint(1));
+test!(test_emit_channel, "(emit :log (+ 1 1))", |_| uint(2));
+test!(test_emit_channel_not_key, "(emit 1 2)", |z| unop_err(
+    z,
+    EvalErr::InvalidArg,
+    "emit",
+    uint(1)
+));
+test!(test_emit_err, "(emit :log (/ 1 0))", |z| binop_err(
+    z,
+    EvalErr::DivByZero,
+    "/",
+    &[uint(1), uint(0)]
+));
+test!(test_emit_invalid, "(emit :log 1 2)", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("emit"),
+    "(:log 1 2)"
+));
+
+// catch
+test!(test_catch_no_err, "(catch (+ 1 2) (lambda (e) 0))", |_| {
+    uint(3)
+});
+test!(test_catch, "(catch (/ 1 0) (lambda (e) 0))", |_| uint(0));
+test!(test_catch_thunk, "(catch (/ 1 0) (lambda () 7))", |_| uint(
+    7
+));
+test!(test_catch_rethrow, "(catch (/ 1 0) (lambda (e) e))", |z| {
+    binop_err(z, EvalErr::DivByZero, "/", &[uint(1), uint(0)])
+});
+test!(
+    test_catch_nested,
+    "(catch (catch (/ 1 0) (lambda (e) e)) (lambda (e) 9))",
+    |_| uint(9)
+);
+test!(
+    test_catch_closure,
+    "(let ((x 5)) (catch (car 1) (lambda (e) x)))",
+    |_| uint(5)
+);
+test!(
+    test_catch_curried,
+    "((catch (car 1) (lambda (e y) y)) 4)",
+    |_| uint(4)
+);
+test!(
+    test_catch_rest,
+    "(catch (car 1) (lambda (&rest e) e))",
+    |z| {
+        let err = unop_err(z, EvalErr::NotCons, "car", uint(1));
+        z.intern_list([err])
+    }
+);
+test!(
+    test_catch_rest2,
+    "(catch (car 1) (lambda (e &rest r) r))",
+    |z| *z.nil()
+);
+test!(
+    test_catch_curried2,
+    "((catch (car 1) (lambda (e y z) z)) 4 5)",
+    |_| uint(5)
+);
+test!(
+    test_catch_rest_err,
+    "(catch (car 1) (lambda (&rest) 0))",
+    |z| { read_err(z, EvalErr::ParamInvalidRest, Some("catch"), "(&rest)") }
+);
+test!(
+    test_catch_rest_err2,
+    "(catch (car 1) (lambda (&rest e f) 0))",
+    |z| { read_err(z, EvalErr::ParamInvalidRest, Some("catch"), "(&rest e f)") }
+);
+test!(
+    test_catch_illegal_param,
+    "(catch (car 1) (lambda (1) 0))",
+    |z| { read_err(z, EvalErr::IllegalBindingVar, Some("catch"), "1") }
+);
+test!(test_catch_not_fun, "(catch (/ 1 0) 1)", |z| {
+    let nil = *z.nil();
+    z.intern_error_with_payload(EvalErr::ApplyNonFunc, uint(1), nil)
+});
+test!(test_catch_invalid_form, "(catch (/ 1 0))", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("catch"),
+    "((/ 1 0))"
+));
+
+// match
+test!(test_match_literal, "(match 2 (1 :one) (2 :two))", |z| z
+    .intern_symbol_no_lang(&Symbol::key(&["two"])));
+test!(test_match_var, "(match (+ 1 2) (x (* x x)))", |_| uint(9));
+test!(test_match_wildcard, "(match 'a (1 :one) (_ :other))", |z| z
+    .intern_symbol_no_lang(&Symbol::key(&["other"])));
+test!(
+    test_match_cons,
+    "(match '(1 2 3) ((cons x rest) (cons rest x)))",
+    |z| {
+        let rest = z.intern_list([uint(2), uint(3)]);
+        z.intern_cons(rest, uint(1))
+    }
+);
+test!(
+    test_match_list,
+    "(match '(1 (2 3)) ((list a (list b c)) (+ a (+ b c))))",
+    |_| uint(6)
+);
+test!(
+    test_match_list_length,
+    "(match '(1 2) ((list a) a) ((list a b c) c) (_ :none))",
+    |z| z.intern_symbol_no_lang(&Symbol::key(&["none"]))
+);
+test!(test_match_quote, "(match 'b ('a 1) ('b 2))", |_| uint(2));
+test!(test_match_quote_wildcard, "(match 'a ('_ 1) (_ 2))", |_| {
+    uint(2)
+});
+test!(test_underscore_binding, "(let ((_ 1)) (+ _ 1))", |_| uint(
+    2
+));
+test!(test_underscore_symbol, "'_", |z| z
+    .intern_symbol_no_lang(&user_sym("_")));
+test!(
+    test_match_type,
+    "(match \"hi\" ((type-eqq 0 n) n) ((type-eqq \"\" s) (string-length s)))",
+    |_| uint(2)
+);
+test!(
+    test_match_guard,
+    "(match 5 (x (< x 3) :small) (x (< x 10) :medium) (_ :large))",
+    |z| z.intern_symbol_no_lang(&Symbol::key(&["medium"]))
+);
+test!(
+    test_match_closure,
+    "(let ((y 10)) (match '(1 . 2) ((cons a b) (+ y (+ a b)))))",
+    |_| uint(13)
+);
+test!(
+    test_match_nil,
+    "(match nil ((cons a b) a) (nil :empty))",
+    |z| z.intern_symbol_no_lang(&Symbol::key(&["empty"]))
+);
+test!(test_match_no_match, "(match 3 (1 :one) (2 :two))", |z| {
+    unop_err(z, EvalErr::NoMatch, "match", uint(3))
+});
+test!(test_match_err, "(match (/ 1 0) (_ 1))", |z| binop_err(
+    z,
+    EvalErr::DivByZero,
+    "/",
+    &[uint(1), uint(0)]
+));
+test!(test_match_guard_err, "(match 1 (x (/ x 0) 1))", |z| {
+    binop_err(z, EvalErr::DivByZero, "/", &[uint(1), uint(0)])
+});
+test!(test_match_invalid_pattern, "(match 1 ((foo x) x))", |z| {
+    read_err(z, EvalErr::InvalidForm, Some("match"), "(foo x)")
+});
+test!(test_match_invalid_clause, "(match 1 (x))", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("match"),
+    "(x)"
+));
+
+// loop
+test!(
+    test_loop_sum,
+    "(loop ((i 0) (acc 0)) (if (= i 10) acc (recur (+ i 1) (+ acc i))))",
+    |_| uint(45)
+);
+test!(test_loop_no_recur, "(loop ((x 1)) (+ x 1))", |_| uint(2));
+test!(test_loop_no_binds, "(loop () 7)", |_| uint(7));
+test!(
+    test_loop_body,
+    "(loop ((n 3) (l nil)) (begin (emit n) (if (= n 0) l (recur (- n 1) (cons n l)))))",
+    |z| z.intern_list([uint(1), uint(2), uint(3)])
+);
+test!(
+    test_loop_parallel_inits,
+    "(let ((x 1)) (loop ((x 2) (y x)) y))",
+    |_| uint(1)
+);
+test!(
+    test_loop_closure,
+    "(let ((step 2)) (loop ((i 0)) (if (< i 9) (recur (+ i step)) i)))",
+    |_| uint(10)
+);
+test!(
+    test_loop_nested,
+    "(loop ((i 0) (acc 0)) (if (= i 3) acc (recur (+ i 1) (loop ((j 0) (acc acc)) (if (= j 2) acc (recur (+ j 1) (+ acc 1)))))))",
+    |_| uint(6)
+);
+test!(
+    test_loop_recur_in_catch,
+    "(loop ((i 0)) (catch (if (= i 2) i (recur (+ i 1))) (lambda (e) :caught)))",
+    |z| read_err(z, EvalErr::InvalidForm, Some("recur"), "(recur (+ i 1))")
+);
+test!(
+    test_loop_recur_not_tail,
+    "(loop ((i 0)) (if (= i 2) i (+ 1 (recur (+ i 1)))))",
+    |z| read_err(z, EvalErr::InvalidForm, Some("recur"), "(recur (+ i 1))")
+);
+test!(
+    test_loop_recur_in_cond,
+    "(loop ((i 0)) (if (recur i) 1 2))",
+    |z| read_err(z, EvalErr::InvalidForm, Some("recur"), "(recur i)")
+);
+test!(
+    test_loop_recur_not_last,
+    "(loop ((i 0)) (recur 1) i)",
+    |z| read_err(z, EvalErr::InvalidForm, Some("recur"), "(recur 1)")
+);
+test!(
+    test_loop_recur_unreached,
+    "(loop ((i 0)) (if (= i 0) i (cons (recur 1) i)))",
+    |z| read_err(z, EvalErr::InvalidForm, Some("recur"), "(recur 1)")
+);
+test!(
+    test_loop_recur_in_lambda,
+    "(loop ((i 0)) (let ((f (lambda (x) (recur x)))) (if (= i 0) (f 1) i)))",
+    |z| read_err(z, EvalErr::InvalidForm, Some("recur"), "(recur x)")
+);
+test!(
+    test_loop_recur_in_called_lambda,
+    "(let ((f (lambda (x) (recur x)))) (loop ((i 0)) (if (= i 0) (f 1) i)))",
+    |z| read_err(z, EvalErr::InvalidForm, Some("recur"), "(x)")
+);
+test!(
+    test_loop_recur_quoted,
+    "(loop ((i 0)) (if (= i 1) '(recur i) (recur (+ i 1))))",
+    |z| z.read("(recur i)", &Default::default()).unwrap()
+);
+test!(
+    test_loop_recur_in_begin,
+    "(loop ((i 0)) (begin (emit i) (if (= i 2) i (begin (recur (+ i 1))))))",
+    |_| uint(2)
+);
+test!(
+    test_loop_recur_in_let,
+    "(loop ((i 0)) (let ((j (+ i 1))) (if (= j 3) j (recur j))))",
+    |_| uint(3)
+);
+test!(
+    test_loop_recur_in_letrec,
+    "(loop ((i 0)) (letrec ((inc (lambda (x) (+ x 1)))) (if (= i 3) i (recur (inc i)))))",
+    |_| uint(3)
+);
+test!(
+    test_loop_recur_in_let_binding,
+    "(loop ((i 0)) (let ((j (recur 1))) j))",
+    |z| read_err(z, EvalErr::InvalidForm, Some("recur"), "(recur 1)")
+);
+test!(
+    test_loop_if_no_else,
+    "(loop ((i 0)) (if (< i 3) (recur (+ i 1))))",
+    |z| *z.nil()
+);
+test!(test_loop_err, "(loop ((x (/ 1 0))) x)", |z| binop_err(
+    z,
+    EvalErr::DivByZero,
+    "/",
+    &[uint(1), uint(0)]
+));
+test!(test_loop_body_err, "(loop ((x 1)) (car x))", |z| unop_err(
+    z,
+    EvalErr::NotCons,
+    "car",
+    uint(1)
+));
+test!(test_loop_arity, "(loop ((x 1)) (recur 1 2))", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("recur"),
+    "(2)"
+));
+test!(test_loop_invalid_binding, "(loop ((1 2)) 3)", |z| unop_err(
+    z,
+    EvalErr::IllegalBindingVar,
+    "loop",
+    uint(1)
+));
+test!(test_loop_invalid, "(loop)", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("loop"),
+    "nil"
+));
+test!(test_recur_outside_loop, "(recur 1 2)", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("recur"),
+    "(1 2)"
+));
+
+/// Executes `lurk_main` on `code` without proving it and returns the depth of the
+/// evaluation
+fn eval_depth(code: &str) -> u32 {
+    let (toplevel, zstore, _) = test_setup_data();
+    let mut zstore = zstore.clone();
+    let zptr = zstore.read(code, &Default::default()).unwrap();
+    let mut record = QueryRecord::new(toplevel);
+    let hashes3 = std::mem::take(&mut zstore.hashes3_diff);
+    let hashes4 = std::mem::take(&mut zstore.hashes4_diff);
+    let hashes5 = std::mem::take(&mut zstore.hashes5_diff);
+    record.inject_inv_queries_owned("hash3", toplevel, hashes3);
+    record.inject_inv_queries_owned("hash4", toplevel, hashes4);
+    record.inject_inv_queries_owned("hash5", toplevel, hashes5);
+    let mut input = [F::zero(); 24];
+    input[..16].copy_from_slice(&zptr.flatten());
+    toplevel
+        .execute_by_name("lurk_main", &input, &mut record, None)
+        .unwrap();
+    let public_values = record.expect_public_values();
+    let depth_bytes = public_values[public_values.len() - DEPTH_W..]
+        .iter()
+        .map(|f| u8::try_from(f.as_canonical_u32()).unwrap())
+        .collect::<Vec<_>>();
+    u32::from_le_bytes(depth_bytes.try_into().unwrap())
+}
+
+#[test]
+fn test_loop_depth() {
+    let loop_depth = |iters: usize| {
+        let code = format!("(loop ((i 1)) (if (= i {iters}) i (recur (+ i 1))))");
+        eval_depth(&code)
+    };
+    // the iterations run in batches of doubling sizes, so going from 2^4 to 2^10
+    // iterations adds 6 batches, each one adding two levels
+    let shallow = loop_depth(1 << 4);
+    let deep = loop_depth(1 << 10);
+    assert!(deep <= shallow + 2 * 6, "{shallow} vs {deep}");
+}
+
 // shadowing built-ins
 test!(test_shadow1, "(let ((cons 1)) (+ cons 1))", |_| uint(2));
 test!(test_shadow2, "(letrec ((cons 1)) (+ cons 1))", |_| uint(2));
@@ -615,7 +1885,7 @@ test!(
 );
 
 // errors
-test!(test_unbound_var, "a", |_| ZPtr::err(EvalErr::UnboundVar));
+test!(test_unbound_var, "a", |z| unbound_var(z, "a"));
 test_raw!(
     test_unbound_var2,
     |z| {
@@ -631,68 +1901,120 @@ test_raw!(
         let bindings = z.intern_list([binding]);
         z.intern_list([let_, bindings, cons_sym])
     },
-    |_| ZPtr::err(EvalErr::UnboundVar)
+    |z| {
+        let cons = z.intern_symbol_no_lang(&builtin_sym("cons"));
+        let cons_sym = ZPtr {
+            tag: Tag::Sym,
+            ..cons
+        };
+        let nil = *z.nil();
+        z.intern_error_with_payload(EvalErr::UnboundVar, cons_sym, nil)
+    }
 );
 
-test!(invalid_form_let, "(let ((a 1)))", |_| ZPtr::err(
-    EvalErr::InvalidForm
+test!(invalid_form_let, "(let ((a 1)))", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("let"),
+    "(((a 1)))"
 ));
-test!(invalid_form_letrec, "(letrec ((a 1)))", |_| ZPtr::err(
-    EvalErr::InvalidForm
+test!(invalid_form_letrec, "(letrec ((a 1)))", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("letrec"),
+    "(((a 1)))"
 ));
-test!(invalid_form_lambda, "(lambda (x))", |_| ZPtr::err(
-    EvalErr::InvalidForm
+test!(invalid_form_lambda, "(lambda (x))", |z| read_err(
+    z,
+    EvalErr::InvalidForm,
+    Some("lambda"),
+    "((x))"
 ));
 
-test!(test_div_by_zero_fel, "(/ 1n 0n)", |_| ZPtr::err(
-    EvalErr::DivByZero
+test!(test_div_by_zero_fel, "(/ 1n 0n)", |z| binop_err(
+    z,
+    EvalErr::DivByZero,
+    "/",
+    &[ZPtr::num(F::one()), ZPtr::num(F::zero())]
 ));
-test!(test_div_by_zero, "(/ 1 0)", |_| ZPtr::err(
-    EvalErr::DivByZero
+test!(test_div_by_zero, "(/ 1 0)", |z| binop_err(
+    z,
+    EvalErr::DivByZero,
+    "/",
+    &[uint(1), uint(0)]
 ));
-test!(test_equal_non_num, "(= 'a 'a)", |_| ZPtr::err(
-    EvalErr::InvalidArg
+test!(test_equal_non_num, "(= 'a 'a)", |z| binop_err(
+    z,
+    EvalErr::InvalidArg,
+    "=",
+    &[ZPtr::char('a'); 2]
 ));
-test!(test_equal_non_num2, "(= (comm #0x0) (comm #0x0))", |_| {
-    ZPtr::err(EvalErr::InvalidArg)
+test!(test_equal_non_num2, "(= (comm #0x0) (comm #0x0))", |z| {
+    binop_err(
+        z,
+        EvalErr::InvalidArg,
+        "=",
+        &[ZPtr::comm([F::zero(); 8]); 2],
+    )
 });
-test!(
-    test_shadow_err1,
-    "(let ((nil 1)) (+ nil 1))",
-    |_| ZPtr::err(EvalErr::IllegalBindingVar)
-);
-test!(test_shadow_err2, "(letrec ((nil 1)) (+ nil 1))", |_| {
-    ZPtr::err(EvalErr::IllegalBindingVar)
+test!(test_shadow_err1, "(let ((nil 1)) (+ nil 1))", |z| read_err(
+    z,
+    EvalErr::IllegalBindingVar,
+    Some("let"),
+    "nil"
+));
+test!(test_shadow_err2, "(letrec ((nil 1)) (+ nil 1))", |z| {
+    read_err(z, EvalErr::IllegalBindingVar, Some("letrec"), "nil")
 });
-test!(test_shadow_err3, "((lambda (nil) (+ nil 1)) 1)", |_| {
-    ZPtr::err(EvalErr::IllegalBindingVar)
+test!(test_shadow_err3, "((lambda (nil) (+ nil 1)) 1)", |z| {
+    read_err(z, EvalErr::IllegalBindingVar, None, "nil")
 });
-test!(test_shadow_err4, "(let ((t 1)) (+ t 1))", |_| ZPtr::err(
-    EvalErr::IllegalBindingVar
+test!(test_shadow_err4, "(let ((t 1)) (+ t 1))", |z| read_err(
+    z,
+    EvalErr::IllegalBindingVar,
+    Some("let"),
+    "t"
 ));
-test!(test_shadow_err5, "(letrec ((t 1)) (+ t 1))", |_| ZPtr::err(
-    EvalErr::IllegalBindingVar
+test!(test_shadow_err5, "(letrec ((t 1)) (+ t 1))", |z| read_err(
+    z,
+    EvalErr::IllegalBindingVar,
+    Some("letrec"),
+    "t"
 ));
-test!(test_shadow_err6, "((lambda (t) (+ t 1)) 1)", |_| ZPtr::err(
-    EvalErr::IllegalBindingVar
+test!(test_shadow_err6, "((lambda (t) (+ t 1)) 1)", |z| read_err(
+    z,
+    EvalErr::IllegalBindingVar,
+    None,
+    "t"
 ));
-test!(test_shadow_err7, "((lambda (x &rest t) (+ x 1)) 1)", |_| {
-    ZPtr::err(EvalErr::IllegalBindingVar)
+test!(test_shadow_err7, "((lambda (x &rest t) (+ x 1)) 1)", |z| {
+    read_err(z, EvalErr::IllegalBindingVar, None, "t")
 });
 test!(
     test_shadow_err8,
     "((lambda (x &rest nil) (+ x 1)) 1)",
-    |_| ZPtr::err(EvalErr::IllegalBindingVar)
+    |z| read_err(z, EvalErr::IllegalBindingVar, None, "nil")
 );
-test!(test_rest_err1, "((lambda (x &rest) x) 1)", |_| ZPtr::err(
-    EvalErr::ParamInvalidRest
+test!(test_rest_err1, "((lambda (x &rest) x) 1)", |z| read_err(
+    z,
+    EvalErr::ParamInvalidRest,
+    None,
+    "(&rest)"
 ));
-test!(test_rest_err2, "((lambda (x &rest y z) x) 1)", |_| {
-    ZPtr::err(EvalErr::ParamInvalidRest)
-});
-test!(test_rest_err3, "((lambda (&rest y z) z) 1)", |_| ZPtr::err(
-    EvalErr::ParamInvalidRest
+test!(
+    test_rest_err2,
+    "((lambda (x &rest y z) x) 1)",
+    |z| read_err(z, EvalErr::ParamInvalidRest, None, "(&rest y z)")
+);
+test!(test_rest_err3, "((lambda (&rest y z) z) 1)", |z| read_err(
+    z,
+    EvalErr::ParamInvalidRest,
+    None,
+    "(&rest y z)"
+));
+test!(test_rest_err4, "((lambda (&rest) &rest) 1)", |z| read_err(
+    z,
+    EvalErr::ParamInvalidRest,
+    None,
+    "(&rest)"
 ));
-test!(test_rest_err4, "((lambda (&rest) &rest) 1)", |_| {
-    ZPtr::err(EvalErr::ParamInvalidRest)
-});
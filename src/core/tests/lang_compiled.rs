@@ -176,33 +176,33 @@
 // test!(test_mul, "(mul-square (+ 1n 2n))", |_| num(9));
 // test!(test_extern, "(extern-square (+ 1n 2n))", |_| num(9));
 
-// test!(test_mul_undersaturated, "(mul-square)", |_| ZPtr::err(
+// test!(test_mul_undersaturated, "(mul-square)", |z| z.intern_error(
 //     EvalErr::InvalidForm
 // ));
 // test!(
 //     test_extern_undersaturated,
 //     "(extern-square)",
-//     |_| ZPtr::err(EvalErr::InvalidForm)
+//     |z| z.intern_error(EvalErr::InvalidForm)
 // );
 
-// test!(test_mul_oversaturated, "(mul-square 3n 2n)", |_| ZPtr::err(
+// test!(test_mul_oversaturated, "(mul-square 3n 2n)", |z| z.intern_error(
 //     EvalErr::InvalidForm
 // ));
-// test!(test_extern_oversaturated, "(extern-square 3n 2n)", |_| {
-//     ZPtr::err(EvalErr::InvalidForm)
+// test!(test_extern_oversaturated, "(extern-square 3n 2n)", |z| {
+//     z.intern_error(EvalErr::InvalidForm)
 // });
 
-// test!(test_mul_mistype, "(mul-square 3)", |_| ZPtr::err(
+// test!(test_mul_mistype, "(mul-square 3)", |z| z.intern_error(
 //     EvalErr::InvalidArg
 // ));
-// test!(test_extern_mistype, "(extern-square 3)", |_| ZPtr::err(
+// test!(test_extern_mistype, "(extern-square 3)", |z| z.intern_error(
 //     EvalErr::InvalidArg
 // ));
 
-// test!(test_mul_arg_err, "(mul-square a)", |_| ZPtr::err(
+// test!(test_mul_arg_err, "(mul-square a)", |z| z.intern_error(
 //     EvalErr::UnboundVar
 // ));
-// test!(test_extern_arg_err, "(extern-square a)", |_| ZPtr::err(
+// test!(test_extern_arg_err, "(extern-square a)", |z| z.intern_error(
 //     EvalErr::UnboundVar
 // ));
 
@@ -151,7 +151,7 @@ fn test_setup_data() -> &'static (
 fn test_case(input_code: &'static str, expected_cloj: fn(&mut ZStore<F, LurkChip>) -> ZPtr<F>) {
     let (lang_symbols, toplevel, zstore, config) = test_setup_data();
     let mut zstore = zstore.clone();
-    let zptr = zstore.read(input_code, lang_symbols);
+    let zptr = zstore.read(input_code, lang_symbols).unwrap();
     run_tests(
         &zptr,
         &ZPtr::null(Tag::Env),
@@ -175,37 +175,51 @@ fn num(n: u32) -> ZPtr<F> {
     ZPtr::num(F::from_canonical_u32(n))
 }
 
+fn unbound_var(zstore: &mut ZStore<F, LurkChip>, name: &str) -> ZPtr<F> {
+    let var = zstore.intern_symbol_no_lang(&user_sym(name));
+    let nil = *zstore.nil();
+    zstore.intern_error_with_payload(EvalErr::UnboundVar, var, nil)
+}
+
+/// An arity error of a coroutine, carrying the evaluated arguments
+fn arity_err(zstore: &mut ZStore<F, LurkChip>, name: &str, args: &[ZPtr<F>]) -> ZPtr<F> {
+    let (lang_symbols, ..) = test_setup_data();
+    let coroutine = zstore.intern_symbol(&user_sym(name), lang_symbols);
+    let args = zstore.intern_list(args.iter().copied());
+    zstore.intern_error_with_payload(EvalErr::InvalidForm, args, coroutine)
+}
+
 test!(test_mul, "(mul-square (+ 1n 2n))", |_| num(9));
 test!(test_extern, "(extern-square (+ 1n 2n))", |_| num(9));
 
-test!(test_mul_undersaturated, "(mul-square)", |_| ZPtr::err(
-    EvalErr::InvalidForm
+test!(test_mul_undersaturated, "(mul-square)", |z| arity_err(
+    z,
+    "mul-square",
+    &[]
 ));
 test!(
     test_extern_undersaturated,
     "(extern-square)",
-    |_| ZPtr::err(EvalErr::InvalidForm)
+    |z| arity_err(z, "extern-square", &[])
 );
 
-test!(test_mul_oversaturated, "(mul-square 3n 2n)", |_| ZPtr::err(
-    EvalErr::InvalidForm
+test!(test_mul_oversaturated, "(mul-square 3n 2n)", |z| arity_err(
+    z,
+    "mul-square",
+    &[num(3), num(2)]
 ));
-test!(test_extern_oversaturated, "(extern-square 3n 2n)", |_| {
-    ZPtr::err(EvalErr::InvalidForm)
+test!(test_extern_oversaturated, "(extern-square 3n 2n)", |z| {
+    arity_err(z, "extern-square", &[num(3), num(2)])
 });
 
-test!(test_mul_mistype, "(mul-square 3)", |_| ZPtr::err(
-    EvalErr::InvalidArg
-));
-test!(test_extern_mistype, "(extern-square 3)", |_| ZPtr::err(
-    EvalErr::InvalidArg
-));
+test!(test_mul_mistype, "(mul-square 3)", |z| z
+    .intern_error(EvalErr::InvalidArg));
+test!(test_extern_mistype, "(extern-square 3)", |z| z
+    .intern_error(EvalErr::InvalidArg));
 
-test!(test_mul_arg_err, "(mul-square a)", |_| ZPtr::err(
-    EvalErr::UnboundVar
-));
-test!(test_extern_arg_err, "(extern-square a)", |_| ZPtr::err(
-    EvalErr::UnboundVar
+test!(test_mul_arg_err, "(mul-square a)", |z| unbound_var(z, "a"));
+test!(test_extern_arg_err, "(extern-square a)", |z| unbound_var(
+    z, "a"
 ));
 
 test!(
@@ -11,9 +11,11 @@ use crate::{
         bytes::{builder::BytesAirRecordWithContext, record::DummyBytesRecord},
         unsigned::{
             add::{Diff, Sum},
+            bitwise::{eval_not, Bitwise, BitwiseOp},
             cmp::CompareWitness,
             div_rem::DivRem,
             mul::Product,
+            shift::{ShiftLeft, ShiftRight},
             Word64,
         },
     },
@@ -26,6 +28,9 @@ pub type DivRem64<T> = DivRem<T, 8>;
 pub type Compare64<T> = CompareWitness<T, 8>;
 pub type IsZero64<T> = IsZero<T, 8>;
 pub type Product64<T> = Product<T, 8>;
+pub type Bitwise64<T> = Bitwise<T, 8>;
+pub type ShiftLeft64<T> = ShiftLeft<T, 8>;
+pub type ShiftRight64<T> = ShiftRight<T, 8>;
 
 #[derive(Clone)]
 pub enum U64 {
@@ -35,6 +40,23 @@ pub enum U64 {
     DivRem,
     LessThan,
     IsZero,
+    And,
+    Or,
+    Xor,
+    Not,
+    Shl,
+    Shr,
+}
+
+impl U64 {
+    fn bitwise_op(&self) -> BitwiseOp {
+        match self {
+            U64::And => BitwiseOp::And,
+            U64::Or => BitwiseOp::Or,
+            U64::Xor => BitwiseOp::Xor,
+            _ => unreachable!(),
+        }
+    }
 }
 
 fn into_u64<F: PrimeField32>(slice: &[F]) -> u64 {
@@ -46,7 +68,7 @@ fn into_u64<F: PrimeField32>(slice: &[F]) -> u64 {
 impl<F: PrimeField32> Chipset<F> for U64 {
     fn input_size(&self) -> usize {
         match self {
-            U64::IsZero => 8,
+            U64::IsZero | U64::Not => 8,
             _ => 16,
         }
     }
@@ -67,6 +89,10 @@ impl<F: PrimeField32> Chipset<F> for U64 {
             U64::DivRem => DivRem64::<F>::witness_size(),
             U64::LessThan => Compare64::<F>::witness_size(),
             U64::IsZero => IsZero64::<F>::witness_size(),
+            U64::And | U64::Or | U64::Xor => Bitwise64::<F>::witness_size(),
+            U64::Not => 0, // negation is linear on the limbs
+            U64::Shl => ShiftLeft64::<F>::witness_size(),
+            U64::Shr => ShiftRight64::<F>::witness_size(),
         }
     }
 
@@ -78,6 +104,10 @@ impl<F: PrimeField32> Chipset<F> for U64 {
             U64::DivRem => DivRem64::<F>::num_requires(),
             U64::LessThan => Compare64::<F>::num_requires(),
             U64::IsZero => IsZero64::<F>::num_requires(),
+            U64::And | U64::Or | U64::Xor => Bitwise64::<F>::num_requires(),
+            U64::Not => 0,
+            U64::Shl => ShiftLeft64::<F>::num_requires(),
+            U64::Shr => ShiftRight64::<F>::num_requires(),
         }
     }
 
@@ -90,7 +120,7 @@ impl<F: PrimeField32> Chipset<F> for U64 {
     ) -> Vec<F> {
         let in1 = into_u64(&input[0..8]);
         let in2 = match self {
-            U64::IsZero => 0, // unused
+            U64::IsZero | U64::Not => 0, // unused
             _ => into_u64(&input[8..16]),
         };
         let bytes = &mut queries.bytes.context(nonce, requires);
@@ -125,13 +155,29 @@ impl<F: PrimeField32> Chipset<F> for U64 {
                 witness.populate_is_zero(&in1);
                 witness.iter_result().into_iter().collect()
             }
+            U64::And | U64::Or | U64::Xor => {
+                let mut witness = Bitwise64::<F>::default();
+                witness.populate(self.bitwise_op(), &in1, &in2, bytes);
+                witness.iter_result().into_iter().collect()
+            }
+            U64::Not => (!in1).to_le_bytes().map(F::from_canonical_u8).into(),
+            U64::Shl => {
+                let mut witness = ShiftLeft64::<F>::default();
+                witness.populate(&in1, &in2, bytes);
+                witness.iter_result().into_iter().collect()
+            }
+            U64::Shr => {
+                let mut witness = ShiftRight64::<F>::default();
+                witness.populate(&in1, &in2, bytes);
+                witness.iter_result().into_iter().collect()
+            }
         }
     }
 
     fn populate_witness(&self, input: &[F], witness: &mut [F]) -> Vec<F> {
         let in1 = into_u64(&input[0..8]);
         let in2 = match self {
-            U64::IsZero => 0, // unused
+            U64::IsZero | U64::Not => 0, // unused
             _ => into_u64(&input[8..16]),
         };
         let bytes = &mut DummyBytesRecord;
@@ -166,6 +212,22 @@ impl<F: PrimeField32> Chipset<F> for U64 {
                 witness.populate_is_zero(&in1);
                 witness.iter_result().into_iter().collect()
             }
+            U64::And | U64::Or | U64::Xor => {
+                let witness: &mut Bitwise64<F> = witness.borrow_mut();
+                witness.populate(self.bitwise_op(), &in1, &in2, bytes);
+                witness.iter_result().into_iter().collect()
+            }
+            U64::Not => (!in1).to_le_bytes().map(F::from_canonical_u8).into(),
+            U64::Shl => {
+                let witness: &mut ShiftLeft64<F> = witness.borrow_mut();
+                witness.populate(&in1, &in2, bytes);
+                witness.iter_result().into_iter().collect()
+            }
+            U64::Shr => {
+                let witness: &mut ShiftRight64<F> = witness.borrow_mut();
+                witness.populate(&in1, &in2, bytes);
+                witness.iter_result().into_iter().collect()
+            }
         }
     }
 
@@ -180,7 +242,7 @@ impl<F: PrimeField32> Chipset<F> for U64 {
     ) -> Vec<AB::Expr> {
         let in1 = ins[0..8].iter().cloned().collect::<Word64<_>>();
         let in2 = match self {
-            U64::IsZero => Word64::default(), // unused
+            U64::IsZero | U64::Not => Word64::default(), // unused
             _ => ins[8..16].iter().cloned().collect::<Word64<_>>(),
         };
         let mut air_record = BytesAirRecordWithContext::default();
@@ -215,6 +277,23 @@ impl<F: PrimeField32> Chipset<F> for U64 {
                 let out = witness.eval_is_zero(builder, in1, is_real.clone());
                 vec![out.into()]
             }
+            U64::And | U64::Or | U64::Xor => {
+                let witness: &Bitwise64<AB::Var> = witness.borrow();
+                let op = self.bitwise_op();
+                let out = witness.eval(op, &in1, &in2, &mut air_record, is_real.clone());
+                out.map(Into::into).into_iter().collect()
+            }
+            U64::Not => eval_not(in1).into_iter().collect(),
+            U64::Shl => {
+                let witness: &ShiftLeft64<AB::Var> = witness.borrow();
+                let out = witness.eval(builder, &in1, &in2, &mut air_record, is_real.clone());
+                out.map(Into::into).into_iter().collect()
+            }
+            U64::Shr => {
+                let witness: &ShiftRight64<AB::Var> = witness.borrow();
+                let out = witness.eval(builder, &in1, &in2, &mut air_record, is_real.clone());
+                out.map(Into::into).into_iter().collect()
+            }
         };
         air_record.require_all(builder, nonce, requires.iter().cloned());
         out
@@ -628,4 +707,111 @@ mod test {
         let mut challenger = machine.config().challenger();
         machine.debug_constraints(&pk, shard, &mut challenger);
     }
+
+    #[test]
+    fn u64_bitwise_test() {
+        sp1_core_machine::utils::setup_logger();
+
+        let bitwise_func = func!(
+        fn bitwise(a: [8], b: [8]): [32] {
+            let c: [8] = extern_call(u64_and, a, b);
+            let d: [8] = extern_call(u64_or, a, b);
+            let e: [8] = extern_call(u64_xor, a, b);
+            let f: [8] = extern_call(u64_not, a);
+            return (c, d, e, f)
+        });
+        let lurk_chip_map = lurk_chip_map_native();
+        let toplevel = Toplevel::new(&[bitwise_func], lurk_chip_map);
+
+        let bitwise_chip = FuncChip::from_name("bitwise", &toplevel);
+        let mut queries = QueryRecord::new(&toplevel);
+        let f = F::from_canonical_u8;
+        let (a, b) = (0xff00_f0f0_1234_5678u64, 0x0ff0_ff00_8765_4321u64);
+        // Little endian
+        let args = [a.to_le_bytes(), b.to_le_bytes()]
+            .concat()
+            .into_iter()
+            .map(f)
+            .collect::<Vec<_>>();
+        let out = toplevel
+            .execute_by_name("bitwise", &args, &mut queries, None)
+            .unwrap();
+        let expected = [a & b, a | b, a ^ b, !a]
+            .map(u64::to_le_bytes)
+            .concat()
+            .into_iter()
+            .map(f)
+            .collect::<Vec<_>>();
+        assert_eq!(out.as_ref(), &expected);
+
+        let lair_chips = build_lair_chip_vector(&bitwise_chip);
+        let queries = Arc::new(queries);
+        debug_chip_constraints_and_queries_with_sharding(&queries, &lair_chips, None);
+
+        let config = BabyBearPoseidon2::new();
+        let machine = StarkMachine::new(
+            config,
+            build_chip_vector(&bitwise_chip),
+            queries.expect_public_values().len(),
+            true,
+        );
+
+        let (pk, _vk) = machine.setup(&LairMachineProgram);
+        let shard = Shard::new_arc(&queries);
+        let mut challenger = machine.config().challenger();
+        machine.debug_constraints(&pk, shard, &mut challenger);
+    }
+
+    #[test]
+    fn u64_shift_test() {
+        sp1_core_machine::utils::setup_logger();
+
+        let shift_func = func!(
+        fn shift(a: [8], b: [8]): [16] {
+            let c: [8] = extern_call(u64_shl, a, b);
+            let d: [8] = extern_call(u64_shr, a, b);
+            return (c, d)
+        });
+        let lurk_chip_map = lurk_chip_map_native();
+        let toplevel = Toplevel::new(&[shift_func], lurk_chip_map);
+
+        let shift_chip = FuncChip::from_name("shift", &toplevel);
+        let mut queries = QueryRecord::new(&toplevel);
+        let f = F::from_canonical_u8;
+        for (a, b) in [(0x1234_5678_9abc_def0u64, 12u64), (u64::MAX, 63), (7, 64)] {
+            // Little endian
+            let args = [a.to_le_bytes(), b.to_le_bytes()]
+                .concat()
+                .into_iter()
+                .map(f)
+                .collect::<Vec<_>>();
+            let out = toplevel
+                .execute_by_name("shift", &args, &mut queries, None)
+                .unwrap();
+            let expected = [a.wrapping_shl(b as u32), a.wrapping_shr(b as u32)]
+                .map(u64::to_le_bytes)
+                .concat()
+                .into_iter()
+                .map(f)
+                .collect::<Vec<_>>();
+            assert_eq!(out.as_ref(), &expected);
+        }
+
+        let lair_chips = build_lair_chip_vector(&shift_chip);
+        let queries = Arc::new(queries);
+        debug_chip_constraints_and_queries_with_sharding(&queries, &lair_chips, None);
+
+        let config = BabyBearPoseidon2::new();
+        let machine = StarkMachine::new(
+            config,
+            build_chip_vector(&shift_chip),
+            queries.expect_public_values().len(),
+            true,
+        );
+
+        let (pk, _vk) = machine.setup(&LairMachineProgram);
+        let shard = Shard::new_arc(&queries);
+        let mut challenger = machine.config().challenger();
+        machine.debug_constraints(&pk, shard, &mut challenger);
+    }
 }
@@ -2,6 +2,7 @@ use anyhow::{bail, Result};
 use core::str;
 use indexmap::IndexSet;
 use itertools::Itertools;
+use num_bigint::BigUint;
 use once_cell::sync::OnceCell;
 use p3_baby_bear::BabyBear;
 use p3_field::{AbstractField, Field, PrimeField32};
@@ -17,7 +18,7 @@ use crate::{
         parser::{syntax::parse, Span},
         state::{builtin_sym, lurk_sym, State, StateRcCell, BUILTIN_SYMBOLS},
         symbol::Symbol,
-        syntax::Syntax,
+        syntax::{fmt_decimal, i64_from_sign_magnitude, Syntax},
         tag::Tag,
     },
     lair::{chipset::Chipset, List},
@@ -137,10 +138,19 @@ impl<F: AbstractField + Copy> ZPtr<F> {
     }
 
     #[inline]
-    pub fn err(err: EvalErr) -> Self {
+    pub fn i64(i: i64) -> Self {
         Self {
-            tag: Tag::Err,
-            digest: digest_from_field(err.to_field()),
+            tag: Tag::I64,
+            digest: i.to_le_bytes().map(F::from_canonical_u8),
+        }
+    }
+
+    /// A decimal from its value scaled by `DECIMAL_SCALE`
+    #[inline]
+    pub fn decimal(x: i64) -> Self {
+        Self {
+            tag: Tag::Decimal,
+            digest: x.to_le_bytes().map(F::from_canonical_u8),
         }
     }
 
@@ -379,6 +389,16 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
         self.memoize_atom_dag(ZPtr::u64(u))
     }
 
+    #[inline]
+    pub fn intern_i64(&mut self, i: i64) -> ZPtr<F> {
+        self.memoize_atom_dag(ZPtr::i64(i))
+    }
+
+    #[inline]
+    pub fn intern_decimal(&mut self, x: i64) -> ZPtr<F> {
+        self.memoize_atom_dag(ZPtr::decimal(x))
+    }
+
     #[inline]
     pub fn intern_big_num(&mut self, c: [F; DIGEST_SIZE]) -> ZPtr<F> {
         self.memoize_atom_dag(ZPtr::big_num(c))
@@ -391,7 +411,19 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
 
     #[inline]
     pub fn intern_error(&mut self, err: EvalErr) -> ZPtr<F> {
-        self.memoize_atom_dag(ZPtr::err(err))
+        self.intern_error_with_payload(err, self.nil, self.nil)
+    }
+
+    /// Interns an error carrying the offending `payload` and the `builtin` that
+    /// raised it. Either can be `nil` when missing.
+    #[inline]
+    pub fn intern_error_with_payload(
+        &mut self,
+        err: EvalErr,
+        payload: ZPtr<F>,
+        builtin: ZPtr<F>,
+    ) -> ZPtr<F> {
+        self.intern_tuple110(Tag::Err, payload, builtin, ZPtr::num(err.to_field()))
     }
 
     pub fn intern_string(&mut self, s: &str) -> ZPtr<F> {
@@ -406,6 +438,148 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
         zptr
     }
 
+    /// Interns a vector as a pair of its length and the root of a Merkle tree whose
+    /// leaves are the elements. The tree is built by pairing consecutive nodes with
+    /// conses, padding levels of odd length with `nil`. The empty vector is null.
+    pub fn intern_vector(&mut self, elts: &[ZPtr<F>]) -> ZPtr<F> {
+        if elts.is_empty() {
+            return self.intern_null(Tag::Vec);
+        }
+        let mut level = elts.to_vec();
+        while level.len() > 1 {
+            let mut next_level = Vec::with_capacity(level.len().div_ceil(2));
+            for pair in level.chunks(2) {
+                let right = pair.get(1).copied().unwrap_or(self.nil);
+                next_level.push(self.intern_cons(pair[0], right));
+            }
+            level = next_level;
+        }
+        let len = self.intern_u64(elts.len() as u64);
+        self.intern_tuple11(Tag::Vec, len, level[0])
+    }
+
+    /// Interns a map as a pair of its size and the root of a binary trie whose leaves
+    /// are conses of keys and values. The path to a key is given by the bits of the
+    /// hash of the key, from the least significant one, and subtrees with a single
+    /// entry are collapsed into their leaves, so the trie only depends on the entries.
+    /// Branches are tagged as maps and empty subtrees are `nil`. Later entries override
+    /// earlier ones with the same key. The empty map is null.
+    pub fn intern_map(&mut self, entries: &[(ZPtr<F>, ZPtr<F>)]) -> ZPtr<F>
+    where
+        F: PrimeField32,
+    {
+        let entries: FxHashMap<_, _> = entries.iter().copied().collect();
+        if entries.is_empty() {
+            return self.intern_null(Tag::Map);
+        }
+        let leaves = entries
+            .into_iter()
+            .map(|(key, val)| (self.map_key_path(&key), self.intern_cons(key, val)))
+            .collect_vec();
+        let size = self.intern_u64(leaves.len() as u64);
+        let root = self.intern_map_node(leaves, 0);
+        self.intern_tuple11(Tag::Map, size, root)
+    }
+
+    /// The path of a key in a map trie, which is the hash of its tag and digest read
+    /// as a `BigNum`
+    fn map_key_path(&mut self, key: &ZPtr<F>) -> BigUint
+    where
+        F: PrimeField32,
+    {
+        let mut preimg = [F::zero(); HASH3_SIZE];
+        preimg[0] = key.tag.to_field();
+        preimg[DIGEST_SIZE..2 * DIGEST_SIZE].copy_from_slice(&key.digest);
+        field_elts_to_biguint(&self.hash3(preimg))
+    }
+
+    fn intern_map_node(&mut self, leaves: Vec<(BigUint, ZPtr<F>)>, depth: u64) -> ZPtr<F>
+    where
+        F: PrimeField32,
+    {
+        match leaves.len() {
+            0 => self.nil,
+            1 => leaves[0].1,
+            _ => {
+                let (right, left): (Vec<_>, Vec<_>) =
+                    leaves.into_iter().partition(|(path, _)| path.bit(depth));
+                let left = self.intern_map_node(left, depth + 1);
+                let right = self.intern_map_node(right, depth + 1);
+                self.intern_tuple11(Tag::Map, left, right)
+            }
+        }
+    }
+
+    /// The levels of the Merkle tree over the elements of the list `leaves`, from the
+    /// leaves up to the root. Leaves are hashed from their tags and digests and parents
+    /// from their children followed by a marker, all with `hash3`. Levels of odd length
+    /// are padded with a zero digest.
+    fn merkle_levels(&mut self, leaves: &ZPtr<F>) -> Vec<Vec<[F; DIGEST_SIZE]>> {
+        let (leaves, _) = self.fetch_list(leaves);
+        let leaves = leaves.into_iter().copied().collect_vec();
+        let mut level = leaves
+            .iter()
+            .map(|leaf| {
+                let mut preimg = [F::zero(); HASH3_SIZE];
+                preimg[..ZPTR_SIZE].copy_from_slice(&leaf.flatten());
+                self.hash3(preimg)
+            })
+            .collect_vec();
+        let mut levels = vec![];
+        while level.len() > 1 {
+            let next_level = level
+                .chunks(2)
+                .map(|pair| {
+                    let mut preimg = [F::zero(); HASH3_SIZE];
+                    preimg[..DIGEST_SIZE].copy_from_slice(&pair[0]);
+                    if let Some(right) = pair.get(1) {
+                        preimg[DIGEST_SIZE..2 * DIGEST_SIZE].copy_from_slice(right);
+                    }
+                    preimg[2 * DIGEST_SIZE] = F::one();
+                    self.hash3(preimg)
+                })
+                .collect_vec();
+            levels.push(level);
+            level = next_level;
+        }
+        levels.push(level);
+        levels
+    }
+
+    /// Interns the root of the Merkle tree over the elements of the list `leaves` as a
+    /// `BigNum`. The root of an empty tree is zero.
+    pub fn intern_merkle_root(&mut self, leaves: &ZPtr<F>) -> ZPtr<F> {
+        let levels = self.merkle_levels(leaves);
+        let root = levels[levels.len() - 1]
+            .first()
+            .copied()
+            .unwrap_or([F::zero(); DIGEST_SIZE]);
+        self.intern_big_num(root)
+    }
+
+    /// Interns the Merkle path of the element of index `idx` of the list `leaves`, as
+    /// checked by `merkle-verify`. That's a list of pairs `(right . sibling)`, from the
+    /// leaf up, where `right` is `t` if the node is a right child or `nil` otherwise.
+    /// Returns `None` if the index is out of bounds.
+    pub fn intern_merkle_path(&mut self, leaves: &ZPtr<F>, mut idx: usize) -> Option<ZPtr<F>> {
+        let levels = self.merkle_levels(leaves);
+        if idx >= levels[0].len() {
+            return None;
+        }
+        let mut path = Vec::with_capacity(levels.len() - 1);
+        for level in &levels[..levels.len() - 1] {
+            let sibling = level
+                .get(idx ^ 1)
+                .copied()
+                .unwrap_or([F::zero(); DIGEST_SIZE]);
+            let right = if idx & 1 == 1 { self.t } else { self.nil };
+            let sibling = self.intern_big_num(sibling);
+            path.push(self.intern_cons(right, sibling));
+            idx >>= 1;
+        }
+        Some(self.intern_list(path))
+    }
+
     pub fn intern_symbol(&mut self, sym: &Symbol, lang_symbols: &FxHashSet<Symbol>) -> ZPtr<F> {
         if let Some(zptr) = self.sym_cache.get(sym) {
             return *zptr;
@@ -510,14 +684,174 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
         self.intern_list([self.quote, zptr])
     }
 
-    fn intern_syntax(&mut self, syn: &Syntax<F>, lang_symbols: &FxHashSet<Symbol>) -> ZPtr<F> {
+    /// Interns `(<name> <zptr>)`, where `name` is one of the `READER_SYMBOLS`
+    pub fn intern_reader_form(&mut self, name: &str, zptr: ZPtr<F>) -> ZPtr<F> {
+        let sym = self.intern_symbol_no_lang(&lurk_sym(name));
+        self.intern_list([sym, zptr])
+    }
+
+    /// Returns the expression that constructs the datum quasiquoted by
+    /// `(quasiquote <zptr>)`, made of `cons` calls and quoted constants
+    pub fn expand_quasiquote(&mut self, zptr: &ZPtr<F>) -> Result<ZPtr<F>> {
+        self.expand_quasiquote_aux(zptr, 1)
+    }
+
+    /// Expands the `(quasiquote <x>)` forms of an expression, starting from the
+    /// outermost ones so nested quasiquotes are seen by `expand_quasiquote`. Quoted
+    /// data is left untouched
+    pub fn expand_quasiquotes(&mut self, zptr: &ZPtr<F>) -> Result<ZPtr<F>> {
+        if zptr.tag != Tag::Cons {
+            return Ok(*zptr);
+        }
+        let (&head, &args) = self.fetch_tuple11(zptr);
+        if head == self.quote {
+            return Ok(*zptr);
+        }
+        if head == self.intern_symbol_no_lang(&lurk_sym("quasiquote")) {
+            let x = self.single_arg(&args)?;
+            let expanded = self.expand_quasiquote(&x)?;
+            // the unquoted expressions may have quasiquotes of their own
+            return self.expand_quasiquotes(&expanded);
+        }
+        let (xs, y) = self.fetch_list(zptr);
+        let xs = xs.into_iter().copied().collect::<Vec<_>>();
+        let y = y.copied();
+        let xs = xs
+            .iter()
+            .map(|x| self.expand_quasiquotes(x))
+            .collect::<Result<Vec<_>>>()?;
+        match y {
+            Some(y) => Ok(self.intern_list_full(xs, y)),
+            None => Ok(self.intern_list(xs)),
+        }
+    }
+
+    /// Returns `x` if `args` is the singleton list `(x)`
+    fn single_arg(&self, args: &ZPtr<F>) -> Result<ZPtr<F>> {
+        if args.tag == Tag::Cons {
+            let (arg, rest) = self.fetch_tuple11(args);
+            if rest == &self.nil {
+                return Ok(*arg);
+            }
+        }
+        bail!("Reader forms expect exactly one argument")
+    }
+
+    /// `depth` is the number of enclosing quasiquotes
+    fn expand_quasiquote_aux(&mut self, zptr: &ZPtr<F>, depth: usize) -> Result<ZPtr<F>> {
+        if zptr.tag != Tag::Cons {
+            return Ok(self.intern_quoted(*zptr));
+        }
+        let (&head, &rest) = self.fetch_tuple11(zptr);
+        let quasiquote = self.intern_symbol_no_lang(&lurk_sym("quasiquote"));
+        let unquote = self.intern_symbol_no_lang(&lurk_sym("unquote"));
+        let unquote_splicing = self.intern_symbol_no_lang(&lurk_sym("unquote-splicing"));
+        let quoted_nil = self.intern_quoted(self.nil);
+        if head == unquote || head == unquote_splicing || head == quasiquote {
+            let arg = self.single_arg(&rest)?;
+            if head == unquote && depth == 1 {
+                return Ok(arg);
+            }
+            if head == unquote_splicing && depth == 1 {
+                bail!("Can't splice outside of a list");
+            }
+            let depth = if head == quasiquote {
+                depth + 1
+            } else {
+                depth - 1
+            };
+            let head = self.intern_quoted(head);
+            let arg = self.expand_quasiquote_aux(&arg, depth)?;
+            let tail = self.cons_expr(arg, quoted_nil);
+            return Ok(self.cons_expr(head, tail));
+        }
+        let tail = self.expand_quasiquote_aux(&rest, depth)?;
+        if depth == 1 && head.tag == Tag::Cons {
+            let (&head_head, &head_rest) = self.fetch_tuple11(&head);
+            if head_head == unquote_splicing {
+                let list = self.single_arg(&head_rest)?;
+                if tail == quoted_nil {
+                    return Ok(list);
+                }
+                let append = self.append_fun();
+                return Ok(self.intern_list([append, list, tail]));
+            }
+        }
+        let head = self.expand_quasiquote_aux(&head, depth)?;
+        Ok(self.cons_expr(head, tail))
+    }
+
+    /// Returns `x` if `zptr` is `(quote x)`
+    fn unquoted(&self, zptr: &ZPtr<F>) -> Option<ZPtr<F>> {
+        if zptr.tag != Tag::Cons {
+            return None;
+        }
+        let (head, rest) = self.fetch_tuple11(zptr);
+        if head != &self.quote || rest.tag != Tag::Cons {
+            return None;
+        }
+        let (x, nil) = self.fetch_tuple11(rest);
+        (nil == &self.nil).then_some(*x)
+    }
+
+    /// Returns the expression `(cons car cdr)`, folding it into a quoted pair
+    /// if both `car` and `cdr` are quoted
+    fn cons_expr(&mut self, car: ZPtr<F>, cdr: ZPtr<F>) -> ZPtr<F> {
+        match (self.unquoted(&car), self.unquoted(&cdr)) {
+            (Some(car), Some(cdr)) => {
+                let pair = self.intern_cons(car, cdr);
+                self.intern_quoted(pair)
+            }
+            _ => {
+                let cons = self.intern_symbol_no_lang(&builtin_sym("cons"));
+                self.intern_list([cons, car, cdr])
+            }
+        }
+    }
+
+    /// Returns the expression for a function that appends two lists:
+    /// ```lisp
+    /// (letrec ((append (lambda (xs ys)
+    ///                    (if (eq xs nil) ys (cons (car xs) (append (cdr xs) ys))))))
+    ///   append)
+    /// ```
+    /// Its symbols live in the `.lurk` package so they don't clash with user code
+    fn append_fun(&mut self) -> ZPtr<F> {
+        let [append, xs, ys] =
+            ["append", "xs", "ys"].map(|name| self.intern_symbol_no_lang(&lurk_sym(name)));
+        let [letrec, lambda, if_, eq, cons, car, cdr] =
+            ["letrec", "lambda", "if", "eq", "cons", "car", "cdr"]
+                .map(|name| self.intern_symbol_no_lang(&builtin_sym(name)));
+        let car_xs = self.intern_list([car, xs]);
+        let cdr_xs = self.intern_list([cdr, xs]);
+        let recursion = self.intern_list([append, cdr_xs, ys]);
+        let cons_call = self.intern_list([cons, car_xs, recursion]);
+        let is_empty = self.intern_list([eq, xs, self.nil]);
+        let body = self.intern_list([if_, is_empty, ys, cons_call]);
+        let params = self.intern_list([xs, ys]);
+        let fun = self.intern_list([lambda, params, body]);
+        let binding = self.intern_list([append, fun]);
+        let bindings = self.intern_list([binding]);
+        self.intern_list([letrec, bindings, append])
+    }
+
+    fn intern_syntax(
+        &mut self,
+        syn: &Syntax<F>,
+        lang_symbols: &FxHashSet<Symbol>,
+    ) -> Result<ZPtr<F>> {
         if let Some(zptr) = self.syn_cache.get(syn) {
-            return *zptr;
+            return Ok(*zptr);
         }
         let zptr = match syn {
             Syntax::Num(_, f) => self.intern_num(*f),
             Syntax::Char(_, c) => self.intern_char(*c),
             Syntax::U64(_, u) => self.intern_u64(*u),
+            Syntax::I64(_, neg, x) => match i64_from_sign_magnitude(*neg, *x) {
+                Some(i) => self.intern_i64(i),
+                None => bail!("Signed integer out of range: {syn}"),
+            },
+            Syntax::Decimal(_, x) => self.intern_decimal(*x),
             Syntax::BigNum(_, c) => self.intern_big_num(*c),
             Syntax::Comm(_, c) => self.intern_comm(*c),
             Syntax::String(_, s) => self.intern_string(s),
@@ -526,39 +860,51 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
                 let xs = xs
                     .iter()
                     .map(|x| self.intern_syntax(x, lang_symbols))
-                    .collect::<Vec<_>>();
+                    .collect::<Result<Vec<_>>>()?;
                 self.intern_list(xs)
             }
             Syntax::Improper(_, xs, y) => {
                 let xs = xs
                     .iter()
                     .map(|x| self.intern_syntax(x, lang_symbols))
-                    .collect::<Vec<_>>();
-                let y = self.intern_syntax(y, lang_symbols);
+                    .collect::<Result<Vec<_>>>()?;
+                let y = self.intern_syntax(y, lang_symbols)?;
                 self.intern_list_full(xs, y)
             }
             Syntax::Quote(_, x) => {
-                let x = self.intern_syntax(x, lang_symbols);
+                let x = self.intern_syntax(x, lang_symbols)?;
                 self.intern_list([self.quote, x])
             }
+            Syntax::Quasiquote(_, x) => {
+                let x = self.intern_syntax(x, lang_symbols)?;
+                self.intern_reader_form("quasiquote", x)
+            }
+            Syntax::Unquote(_, x) => {
+                let x = self.intern_syntax(x, lang_symbols)?;
+                self.intern_reader_form("unquote", x)
+            }
+            Syntax::UnquoteSplicing(_, x) => {
+                let x = self.intern_syntax(x, lang_symbols)?;
+                self.intern_reader_form("unquote-splicing", x)
+            }
             Syntax::Env(_, env) => {
                 let xs = env
                     .iter()
                     .map(|(sym, val)| {
                         let sym = self.intern_symbol(sym, lang_symbols);
-                        let val = self.intern_syntax(val, lang_symbols);
-                        (sym, val)
+                        let val = self.intern_syntax(val, lang_symbols)?;
+                        Ok((sym, val))
                     })
-                    .collect::<Vec<_>>();
+                    .collect::<Result<Vec<_>>>()?;
                 let empty_env = self.intern_empty_env();
                 xs.into_iter()
                     .rev()
                     .fold(empty_env, |acc, (sym, val)| self.intern_env(sym, val, acc))
             }
-            Syntax::I64(..) | Syntax::Meta(..) => panic!("not supported"),
+            Syntax::Meta(..) => bail!("Meta commands can't be read as data: {syn}"),
         };
         self.syn_cache.insert(syn.clone(), zptr);
-        zptr
+        Ok(zptr)
     }
 
     #[inline]
@@ -567,15 +913,16 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
         input: &str,
         state: StateRcCell,
         lang_symbols: &FxHashSet<Symbol>,
-    ) -> ZPtr<F> {
-        let (_, syn) = parse(Span::new(input), state, true)
-            .expect("parse error")
-            .expect("no input");
-        self.intern_syntax(&syn, lang_symbols)
+    ) -> Result<ZPtr<F>> {
+        let Some((_, syn)) = parse(Span::new(input), state, true)? else {
+            bail!("No input to read")
+        };
+        let zptr = self.intern_syntax(&syn, lang_symbols)?;
+        self.expand_quasiquotes(&zptr)
     }
 
     #[inline]
-    pub fn read(&mut self, input: &str, lang_symbols: &FxHashSet<Symbol>) -> ZPtr<F> {
+    pub fn read(&mut self, input: &str, lang_symbols: &FxHashSet<Symbol>) -> Result<ZPtr<F>> {
         self.read_with_state(input, State::init_lurk_state().rccell(), lang_symbols)
     }
 
@@ -662,6 +1009,36 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
                 digest = cdr_digest;
                 zptr = ZPtr::from_flat_data(cdr);
             },
+            Tag::Vec => {
+                if digest == zeros {
+                    self.memoize_atom_dag(ZPtr { tag, digest: zeros });
+                } else {
+                    let preimg = hashes4_inv.get(digest).expect("Hash4 preimg not found");
+                    let (len, root) = preimg.split_at(ZPTR_SIZE);
+                    let len_digest = &len[DIGEST_SIZE..];
+                    let (root_tag, root_digest) = root.split_at(DIGEST_SIZE);
+                    let root_tag = Tag::from_field(&root_tag[0]);
+                    recurse!(Tag::U64, len_digest);
+                    recurse!(root_tag, root_digest);
+                    memoize_tuple11!(Tag::U64, len_digest, root_tag, root_digest);
+                }
+            }
+            Tag::Map => {
+                if digest == zeros {
+                    self.memoize_atom_dag(ZPtr { tag, digest: zeros });
+                } else {
+                    // both the map itself and the branches of its trie are pairs
+                    let preimg = hashes4_inv.get(digest).expect("Hash4 preimg not found");
+                    let (fst, snd) = preimg.split_at(ZPTR_SIZE);
+                    let (fst_tag, fst_digest) = fst.split_at(DIGEST_SIZE);
+                    let (snd_tag, snd_digest) = snd.split_at(DIGEST_SIZE);
+                    let fst_tag = Tag::from_field(&fst_tag[0]);
+                    let snd_tag = Tag::from_field(&snd_tag[0]);
+                    recurse!(fst_tag, fst_digest);
+                    recurse!(snd_tag, snd_digest);
+                    memoize_tuple11!(fst_tag, fst_digest, snd_tag, snd_digest);
+                }
+            }
             Tag::Env => loop {
                 if digest == zeros {
                     self.memoize_atom_dag(ZPtr { tag, digest: zeros });
@@ -705,8 +1082,29 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
                     env_digest
                 );
             }
+            Tag::Err => {
+                let preimg = hashes5_inv.get(digest).expect("Hash5 preimg not found");
+                let (payload, rest) = preimg.split_at(ZPTR_SIZE);
+                let (builtin, code_digest) = rest.split_at(ZPTR_SIZE);
+                let (payload_tag, payload_digest) = payload.split_at(DIGEST_SIZE);
+                let (builtin_tag, builtin_digest) = builtin.split_at(DIGEST_SIZE);
+                let payload_tag = Tag::from_field(&payload_tag[0]);
+                let builtin_tag = Tag::from_field(&builtin_tag[0]);
+                let code_tag = Tag::Num;
+                recurse!(payload_tag, payload_digest);
+                recurse!(builtin_tag, builtin_digest);
+                recurse!(code_tag, code_digest);
+                memoize_tuple110!(
+                    payload_tag,
+                    payload_digest,
+                    builtin_tag,
+                    builtin_digest,
+                    code_tag,
+                    code_digest
+                );
+            }
             Tag::Sym | Tag::Key | Tag::Builtin | Tag::Coroutine => (), // these should be already memoized
-            Tag::Num | Tag::U64 | Tag::Char | Tag::Err | Tag::BigNum | Tag::Comm => {
+            Tag::Num | Tag::U64 | Tag::I64 | Tag::Decimal | Tag::Char | Tag::BigNum | Tag::Comm => {
                 self.memoize_atom_dag(ZPtr {
                     tag,
                     digest: into_sized(digest),
@@ -788,6 +1186,62 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
         }
     }
 
+    pub fn fetch_vector<'a>(&'a self, zptr: &'a ZPtr<F>) -> Vec<&'a ZPtr<F>>
+    where
+        F: PrimeField32,
+    {
+        assert_eq!(zptr.tag, Tag::Vec);
+        let mut elts = vec![];
+        if zptr.digest == [F::zero(); DIGEST_SIZE] {
+            return elts;
+        }
+        let (len, root) = self.fetch_tuple11(zptr);
+        let len = u64::from_le_bytes(
+            len.digest
+                .map(|f| u8::try_from(f.as_canonical_u32()).expect("invalid u64 limbs")),
+        ) as usize;
+        let depth = len.next_power_of_two().trailing_zeros();
+        // the padding nodes come after all the elements, so they're never reached
+        let mut stack = vec![(root, depth)];
+        while let Some((node, depth)) = stack.pop() {
+            if elts.len() == len {
+                break;
+            }
+            if depth == 0 {
+                elts.push(node);
+            } else {
+                let (left, right) = self.fetch_tuple11(node);
+                stack.push((right, depth - 1));
+                stack.push((left, depth - 1));
+            }
+        }
+        elts
+    }
+
+    /// Returns the entries of a map in the order of their paths in the trie
+    pub fn fetch_map<'a>(&'a self, zptr: &'a ZPtr<F>) -> Vec<(&'a ZPtr<F>, &'a ZPtr<F>)> {
+        assert_eq!(zptr.tag, Tag::Map);
+        let mut entries = vec![];
+        if zptr.digest == [F::zero(); DIGEST_SIZE] {
+            return entries;
+        }
+        let (_, root) = self.fetch_tuple11(zptr);
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            match node.tag {
+                Tag::Cons => entries.push(self.fetch_tuple11(node)),
+                Tag::Map => {
+                    let (left, right) = self.fetch_tuple11(node);
+                    stack.push(right);
+                    stack.push(left);
+                }
+                // empty subtrees
+                _ => (),
+            }
+        }
+        entries
+    }
+
     pub fn fetch_env<'a>(&'a self, mut zptr: &'a ZPtr<F>) -> Vec<(&'a ZPtr<F>, &'a ZPtr<F>)>
     where
         F: PrimeField32,
@@ -846,6 +1300,18 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
                         .map(|f| u8::try_from(f.as_canonical_u32()).expect("invalid u64 limbs"))
                 )
             ),
+            Tag::I64 => format!(
+                "{}i64",
+                i64::from_le_bytes(
+                    zptr.digest
+                        .map(|f| u8::try_from(f.as_canonical_u32()).expect("invalid i64 limbs"))
+                )
+            ),
+            Tag::Decimal => {
+                fmt_decimal(i64::from_le_bytes(zptr.digest.map(|f| {
+                    u8::try_from(f.as_canonical_u32()).expect("invalid decimal limbs")
+                })))
+            }
             Tag::Char => format!("'{}'", get_char(&zptr.digest)),
             Tag::BigNum => format!("#{:#x}", field_elts_to_biguint(&zptr.digest)),
             Tag::Comm => format!("#c{:#x}", field_elts_to_biguint(&zptr.digest)),
@@ -874,6 +1340,28 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
                     )
                 }
             }
+            Tag::Vec => {
+                let elts_str = self
+                    .fetch_vector(zptr)
+                    .iter()
+                    .map(|z| self.fmt_with_state(state, z))
+                    .join(" ");
+                format!("#({elts_str})")
+            }
+            Tag::Map => {
+                let entries_str = self
+                    .fetch_map(zptr)
+                    .iter()
+                    .map(|(key, val)| {
+                        format!(
+                            "{}: {}",
+                            self.fmt_with_state(state, key),
+                            self.fmt_with_state(state, val)
+                        )
+                    })
+                    .join(", ");
+                format!("#{{{entries_str}}}")
+            }
             Tag::Env => {
                 let pairs_str = self
                     .fetch_env(zptr)
@@ -893,10 +1381,29 @@ impl<F: Field, C: Chipset<F>> ZStore<F, C> {
                 let (body, ..) = self.fetch_tuple110(zptr);
                 format!("<Fix {}>", self.fmt_with_state(state, body))
             }
-            Tag::Err => format!("<Err {:?}>", EvalErr::from_field(&zptr.digest[0])),
+            Tag::Err => format!("<Err {}>", self.fmt_error_with_state(state, zptr)),
         }
     }
 
+    /// Formats an error as its description, followed by its payload and the builtin
+    /// that raised it when present. E.g. `unbound variable foo`
+    pub fn fmt_error_with_state(&self, state: &StateRcCell, zptr: &ZPtr<F>) -> String
+    where
+        F: PrimeField32,
+    {
+        assert_eq!(zptr.tag, Tag::Err);
+        let (payload, builtin, code) = self.fetch_tuple110(zptr);
+        let mut msg = EvalErr::from_field(&code.digest[0]).to_string();
+        if payload != &self.nil {
+            msg.push(' ');
+            msg.push_str(&self.fmt_with_state(state, payload));
+        }
+        if builtin != &self.nil {
+            msg.push_str(&format!(" (in {})", self.fmt_with_state(state, builtin)));
+        }
+        msg
+    }
+
     #[inline]
     pub fn fmt(&self, zptr: &ZPtr<F>) -> String
     where
@@ -920,6 +1427,7 @@ mod test {
     use crate::{
         core::{
             chipset::lurk_hasher,
+            error::EvalErr,
             eval_direct::build_lurk_toplevel_native,
             state::{builtin_sym, user_sym, State},
             symbol::Symbol,
@@ -931,6 +1439,67 @@ mod test {
 
     use super::{into_sized, ZPtr};
 
+    #[test]
+    fn test_expand_quasiquote() {
+        let mut zstore = lurk_zstore();
+        let state = &State::init_lurk_state().rccell();
+        let mut expand = |input| {
+            let zptr = zstore
+                .read_with_state(input, state.clone(), &Default::default())
+                .unwrap();
+            zstore.fmt_with_state(state, &zptr)
+        };
+        assert_eq!(expand("`(a b)"), "(quote (a b))");
+        assert_eq!(expand("`(a ,b)"), "(cons (quote a) (cons b (quote nil)))");
+        assert_eq!(expand("`(a . ,b)"), "(cons (quote a) b)");
+        assert_eq!(expand("`(a ,@b)"), "(cons (quote a) b)");
+    }
+
+    #[test]
+    fn test_read_nested_quasiquote() {
+        let mut zstore = lurk_zstore();
+        let state = &State::init_lurk_state().rccell();
+        let mut read = |input| {
+            let zptr = zstore
+                .read_with_state(input, state.clone(), &Default::default())
+                .unwrap();
+            zstore.fmt_with_state(state, &zptr)
+        };
+        assert_eq!(read("`(a `b)"), "(quote (a (quasiquote b)))");
+        // the inner `,x` is unquoted by the outer quasiquote
+        assert_eq!(
+            read("`(a `(b ,(c ,x)))"),
+            concat!(
+                "(cons (quote a) (cons (cons (quote quasiquote) (cons (cons (quote b) ",
+                "(cons (cons (quote unquote) (cons (cons (quote c) (cons x (quote nil))) ",
+                "(quote nil))) (quote nil))) (quote nil))) (quote nil)))"
+            )
+        );
+        assert_eq!(read("'`(a ,b)"), "(quote (quasiquote (a (unquote b))))");
+    }
+
+    #[test]
+    fn test_read_invalid_quasiquote() {
+        let mut zstore = lurk_zstore();
+        assert!(zstore.read("`,@a", &Default::default()).is_err());
+        assert!(zstore
+            .read("`(a (unquote b c))", &Default::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_read_i64_out_of_range() {
+        let mut zstore = lurk_zstore();
+        let min = zstore.read("-9223372036854775808", &Default::default());
+        assert_eq!(min.unwrap(), ZPtr::i64(i64::MIN));
+        assert!(zstore
+            .read("-9223372036854775809", &Default::default())
+            .is_err());
+        assert!(zstore
+            .read("9223372036854775808i64", &Default::default())
+            .is_err());
+    }
+
     #[test]
     fn test_sym_key_hash_equivalence() {
         let mut zstore = lurk_zstore();
@@ -952,7 +1521,9 @@ mod test {
         let ZPtr {
             tag: expr_tag,
             digest: expr_digest,
-        } = zstore.read("(cons \"hi\" (lambda (x) x))", &lang_symbols);
+        } = zstore
+            .read("(cons \"hi\" (lambda (x) x))", &lang_symbols)
+            .unwrap();
 
         let record = &mut QueryRecord::new(&toplevel);
         record.inject_inv_queries("hash4", &toplevel, &zstore.hashes4);
@@ -1010,6 +1581,12 @@ mod test {
         let one_u64 = ZPtr::u64(1);
         assert_eq!(zstore.fmt_with_state(state, &one_u64), "1");
 
+        let minus_one_i64 = ZPtr::i64(-1);
+        assert_eq!(zstore.fmt_with_state(state, &minus_one_i64), "-1i64");
+
+        let decimal = ZPtr::decimal(-1_250_000);
+        assert_eq!(zstore.fmt_with_state(state, &decimal), "-1.25");
+
         let zero_big_num = ZPtr::big_num([BabyBear::zero(); 8]);
         assert_eq!(zstore.fmt_with_state(state, &zero_big_num), "#0x0");
 
@@ -1054,6 +1631,18 @@ mod test {
         let list = zstore.intern_list([x, hi]);
         assert_eq!(zstore.fmt_with_state(state, &list), "(x :hi)");
 
+        let empty_vec = zstore.intern_vector(&[]);
+        assert_eq!(zstore.fmt_with_state(state, &empty_vec), "#()");
+
+        let vec = zstore.intern_vector(&[x, hi, one, one_u64, a_char]);
+        assert_eq!(zstore.fmt_with_state(state, &vec), "#(x :hi 1n 1 'a')");
+
+        let empty_map = zstore.intern_map(&[]);
+        assert_eq!(zstore.fmt_with_state(state, &empty_map), "#{}");
+
+        let map = zstore.intern_map(&[(x, one), (x, one_u64)]);
+        assert_eq!(zstore.fmt_with_state(state, &map), "#{x: 1}");
+
         let list_x = zstore.intern_list([x]);
         let empty_env = zstore.intern_empty_env();
         let fun = zstore.intern_fun(list_x, list_x, empty_env);
@@ -1062,5 +1651,22 @@ mod test {
         assert_eq!(zstore.fmt_with_state(state, &empty_env), "{  }");
         let env = zstore.intern_env(x, one, empty_env);
         assert_eq!(zstore.fmt_with_state(state, &env), "{ x: 1n }");
+
+        let div_by_zero = zstore.intern_error(EvalErr::DivByZero);
+        assert_eq!(
+            zstore.fmt_with_state(state, &div_by_zero),
+            "<Err division by zero>"
+        );
+        let unbound_x = zstore.intern_error_with_payload(EvalErr::UnboundVar, x, zstore.nil);
+        assert_eq!(
+            zstore.fmt_error_with_state(state, &unbound_x),
+            "unbound variable x"
+        );
+        let plus = zstore.intern_symbol_no_lang(&builtin_sym("+"));
+        let not_u64 = zstore.intern_error_with_payload(EvalErr::NotU64, one, plus);
+        assert_eq!(
+            zstore.fmt_error_with_state(state, &not_u64),
+            "not a u64 1n (in +)"
+        );
     }
 }
@@ -0,0 +1,621 @@
+//! Arithmetic on BigNums, performed by converting the digests into words and
+//! reusing the unsigned gadgets.
+//!
+//! The gadgets assume that the results can be represented as BigNums, that is, that
+//! they are smaller than p^8. Callers must check for overflows beforehand.
+
+use crate::core::zstore::DIGEST_SIZE;
+use crate::gadgets::big_num::digits::{
+    BigNumToWord, BigNumUInt, BigNumWord, WordToBigNum, BIG_NUM_BYTES,
+};
+use crate::gadgets::big_num::uint::UInt;
+use crate::gadgets::bytes::{ByteAirRecord, ByteRecord};
+use crate::gadgets::unsigned::add::{Diff, Sum};
+use crate::gadgets::unsigned::div_rem::DivRem;
+use crate::gadgets::unsigned::mul::Product;
+use crate::gadgets::unsigned::Word;
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, PrimeField32};
+use sp1_derive::AlignedBorrow;
+
+/// The number of bytes of the full product of two BigNums
+const WIDE_BYTES: usize = 2 * BIG_NUM_BYTES;
+
+type WideUInt = UInt<WIDE_BYTES>;
+
+/// Zero-extends a word to the size of a full product
+fn widen<Expr: AbstractField>(word: &BigNumWord<Expr>) -> Word<Expr, WIDE_BYTES> {
+    Word::from_fn(|i| {
+        if i < BIG_NUM_BYTES {
+            word[i].clone()
+        } else {
+            Expr::zero()
+        }
+    })
+}
+
+/// Witness for the conversion of two BigNum operands into words
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct BigNumOperands<T> {
+    lhs: BigNumToWord<T>,
+    rhs: BigNumToWord<T>,
+}
+
+impl<F: PrimeField32> BigNumOperands<F> {
+    pub fn populate(
+        &mut self,
+        lhs: &[F; DIGEST_SIZE],
+        rhs: &[F; DIGEST_SIZE],
+        byte_record: &mut impl ByteRecord,
+    ) -> (BigNumUInt, BigNumUInt) {
+        let lhs = self.lhs.populate(lhs, byte_record);
+        let rhs = self.rhs.populate(rhs, byte_record);
+        (lhs, rhs)
+    }
+}
+
+impl<Var> BigNumOperands<Var> {
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        lhs: &[AB::Expr; DIGEST_SIZE],
+        rhs: &[AB::Expr; DIGEST_SIZE],
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> (BigNumWord<AB::Expr>, BigNumWord<AB::Expr>)
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let lhs = self.lhs.eval(builder, lhs, record, is_real.clone());
+        let rhs = self.rhs.eval(builder, rhs, record, is_real);
+        (lhs.into(), rhs.into())
+    }
+}
+
+impl<T> BigNumOperands<T> {
+    pub const fn num_requires() -> usize {
+        2 * BigNumToWord::<T>::num_requires()
+    }
+}
+
+/// Witness for the sum of two BigNums
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct BigNumSum<T> {
+    operands: BigNumOperands<T>,
+    sum: Sum<T, BIG_NUM_BYTES>,
+    result: WordToBigNum<T>,
+}
+
+impl<F: PrimeField32> BigNumSum<F> {
+    pub fn populate(
+        &mut self,
+        lhs: &[F; DIGEST_SIZE],
+        rhs: &[F; DIGEST_SIZE],
+        byte_record: &mut impl ByteRecord,
+    ) -> [F; DIGEST_SIZE] {
+        let (lhs, rhs) = self.operands.populate(lhs, rhs, byte_record);
+        let sum = self.sum.populate(&lhs, &rhs, byte_record);
+        self.result.populate(&sum, byte_record)
+    }
+}
+
+impl<Var> BigNumSum<Var> {
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        lhs: &[AB::Expr; DIGEST_SIZE],
+        rhs: &[AB::Expr; DIGEST_SIZE],
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> [AB::Var; DIGEST_SIZE]
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let (lhs, rhs) = self
+            .operands
+            .eval(builder, lhs, rhs, record, is_real.clone());
+        let sum = self.sum.eval(builder, lhs, rhs, record, is_real.clone());
+        self.result.eval(builder, &sum.into(), record, is_real)
+    }
+}
+
+impl<T> BigNumSum<T> {
+    pub const fn num_requires() -> usize {
+        BigNumOperands::<T>::num_requires()
+            + Sum::<T, BIG_NUM_BYTES>::num_requires()
+            + WordToBigNum::<T>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<BigNumSum<u8>>()
+    }
+
+    pub fn iter_result(&self) -> impl IntoIterator<Item = T>
+    where
+        T: Clone,
+    {
+        self.result.iter_result()
+    }
+}
+
+/// Witness for the difference of two BigNums, where the left-hand side is not smaller
+/// than the right-hand side
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct BigNumDiff<T> {
+    operands: BigNumOperands<T>,
+    diff: Diff<T, BIG_NUM_BYTES>,
+    result: WordToBigNum<T>,
+}
+
+impl<F: PrimeField32> BigNumDiff<F> {
+    pub fn populate(
+        &mut self,
+        lhs: &[F; DIGEST_SIZE],
+        rhs: &[F; DIGEST_SIZE],
+        byte_record: &mut impl ByteRecord,
+    ) -> [F; DIGEST_SIZE] {
+        let (lhs, rhs) = self.operands.populate(lhs, rhs, byte_record);
+        let diff = self.diff.populate(&lhs, &rhs, byte_record);
+        self.result.populate(&diff, byte_record)
+    }
+}
+
+impl<Var> BigNumDiff<Var> {
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        lhs: &[AB::Expr; DIGEST_SIZE],
+        rhs: &[AB::Expr; DIGEST_SIZE],
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> [AB::Var; DIGEST_SIZE]
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let (lhs, rhs) = self
+            .operands
+            .eval(builder, lhs, rhs, record, is_real.clone());
+        let diff = self.diff.eval(builder, lhs, rhs, record, is_real.clone());
+        self.result.eval(builder, &diff.into(), record, is_real)
+    }
+}
+
+impl<T> BigNumDiff<T> {
+    pub const fn num_requires() -> usize {
+        BigNumOperands::<T>::num_requires()
+            + Diff::<T, BIG_NUM_BYTES>::num_requires()
+            + WordToBigNum::<T>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<BigNumDiff<u8>>()
+    }
+
+    pub fn iter_result(&self) -> impl IntoIterator<Item = T>
+    where
+        T: Clone,
+    {
+        self.result.iter_result()
+    }
+}
+
+/// Witness for the product of two BigNums. The full product is computed and its upper
+/// half is constrained to be zero, so a product that doesn't fit in a word is rejected.
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct BigNumProduct<T> {
+    operands: BigNumOperands<T>,
+    product: Product<T, WIDE_BYTES>,
+    result: WordToBigNum<T>,
+}
+
+impl<F: PrimeField32> BigNumProduct<F> {
+    pub fn populate(
+        &mut self,
+        lhs: &[F; DIGEST_SIZE],
+        rhs: &[F; DIGEST_SIZE],
+        byte_record: &mut impl ByteRecord,
+    ) -> [F; DIGEST_SIZE] {
+        let (lhs, rhs) = self.operands.populate(lhs, rhs, byte_record);
+        let product: WideUInt = self
+            .product
+            .populate(&lhs.resize(), &rhs.resize(), byte_record);
+        self.result.populate(&product.resize(), byte_record)
+    }
+}
+
+impl<Var> BigNumProduct<Var> {
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        lhs: &[AB::Expr; DIGEST_SIZE],
+        rhs: &[AB::Expr; DIGEST_SIZE],
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> [AB::Var; DIGEST_SIZE]
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let (lhs, rhs) = self
+            .operands
+            .eval(builder, lhs, rhs, record, is_real.clone());
+        let product =
+            self.product
+                .eval(builder, &widen(&lhs), &widen(&rhs), record, is_real.clone());
+        // the product must fit in a word, so its upper half is zero
+        for &limb in &product[BIG_NUM_BYTES..] {
+            builder.when(is_real.clone()).assert_zero(limb);
+        }
+        let product = Word::from_fn(|i| product[i].into());
+        self.result.eval(builder, &product, record, is_real)
+    }
+}
+
+impl<T> BigNumProduct<T> {
+    pub const fn num_requires() -> usize {
+        BigNumOperands::<T>::num_requires()
+            + Product::<T, WIDE_BYTES>::num_requires()
+            + WordToBigNum::<T>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<BigNumProduct<u8>>()
+    }
+
+    pub fn iter_result(&self) -> impl IntoIterator<Item = T>
+    where
+        T: Clone,
+    {
+        self.result.iter_result()
+    }
+}
+
+/// Witness for the quotient and remainder of the division of two BigNums, where the
+/// divisor is non-zero
+#[derive(Clone, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct BigNumDivRem<T> {
+    operands: BigNumOperands<T>,
+    div_rem: DivRem<T, BIG_NUM_BYTES>,
+    quot: WordToBigNum<T>,
+    rem: WordToBigNum<T>,
+}
+
+impl<F: PrimeField32> BigNumDivRem<F> {
+    pub fn populate(
+        &mut self,
+        lhs: &[F; DIGEST_SIZE],
+        rhs: &[F; DIGEST_SIZE],
+        byte_record: &mut impl ByteRecord,
+    ) -> ([F; DIGEST_SIZE], [F; DIGEST_SIZE]) {
+        let (lhs, rhs) = self.operands.populate(lhs, rhs, byte_record);
+        let (quot, rem) = self.div_rem.populate(&lhs, &rhs, byte_record);
+        let quot = self.quot.populate(&quot, byte_record);
+        let rem = self.rem.populate(&rem, byte_record);
+        (quot, rem)
+    }
+}
+
+impl<Var> BigNumDivRem<Var> {
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        lhs: &[AB::Expr; DIGEST_SIZE],
+        rhs: &[AB::Expr; DIGEST_SIZE],
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> ([AB::Var; DIGEST_SIZE], [AB::Var; DIGEST_SIZE])
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let (lhs, rhs) = self
+            .operands
+            .eval(builder, lhs, rhs, record, is_real.clone());
+        let (quot, rem) = self
+            .div_rem
+            .eval(builder, &lhs, &rhs, record, is_real.clone());
+        let quot = self
+            .quot
+            .eval(builder, &quot.into(), record, is_real.clone());
+        let rem = self.rem.eval(builder, &rem.into(), record, is_real);
+        (quot, rem)
+    }
+}
+
+impl<T> BigNumDivRem<T> {
+    pub const fn num_requires() -> usize {
+        BigNumOperands::<T>::num_requires()
+            + DivRem::<T, BIG_NUM_BYTES>::num_requires()
+            + 2 * WordToBigNum::<T>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<BigNumDivRem<u8>>()
+    }
+
+    pub fn iter_result(&self) -> impl IntoIterator<Item = T>
+    where
+        T: Clone,
+    {
+        self.quot
+            .iter_result()
+            .into_iter()
+            .chain(self.rem.iter_result())
+    }
+}
+
+/// Witness for the modular multiplication `lhs * rhs % modulus` of BigNums, where the
+/// modulus is non-zero. The full product is computed, so it can't overflow.
+#[derive(Clone, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct BigNumMulMod<T> {
+    operands: BigNumOperands<T>,
+    modulus: BigNumToWord<T>,
+    product: Product<T, WIDE_BYTES>,
+    div_rem: DivRem<T, WIDE_BYTES>,
+    result: WordToBigNum<T>,
+}
+
+impl<F: PrimeField32> BigNumMulMod<F> {
+    pub fn populate(
+        &mut self,
+        lhs: &[F; DIGEST_SIZE],
+        rhs: &[F; DIGEST_SIZE],
+        modulus: &[F; DIGEST_SIZE],
+        byte_record: &mut impl ByteRecord,
+    ) -> [F; DIGEST_SIZE] {
+        let (lhs, rhs) = self.operands.populate(lhs, rhs, byte_record);
+        let modulus = self.modulus.populate(modulus, byte_record);
+        let product: WideUInt = self
+            .product
+            .populate(&lhs.resize(), &rhs.resize(), byte_record);
+        let (_quot, rem) = self
+            .div_rem
+            .populate(&product, &modulus.resize(), byte_record);
+        self.result.populate(&rem.resize(), byte_record)
+    }
+}
+
+impl<Var> BigNumMulMod<Var> {
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        lhs: &[AB::Expr; DIGEST_SIZE],
+        rhs: &[AB::Expr; DIGEST_SIZE],
+        modulus: &[AB::Expr; DIGEST_SIZE],
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> [AB::Var; DIGEST_SIZE]
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let (lhs, rhs) = self
+            .operands
+            .eval(builder, lhs, rhs, record, is_real.clone());
+        let modulus = self.modulus.eval(builder, modulus, record, is_real.clone());
+        let product =
+            self.product
+                .eval(builder, &widen(&lhs), &widen(&rhs), record, is_real.clone());
+        let (_quot, rem) = self.div_rem.eval(
+            builder,
+            &product.into(),
+            &widen(&modulus.into()),
+            record,
+            is_real.clone(),
+        );
+        // the remainder is smaller than the modulus, so its upper half is zero
+        let rem = Word::from_fn(|i| rem[i].into());
+        self.result.eval(builder, &rem, record, is_real)
+    }
+}
+
+impl<T> BigNumMulMod<T> {
+    pub const fn num_requires() -> usize {
+        BigNumOperands::<T>::num_requires()
+            + BigNumToWord::<T>::num_requires()
+            + Product::<T, WIDE_BYTES>::num_requires()
+            + DivRem::<T, WIDE_BYTES>::num_requires()
+            + WordToBigNum::<T>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<BigNumMulMod<u8>>()
+    }
+
+    pub fn iter_result(&self) -> impl IntoIterator<Item = T>
+    where
+        T: Clone,
+    {
+        self.result.iter_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::big_num::{biguint_to_field_elts, field_elts_to_biguint};
+    use crate::gadgets::big_num::digits::big_num_max;
+    use crate::gadgets::debug::{ByteRecordTester, GadgetTester};
+    use expect_test::expect;
+    use num_bigint::BigUint;
+    use p3_baby_bear::BabyBear;
+    use proptest::prelude::*;
+
+    type F = BabyBear;
+
+    const BABYBEAR_MOD: u32 = 0x78000001;
+
+    #[test]
+    fn test_witness_size() {
+        expect!["317"].assert_eq(&BigNumSum::<u8>::witness_size().to_string());
+        expect!["317"].assert_eq(&BigNumDiff::<u8>::witness_size().to_string());
+        expect!["413"].assert_eq(&BigNumProduct::<u8>::witness_size().to_string());
+        expect!["594"].assert_eq(&BigNumDivRem::<u8>::witness_size().to_string());
+        expect!["970"].assert_eq(&BigNumMulMod::<u8>::witness_size().to_string());
+    }
+
+    #[test]
+    fn test_num_requires() {
+        expect!["213"].assert_eq(&BigNumSum::<u8>::num_requires().to_string());
+        expect!["213"].assert_eq(&BigNumDiff::<u8>::num_requires().to_string());
+        expect!["293"].assert_eq(&BigNumProduct::<u8>::num_requires().to_string());
+        expect!["334"].assert_eq(&BigNumDivRem::<u8>::num_requires().to_string());
+        expect!["526"].assert_eq(&BigNumMulMod::<u8>::num_requires().to_string());
+    }
+
+    fn big_num(digest: [u32; DIGEST_SIZE]) -> [F; DIGEST_SIZE] {
+        digest.map(|x| x % BABYBEAR_MOD).map(F::from_canonical_u32)
+    }
+
+    fn test_arith_inner(lhs: [F; DIGEST_SIZE], rhs: [F; DIGEST_SIZE]) {
+        let (a, b) = (field_elts_to_biguint(&lhs), field_elts_to_biguint(&rhs));
+        let max = big_num_max::<F>();
+        let record = &mut ByteRecordTester::default();
+
+        if &a + &b <= max {
+            let mut witness = BigNumSum::<F>::default();
+            let sum = witness.populate(&lhs, &rhs, record);
+            assert_eq!(field_elts_to_biguint(&sum), &a + &b);
+            let sum_f = witness.eval(
+                &mut GadgetTester::passing(),
+                &lhs,
+                &rhs,
+                &mut record.passing(BigNumSum::<F>::num_requires()),
+                F::one(),
+            );
+            assert_eq!(sum_f, sum);
+        }
+
+        if a >= b {
+            let mut witness = BigNumDiff::<F>::default();
+            let diff = witness.populate(&lhs, &rhs, record);
+            assert_eq!(field_elts_to_biguint(&diff), &a - &b);
+            let diff_f = witness.eval(
+                &mut GadgetTester::passing(),
+                &lhs,
+                &rhs,
+                &mut record.passing(BigNumDiff::<F>::num_requires()),
+                F::one(),
+            );
+            assert_eq!(diff_f, diff);
+        }
+
+        if &a * &b <= max {
+            let mut witness = BigNumProduct::<F>::default();
+            let product = witness.populate(&lhs, &rhs, record);
+            assert_eq!(field_elts_to_biguint(&product), &a * &b);
+            let product_f = witness.eval(
+                &mut GadgetTester::passing(),
+                &lhs,
+                &rhs,
+                &mut record.passing(BigNumProduct::<F>::num_requires()),
+                F::one(),
+            );
+            assert_eq!(product_f, product);
+        }
+
+        if b != BigUint::ZERO {
+            let mut witness = BigNumDivRem::<F>::default();
+            let (quot, rem) = witness.populate(&lhs, &rhs, record);
+            assert_eq!(field_elts_to_biguint(&quot), &a / &b);
+            assert_eq!(field_elts_to_biguint(&rem), &a % &b);
+            let (quot_f, rem_f) = witness.eval(
+                &mut GadgetTester::passing(),
+                &lhs,
+                &rhs,
+                &mut record.passing(BigNumDivRem::<F>::num_requires()),
+                F::one(),
+            );
+            assert_eq!((quot_f, rem_f), (quot, rem));
+
+            let mut witness = BigNumMulMod::<F>::default();
+            let res = witness.populate(&lhs, &lhs, &rhs, record);
+            assert_eq!(field_elts_to_biguint(&res), &a * &a % &b);
+            let res_f = witness.eval(
+                &mut GadgetTester::passing(),
+                &lhs,
+                &lhs,
+                &rhs,
+                &mut record.passing(BigNumMulMod::<F>::num_requires()),
+                F::one(),
+            );
+            assert_eq!(res_f, res);
+        }
+    }
+
+    #[test]
+    fn test_arith_special() {
+        let zero = [F::zero(); DIGEST_SIZE];
+        let max = biguint_to_field_elts(&big_num_max::<F>());
+        let one = biguint_to_field_elts(&BigUint::from(1u32));
+        test_arith_inner(zero, zero);
+        test_arith_inner(max, zero);
+        test_arith_inner(max, one);
+        test_arith_inner(max, max);
+        test_arith_inner(one, max);
+    }
+
+    #[test]
+    fn test_sum_overflow_fails() {
+        // the sum of the largest BigNum with itself can't be represented
+        let max = biguint_to_field_elts::<F>(&big_num_max::<F>());
+        let record = &mut ByteRecordTester::default();
+        let mut witness = BigNumSum::<F>::default();
+        let (lhs, rhs) = witness.operands.populate(&max, &max, record);
+        witness.sum.populate(&lhs, &rhs, record);
+        witness.eval(
+            &mut GadgetTester::failing(),
+            &max,
+            &max,
+            &mut record.ignoring(),
+            F::one(),
+        );
+    }
+
+    #[test]
+    fn test_product_overflow_fails() {
+        // the square of the largest BigNum can't be represented, and its truncation to
+        // a word must not be accepted either
+        let max = biguint_to_field_elts::<F>(&big_num_max::<F>());
+        let record = &mut ByteRecordTester::default();
+        let mut witness = BigNumProduct::<F>::default();
+        let (lhs, rhs) = witness.operands.populate(&max, &max, record);
+        let _: WideUInt = witness
+            .product
+            .populate(&lhs.resize(), &rhs.resize(), record);
+        witness.eval(
+            &mut GadgetTester::failing(),
+            &max,
+            &max,
+            &mut record.ignoring(),
+            F::one(),
+        );
+    }
+
+    proptest! {
+
+    #[test]
+    fn test_arith(lhs: [u32; DIGEST_SIZE], rhs: [u32; DIGEST_SIZE]) {
+        test_arith_inner(big_num(lhs), big_num(rhs));
+    }
+
+    #[test]
+    fn test_arith_small(lhs: [u32; 4], rhs: [u32; 2]) {
+        // operands with fewer digits exercise the non-overflowing cases
+        let lhs = big_num([lhs[0], lhs[1], lhs[2], lhs[3], 0, 0, 0, 0]);
+        let rhs = big_num([rhs[0], rhs[1], 0, 0, 0, 0, 0, 0]);
+        test_arith_inner(lhs, rhs);
+        test_arith_inner(rhs, lhs);
+    }
+
+    }
+}
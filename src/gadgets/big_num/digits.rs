@@ -0,0 +1,357 @@
+//! Gadgets for converting between the digest representation of a BigNum, whose
+//! elements are the little-endian base-p digits of an integer smaller than p^8, and
+//! its little-endian byte representation, on which the unsigned gadgets operate.
+
+use crate::core::big_num::{biguint_to_field_elts, field_elts_to_biguint};
+use crate::core::zstore::DIGEST_SIZE;
+use crate::gadgets::big_num::uint::UInt;
+use crate::gadgets::bytes::{ByteAirRecord, ByteRecord};
+use crate::gadgets::unsigned::field::FieldToWord32;
+use crate::gadgets::unsigned::{UncheckedWord, Word, Word32, WORD32_SIZE};
+use num_bigint::BigUint;
+use num_traits::{FromBytes, ToBytes};
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, Field, PrimeField32};
+use sp1_derive::AlignedBorrow;
+use std::array;
+use std::iter::zip;
+
+/// The number of bytes of a BigNum, which is enough since p^8 < 2^248
+pub const BIG_NUM_BYTES: usize = 32;
+
+pub type BigNumWord<T> = Word<T, BIG_NUM_BYTES>;
+pub type BigNumUInt = UInt<BIG_NUM_BYTES>;
+
+/// Returns the little-endian bytes of `p^i` for every digit position `i`
+fn order_powers<F: Field>() -> [[u8; BIG_NUM_BYTES]; DIGEST_SIZE] {
+    let order = F::order();
+    array::from_fn(|i| BigNumUInt::from_biguint(&order.pow(i as u32)).to_le_bytes())
+}
+
+/// Witness variables for proving that a digest and a word represent the same integer.
+///
+/// Each digit is decomposed into bytes, and the weighted sum of the digit bytes by the
+/// bytes of the powers of p is compared against the word, byte by byte, with carries.
+#[derive(Clone, Debug, AlignedBorrow)]
+#[repr(C)]
+pub struct DigitsWitness<T> {
+    digits: [FieldToWord32<T>; DIGEST_SIZE],
+    carry: [T; BIG_NUM_BYTES - 1],
+}
+
+impl<F: PrimeField32> DigitsWitness<F> {
+    fn populate(
+        &mut self,
+        digest: &[F; DIGEST_SIZE],
+        byte_record: &mut impl ByteRecord,
+    ) -> [u8; BIG_NUM_BYTES] {
+        let digits: [[u8; WORD32_SIZE]; DIGEST_SIZE] = array::from_fn(|i| {
+            let digit = digest[i].as_canonical_u32();
+            self.digits[i].populate(&digit, byte_record);
+            digit.to_le_bytes()
+        });
+        let powers = order_powers::<F>();
+
+        let mut carry = 0u32;
+        let mut result = [0u8; BIG_NUM_BYTES];
+        for k in 0..BIG_NUM_BYTES {
+            // sum[k] = ∑_{i, j} digits[i][j] * powers[i][k - j]
+            let mut sum = carry;
+            for (digit, power) in zip(&digits, &powers) {
+                for (j, &limb) in digit.iter().enumerate().take(k + 1) {
+                    sum += u32::from(limb) * u32::from(power[k - j]);
+                }
+            }
+            let [limb, carry_lo, carry_hi, null] = sum.to_le_bytes();
+            debug_assert_eq!(null, 0);
+            result[k] = limb;
+            let carry_u16 = u16::from_le_bytes([carry_lo, carry_hi]);
+            if let Some(carry_f) = self.carry.get_mut(k) {
+                byte_record.range_check_u16(carry_u16);
+                *carry_f = F::from_canonical_u16(carry_u16);
+            } else {
+                debug_assert_eq!(carry_u16, 0, "integer too large");
+            }
+            carry = u32::from(carry_u16);
+        }
+        result
+    }
+}
+
+impl<Var> DigitsWitness<Var> {
+    /// Constraints for checking that the digest and the word, assumed to be range checked,
+    /// represent the same integer. The digits are checked to be canonical field elements.
+    fn assert_eq<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        digest: &[AB::Expr; DIGEST_SIZE],
+        word: &BigNumWord<AB::Expr>,
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let digits: [Word32<AB::Var>; DIGEST_SIZE] =
+            array::from_fn(|i| self.digits[i].eval(builder, &digest[i], record, is_real.clone()));
+        let powers = order_powers::<AB::F>();
+        let base = AB::F::from_canonical_u16(256);
+
+        // Since there are at most 32 products of bytes in each sum, all the values fit
+        // in 24 bits and no overflow can happen in the field.
+        let mut carry_prev = AB::Expr::zero();
+        for k in 0..BIG_NUM_BYTES {
+            let mut sum = carry_prev;
+            for (digit, power) in zip(&digits, &powers) {
+                for (j, &limb) in digit.iter().enumerate().take(k + 1) {
+                    if power[k - j] != 0 {
+                        sum += limb.into() * AB::F::from_canonical_u8(power[k - j]);
+                    }
+                }
+            }
+            // there is no carry out of the most significant byte
+            let carry = match self.carry.get(k) {
+                Some(&carry) => {
+                    record.range_check_u16(carry, is_real.clone());
+                    carry.into()
+                }
+                None => AB::Expr::zero(),
+            };
+            builder
+                .when(is_real.clone())
+                .assert_eq(sum, word[k].clone() + carry.clone() * base);
+            carry_prev = carry;
+        }
+    }
+}
+
+impl<T> DigitsWitness<T> {
+    pub const fn num_requires() -> usize {
+        FieldToWord32::<T>::num_requires() * DIGEST_SIZE + (BIG_NUM_BYTES - 1) // u16 carry checks
+    }
+}
+
+impl<T: Default> Default for DigitsWitness<T> {
+    fn default() -> Self {
+        Self {
+            digits: array::from_fn(|_| Default::default()),
+            carry: array::from_fn(|_| T::default()),
+        }
+    }
+}
+
+/// Converts a BigNum digest into the word of the integer it represents
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct BigNumToWord<T> {
+    witness: DigitsWitness<T>,
+    result: UncheckedWord<T, BIG_NUM_BYTES>,
+}
+
+impl<F: PrimeField32> BigNumToWord<F> {
+    pub fn populate(
+        &mut self,
+        digest: &[F; DIGEST_SIZE],
+        byte_record: &mut impl ByteRecord,
+    ) -> BigNumUInt {
+        let out = self.witness.populate(digest, byte_record);
+        self.result.assign_bytes(&out, byte_record);
+        BigNumUInt::from_le_bytes(&out)
+    }
+}
+
+impl<Var> BigNumToWord<Var> {
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        digest: &[AB::Expr; DIGEST_SIZE],
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> BigNumWord<AB::Var>
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        self.witness.assert_eq(
+            builder,
+            digest,
+            &self.result.into_unchecked().into(),
+            record,
+            is_real.clone(),
+        );
+        self.result.into_checked(record, is_real)
+    }
+}
+
+impl<T> BigNumToWord<T> {
+    pub const fn num_requires() -> usize {
+        DigitsWitness::<T>::num_requires() + BIG_NUM_BYTES / 2
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<BigNumToWord<u8>>()
+    }
+}
+
+/// Converts a range checked word into the BigNum digest of the same integer, which
+/// must be smaller than p^8
+#[derive(Clone, Debug, AlignedBorrow)]
+#[repr(C)]
+pub struct WordToBigNum<T> {
+    result: [T; DIGEST_SIZE],
+    witness: DigitsWitness<T>,
+}
+
+impl<F: PrimeField32> WordToBigNum<F> {
+    pub fn populate(
+        &mut self,
+        word: &BigNumUInt,
+        byte_record: &mut impl ByteRecord,
+    ) -> [F; DIGEST_SIZE] {
+        let digest = biguint_to_field_elts(&word.to_biguint());
+        let bytes = self.witness.populate(&digest, byte_record);
+        debug_assert_eq!(&bytes, &word.to_le_bytes());
+        self.result = digest;
+        digest
+    }
+}
+
+impl<Var> WordToBigNum<Var> {
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        word: &BigNumWord<AB::Expr>,
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> [AB::Var; DIGEST_SIZE]
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let digest: [AB::Expr; DIGEST_SIZE] = self.result.map(Into::into);
+        self.witness
+            .assert_eq(builder, &digest, word, record, is_real);
+        self.result
+    }
+}
+
+impl<T> WordToBigNum<T> {
+    pub const fn num_requires() -> usize {
+        DigitsWitness::<T>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<WordToBigNum<u8>>()
+    }
+
+    pub fn iter_result(&self) -> impl IntoIterator<Item = T>
+    where
+        T: Clone,
+    {
+        self.result.clone()
+    }
+}
+
+impl<T: Default> Default for WordToBigNum<T> {
+    fn default() -> Self {
+        Self {
+            result: array::from_fn(|_| T::default()),
+            witness: Default::default(),
+        }
+    }
+}
+
+/// Returns the integer represented by a BigNum digest as a word
+pub fn big_num_to_uint<F: PrimeField32>(digest: &[F]) -> BigNumUInt {
+    BigNumUInt::from_biguint(&field_elts_to_biguint(digest))
+}
+
+/// Returns the largest integer that can be represented by a BigNum, p^8 - 1
+pub fn big_num_max<F: Field>() -> BigUint {
+    F::order().pow(DIGEST_SIZE as u32) - 1u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::debug::{ByteRecordTester, GadgetTester};
+    use expect_test::expect;
+    use p3_baby_bear::BabyBear;
+    use proptest::prelude::*;
+
+    type F = BabyBear;
+
+    const BABYBEAR_MOD: u32 = 0x78000001;
+
+    #[test]
+    fn test_witness_size() {
+        expect!["103"].assert_eq(&BigNumToWord::<u8>::witness_size().to_string());
+        expect!["79"].assert_eq(&WordToBigNum::<u8>::witness_size().to_string());
+    }
+
+    #[test]
+    fn test_num_requires() {
+        expect!["71"].assert_eq(&BigNumToWord::<u8>::num_requires().to_string());
+        expect!["55"].assert_eq(&WordToBigNum::<u8>::num_requires().to_string());
+    }
+
+    fn test_digits_inner(digest: [F; DIGEST_SIZE]) {
+        let expected = big_num_to_uint(&digest);
+        let record = &mut ByteRecordTester::default();
+
+        let mut to_word = BigNumToWord::<F>::default();
+        let word = to_word.populate(&digest, record);
+        assert_eq!(word, expected);
+        let word_f = to_word.eval(
+            &mut GadgetTester::passing(),
+            &digest,
+            &mut record.passing(BigNumToWord::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(word_f, Word::from_unsigned(&expected));
+
+        let mut to_big_num = WordToBigNum::<F>::default();
+        let digest_out = to_big_num.populate(&word, record);
+        assert_eq!(digest_out, digest);
+        let digest_f = to_big_num.eval(
+            &mut GadgetTester::passing(),
+            &word_f,
+            &mut record.passing(WordToBigNum::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(digest_f, digest);
+    }
+
+    #[test]
+    fn test_digits_special() {
+        test_digits_inner([F::zero(); DIGEST_SIZE]);
+        test_digits_inner([F::neg_one(); DIGEST_SIZE]);
+    }
+
+    #[test]
+    fn test_digits_wrong_word() {
+        let digest = [F::one(); DIGEST_SIZE];
+        let record = &mut ByteRecordTester::default();
+        let mut witness = BigNumToWord::<F>::default();
+        let word = witness.populate(&digest, record);
+        let mut to_big_num = WordToBigNum::<F>::default();
+        to_big_num.populate(&word, record);
+        // the digest of a different integer can't be proven equal to the word
+        let other = word + BigNumUInt::from_biguint(&BigUint::from(1u32));
+        to_big_num.eval(
+            &mut GadgetTester::failing(),
+            &Word::from_unsigned(&other),
+            &mut record.ignoring(),
+            F::one(),
+        );
+    }
+
+    proptest! {
+
+    #[test]
+    fn test_digits(digest: [u32; DIGEST_SIZE]) {
+        let digest = digest.map(|x| x % BABYBEAR_MOD).map(F::from_canonical_u32);
+        test_digits_inner(digest);
+    }
+
+    }
+}
@@ -1 +1,4 @@
+pub mod arith;
 pub mod cmp;
+pub mod digits;
+pub mod uint;
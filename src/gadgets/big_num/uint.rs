@@ -0,0 +1,210 @@
+//! A fixed-size unsigned integer type, used for populating the unsigned gadgets with
+//! words that are larger than the native Rust integers
+
+use num_bigint::{BigUint, ParseBigIntError};
+use num_traits::ops::overflowing::{OverflowingAdd, OverflowingSub};
+use num_traits::{FromBytes, Num, One, ToBytes, Unsigned, Zero};
+use std::array;
+use std::cmp::Ordering;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// An unsigned integer with `W` little-endian bytes. Addition, subtraction and
+/// multiplication wrap around at the boundary of the type, like Rust's `wrapping_*`
+/// operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UInt<const W: usize>([u8; W]);
+
+impl<const W: usize> UInt<W> {
+    /// Converts from a `BigUint`, which must fit in `W` bytes
+    pub fn from_biguint(num: &BigUint) -> Self {
+        let bytes = num.to_bytes_le();
+        assert!(bytes.len() <= W, "Integer too large");
+        Self(array::from_fn(|i| bytes.get(i).copied().unwrap_or(0)))
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        BigUint::from_bytes_le(&self.0)
+    }
+
+    /// Truncates a `BigUint` to its `W` least significant bytes
+    fn wrapping_from_biguint(num: &BigUint) -> Self {
+        let bytes = num.to_bytes_le();
+        Self(array::from_fn(|i| bytes.get(i).copied().unwrap_or(0)))
+    }
+
+    /// Zero-extends (or truncates) to a different number of bytes
+    pub fn resize<const W2: usize>(&self) -> UInt<W2> {
+        UInt(array::from_fn(|i| self.0.get(i).copied().unwrap_or(0)))
+    }
+}
+
+impl<const W: usize> Ord for UInt<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl<const W: usize> PartialOrd for UInt<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const W: usize> Add for UInt<W> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.overflowing_add(&rhs).0
+    }
+}
+
+impl<const W: usize> Sub for UInt<W> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.overflowing_sub(&rhs).0
+    }
+}
+
+impl<const W: usize> Mul for UInt<W> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::wrapping_from_biguint(&(self.to_biguint() * rhs.to_biguint()))
+    }
+}
+
+impl<const W: usize> Div for UInt<W> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        Self::from_biguint(&(self.to_biguint() / rhs.to_biguint()))
+    }
+}
+
+impl<const W: usize> Rem for UInt<W> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self {
+        Self::from_biguint(&(self.to_biguint() % rhs.to_biguint()))
+    }
+}
+
+impl<const W: usize> OverflowingAdd for UInt<W> {
+    fn overflowing_add(&self, v: &Self) -> (Self, bool) {
+        let mut out = [0u8; W];
+        let mut carry = 0u16;
+        for (o, (&a, &b)) in out.iter_mut().zip(self.0.iter().zip(&v.0)) {
+            let sum = u16::from(a) + u16::from(b) + carry;
+            *o = sum as u8;
+            carry = sum >> 8;
+        }
+        (Self(out), carry != 0)
+    }
+}
+
+impl<const W: usize> OverflowingSub for UInt<W> {
+    fn overflowing_sub(&self, v: &Self) -> (Self, bool) {
+        let mut out = [0u8; W];
+        let mut borrow = false;
+        for (o, (&a, &b)) in out.iter_mut().zip(self.0.iter().zip(&v.0)) {
+            let (diff, borrow1) = a.overflowing_sub(b);
+            let (diff, borrow2) = diff.overflowing_sub(u8::from(borrow));
+            *o = diff;
+            borrow = borrow1 || borrow2;
+        }
+        (Self(out), borrow)
+    }
+}
+
+impl<const W: usize> Zero for UInt<W> {
+    fn zero() -> Self {
+        Self([0; W])
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(|&b| b == 0)
+    }
+}
+
+impl<const W: usize> One for UInt<W> {
+    fn one() -> Self {
+        Self(array::from_fn(|i| u8::from(i == 0)))
+    }
+}
+
+impl<const W: usize> Num for UInt<W> {
+    type FromStrRadixErr = ParseBigIntError;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        BigUint::from_str_radix(str, radix).map(|num| Self::wrapping_from_biguint(&num))
+    }
+}
+
+impl<const W: usize> Unsigned for UInt<W> {}
+
+impl<const W: usize> ToBytes for UInt<W> {
+    type Bytes = [u8; W];
+
+    fn to_be_bytes(&self) -> Self::Bytes {
+        let mut bytes = self.0;
+        bytes.reverse();
+        bytes
+    }
+
+    fn to_le_bytes(&self) -> Self::Bytes {
+        self.0
+    }
+}
+
+impl<const W: usize> FromBytes for UInt<W> {
+    type Bytes = [u8; W];
+
+    fn from_be_bytes(bytes: &Self::Bytes) -> Self {
+        let mut bytes = *bytes;
+        bytes.reverse();
+        Self(bytes)
+    }
+
+    fn from_le_bytes(bytes: &Self::Bytes) -> Self {
+        Self(*bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    type U128 = UInt<16>;
+
+    fn uint(u: u128) -> U128 {
+        U128::from_le_bytes(&u.to_le_bytes())
+    }
+
+    proptest! {
+
+    #[test]
+    fn test_ops(a: u128, b: u128) {
+        let (x, y) = (uint(a), uint(b));
+        assert_eq!(x + y, uint(a.wrapping_add(b)));
+        assert_eq!(x - y, uint(a.wrapping_sub(b)));
+        assert_eq!(x * y, uint(a.wrapping_mul(b)));
+        assert_eq!(x.overflowing_add(&y), (uint(a.wrapping_add(b)), a.checked_add(b).is_none()));
+        assert_eq!(x.overflowing_sub(&y), (uint(a.wrapping_sub(b)), a < b));
+        assert_eq!(x.cmp(&y), a.cmp(&b));
+        if b != 0 {
+            assert_eq!(x / y, uint(a / b));
+            assert_eq!(x % y, uint(a % b));
+        }
+    }
+
+    #[test]
+    fn test_resize(a: u64) {
+        let x = UInt::<8>::from_le_bytes(&a.to_le_bytes());
+        assert_eq!(x.resize::<16>(), uint(a.into()));
+        assert_eq!(uint(a.into()).resize::<8>(), x);
+    }
+
+    }
+}
@@ -97,6 +97,23 @@ impl BytesRecord {
         self.records.is_empty()
     }
 
+    /// Returns the number of distinct byte inputs that were looked up
+    pub fn num_inputs(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns the total number of lookups for each operation, in the same
+    /// order as `BytesInputRecord::iter_records`
+    pub fn lookup_counts(&self) -> [usize; 6] {
+        let mut counts = [0; 6];
+        for input_record in self.records.values() {
+            for (count, record) in counts.iter_mut().zip(input_record.iter_records()) {
+                *count += record.count as usize;
+            }
+        }
+        counts
+    }
+
     pub fn clear(&mut self) {
         self.records.clear()
     }
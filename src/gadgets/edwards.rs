@@ -0,0 +1,346 @@
+//! Gadgets for the twisted Edwards curve `x^2 + y^2 = 1 + 62 * x^2 * y^2` over the
+//! degree 7 extension `F[z] / (z^7 - z - 21)` of the BabyBear field. Extension elements
+//! are their coefficients in the basis `1, z, ..., z^6`. Since 62 isn't a square in the
+//! extension, the addition law is complete: the same formula adds distinct points,
+//! doubles points and adds the identity `(0, 1)`, without exceptions.
+
+use num_bigint::BigUint;
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, PrimeField32};
+use sp1_derive::AlignedBorrow;
+use std::array;
+
+/// The degree of the extension
+pub const EXT_DEGREE: usize = 7;
+
+/// The constant term of the reduction `z^7 = z + 21`
+const EXT_W: u32 = 21;
+
+/// The coefficient `d` of the curve
+const EDWARDS_D: u32 = 62;
+
+/// An element of the extension
+pub type Ext<T> = [T; EXT_DEGREE];
+
+fn ext_from_base<T: AbstractField>(c: T) -> Ext<T> {
+    array::from_fn(|i| if i == 0 { c.clone() } else { T::zero() })
+}
+
+fn ext_add<T: AbstractField>(a: &Ext<T>, b: &Ext<T>) -> Ext<T> {
+    array::from_fn(|i| a[i].clone() + b[i].clone())
+}
+
+fn ext_sub<T: AbstractField>(a: &Ext<T>, b: &Ext<T>) -> Ext<T> {
+    array::from_fn(|i| a[i].clone() - b[i].clone())
+}
+
+fn ext_scale<T: AbstractField>(a: &Ext<T>, c: u32) -> Ext<T> {
+    array::from_fn(|i| a[i].clone() * T::from_canonical_u32(c))
+}
+
+/// Multiplies extension elements, whose coefficients can be field elements as well as
+/// expressions. The degree of the result is the sum of the degrees of the operands.
+pub fn ext_mul<T: AbstractField>(a: &Ext<T>, b: &Ext<T>) -> Ext<T> {
+    let mut prod: [T; 2 * EXT_DEGREE - 1] = array::from_fn(|_| T::zero());
+    for (i, a_i) in a.iter().enumerate() {
+        for (j, b_j) in b.iter().enumerate() {
+            prod[i + j] += a_i.clone() * b_j.clone();
+        }
+    }
+    // reduce from the top with `z^(7 + j) = z^(j + 1) + 21 * z^j`
+    for k in (EXT_DEGREE..2 * EXT_DEGREE - 1).rev() {
+        let c = std::mem::replace(&mut prod[k], T::zero());
+        prod[k - EXT_DEGREE + 1] += c.clone();
+        prod[k - EXT_DEGREE] += c * T::from_canonical_u32(EXT_W);
+    }
+    array::from_fn(|i| prod[i].clone())
+}
+
+/// Inverts a non-zero extension element by raising it to `p^7 - 2`
+fn ext_inv<F: PrimeField32>(a: &Ext<F>) -> Ext<F> {
+    assert!(a.iter().any(|c| !c.is_zero()), "Inverse of zero");
+    let exp = BigUint::from(F::ORDER_U32).pow(EXT_DEGREE as u32) - 2u32;
+    let mut res = ext_from_base(F::one());
+    for i in (0..exp.bits()).rev() {
+        res = ext_mul(&res, &res);
+        if exp.bit(i) {
+            res = ext_mul(&res, a);
+        }
+    }
+    res
+}
+
+/// A point of the curve, or of its equation evaluated over expressions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Point<T> {
+    pub x: Ext<T>,
+    pub y: Ext<T>,
+}
+
+impl<T: Clone> Point<T> {
+    /// Splits the coordinates of a point out of their concatenation
+    pub fn from_slice(coords: &[T]) -> Self {
+        assert_eq!(coords.len(), 2 * EXT_DEGREE);
+        let x = array::from_fn(|i| coords[i].clone());
+        let y = array::from_fn(|i| coords[EXT_DEGREE + i].clone());
+        Self { x, y }
+    }
+
+    /// The concatenation of the coordinates
+    pub fn to_vec(&self) -> Vec<T> {
+        self.x.iter().chain(&self.y).cloned().collect()
+    }
+}
+
+impl<F: PrimeField32> Point<F> {
+    pub fn identity() -> Self {
+        Self {
+            x: ext_from_base(F::zero()),
+            y: ext_from_base(F::one()),
+        }
+    }
+
+    pub fn from_canonical_u32s(coords: &[u32]) -> Self {
+        let coords = coords
+            .iter()
+            .map(|c| F::from_canonical_u32(*c))
+            .collect::<Vec<_>>();
+        Self::from_slice(&coords)
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        ResidualWitness::<F>::default()
+            .populate(self)
+            .iter()
+            .all(F::is_zero)
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        AddWitness::<F>::default().populate(self, other)
+    }
+
+    /// Multiplies the point by a scalar, doubling and adding from the most significant
+    /// bit
+    pub fn mul(&self, scalar: &BigUint) -> Self {
+        let mut res = Self::identity();
+        for i in (0..scalar.bits()).rev() {
+            res = res.add(&res);
+            if scalar.bit(i) {
+                res = res.add(self);
+            }
+        }
+        res
+    }
+}
+
+/// Witness for the sum `(x, y)` of two points, with `x * (1 + d * w) = x1 * y2 + y1 * x2`
+/// and `y * (1 - d * w) = y1 * y2 - x1 * x2`, where `w = x1 * x2 * y1 * y2`
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct AddWitness<T> {
+    /// `x1 * x2`
+    u: Ext<T>,
+    /// `y1 * y2`
+    v: Ext<T>,
+    /// `u * v`
+    w: Ext<T>,
+    x: Ext<T>,
+    y: Ext<T>,
+}
+
+impl<F: PrimeField32> AddWitness<F> {
+    pub fn populate(&mut self, p1: &Point<F>, p2: &Point<F>) -> Point<F> {
+        let one = ext_from_base(F::one());
+        self.u = ext_mul(&p1.x, &p2.x);
+        self.v = ext_mul(&p1.y, &p2.y);
+        self.w = ext_mul(&self.u, &self.v);
+        let dw = ext_scale(&self.w, EDWARDS_D);
+        let x_num = ext_add(&ext_mul(&p1.x, &p2.y), &ext_mul(&p1.y, &p2.x));
+        let y_num = ext_sub(&self.v, &self.u);
+        let (x_den, y_den) = (ext_add(&one, &dw), ext_sub(&one, &dw));
+        // a single inversion for both denominators
+        let inv = ext_inv(&ext_mul(&x_den, &y_den));
+        self.x = ext_mul(&x_num, &ext_mul(&y_den, &inv));
+        self.y = ext_mul(&y_num, &ext_mul(&x_den, &inv));
+        Point {
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+impl<Var> AddWitness<Var> {
+    /// Constraints for checking that the result is the sum of the points, which are
+    /// assumed to be on the curve. The denominators of the addition law can't vanish
+    /// on the curve, so the result is determined by the constraints.
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        p1: &Point<AB::Expr>,
+        p2: &Point<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> Point<AB::Var>
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let [u, v, w, x, y] =
+            [self.u, self.v, self.w, self.x, self.y].map(|e| e.map(Into::<AB::Expr>::into));
+        let one = ext_from_base(AB::Expr::one());
+        let dw = ext_scale(&w, EDWARDS_D);
+        let x_num = ext_add(&ext_mul(&p1.x, &p2.y), &ext_mul(&p1.y, &p2.x));
+        let y_num = ext_sub(&v, &u);
+        let constraints = [
+            (u.clone(), ext_mul(&p1.x, &p2.x)),
+            (v.clone(), ext_mul(&p1.y, &p2.y)),
+            (w.clone(), ext_mul(&u, &v)),
+            (ext_mul(&x, &ext_add(&one, &dw)), x_num),
+            (ext_mul(&y, &ext_sub(&one, &dw)), y_num),
+        ];
+        for (lhs, rhs) in constraints {
+            for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                builder.when(is_real.clone()).assert_eq(lhs, rhs);
+            }
+        }
+        Point {
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+impl<T> AddWitness<T> {
+    pub const fn witness_size() -> usize {
+        size_of::<AddWitness<u8>>()
+    }
+}
+
+/// Witness for the residual `x^2 + y^2 - 1 - d * x^2 * y^2` of the curve equation at a
+/// point, which is zero if and only if the point is on the curve
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct ResidualWitness<T> {
+    xx: Ext<T>,
+    yy: Ext<T>,
+    xxyy: Ext<T>,
+}
+
+impl<F: PrimeField32> ResidualWitness<F> {
+    pub fn populate(&mut self, p: &Point<F>) -> Ext<F> {
+        self.xx = ext_mul(&p.x, &p.x);
+        self.yy = ext_mul(&p.y, &p.y);
+        self.xxyy = ext_mul(&self.xx, &self.yy);
+        residual(&self.xx, &self.yy, &self.xxyy)
+    }
+}
+
+impl<Var> ResidualWitness<Var> {
+    /// Constraints for the squares and their product. The residual is linear in the
+    /// witness.
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        p: &Point<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> Ext<AB::Expr>
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let [xx, yy, xxyy] = [self.xx, self.yy, self.xxyy].map(|e| e.map(Into::<AB::Expr>::into));
+        let constraints = [
+            (xx.clone(), ext_mul(&p.x, &p.x)),
+            (yy.clone(), ext_mul(&p.y, &p.y)),
+            (xxyy.clone(), ext_mul(&xx, &yy)),
+        ];
+        for (lhs, rhs) in constraints {
+            for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                builder.when(is_real.clone()).assert_eq(lhs, rhs);
+            }
+        }
+        residual(&xx, &yy, &xxyy)
+    }
+}
+
+impl<T> ResidualWitness<T> {
+    pub const fn witness_size() -> usize {
+        size_of::<ResidualWitness<u8>>()
+    }
+}
+
+fn residual<T: AbstractField>(xx: &Ext<T>, yy: &Ext<T>, xxyy: &Ext<T>) -> Ext<T> {
+    let lhs = ext_add(xx, yy);
+    let rhs = ext_add(&ext_from_base(T::one()), &ext_scale(xxyy, EDWARDS_D));
+    ext_sub(&lhs, &rhs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::debug::GadgetTester;
+    use expect_test::expect;
+    use p3_baby_bear::BabyBear;
+    use proptest::prelude::*;
+
+    type F = BabyBear;
+
+    /// A point of large prime order
+    const GENERATOR: [u32; 14] = [
+        133319257, 557589012, 1941653890, 812862691, 1975566944, 1422021201, 942940366, 269704925,
+        907390707, 2000607361, 612999609, 1710964308, 820806967, 864281447,
+    ];
+
+    #[test]
+    fn test_witness_size() {
+        expect!["35"].assert_eq(&AddWitness::<u8>::witness_size().to_string());
+        expect!["21"].assert_eq(&ResidualWitness::<u8>::witness_size().to_string());
+    }
+
+    #[test]
+    fn test_ext_inv() {
+        let a: Ext<F> = array::from_fn(|i| F::from_canonical_usize(i * i + 3));
+        assert_eq!(ext_mul(&a, &ext_inv(&a)), ext_from_base(F::one()));
+    }
+
+    #[test]
+    fn test_identity() {
+        let g = Point::<F>::from_canonical_u32s(&GENERATOR);
+        let identity = Point::identity();
+        assert!(g.is_on_curve());
+        assert!(identity.is_on_curve());
+        assert_eq!(g.add(&identity), g);
+        assert_eq!(identity.add(&identity), identity);
+        let neg_g = Point {
+            x: g.x.map(|c| -c),
+            y: g.y,
+        };
+        assert_eq!(g.add(&neg_g), identity);
+        assert!(!Point { x: g.y, y: g.y }.is_on_curve());
+    }
+
+    fn test_add(a: u64, b: u64) {
+        let g = Point::<F>::from_canonical_u32s(&GENERATOR);
+        let p1 = g.mul(&a.into());
+        let p2 = g.mul(&b.into());
+        let expected = g.mul(&(BigUint::from(a) + b));
+        let builder = &mut GadgetTester::passing();
+
+        let mut witness = AddWitness::<F>::default();
+        assert_eq!(witness.populate(&p1, &p2), expected);
+        assert_eq!(witness.eval(builder, &p1, &p2, F::one()), expected);
+
+        let mut witness = ResidualWitness::<F>::default();
+        let zero = ext_from_base(F::zero());
+        assert_eq!(witness.populate(&expected), zero);
+        assert_eq!(witness.eval(builder, &expected, F::one()), zero);
+    }
+
+    proptest! {
+
+    #[test]
+    fn test_adds(a in 0..64u64, b in 0..64u64) {
+        test_add(a, b)
+    }
+
+    }
+}
@@ -0,0 +1,483 @@
+//! Gadgets for the rounds of the Keccak-f[1600] permutation, over little-endian
+//! `Word64` lanes. The state is indexed by `x + 5 * y`. Rotations split the limbs of a
+//! lane at the offset within a limb, unless the offset is a multiple of 8, and the
+//! bitwise operations are lookups to the bytes chip.
+
+use crate::gadgets::bytes::{ByteAirRecord, ByteRecord};
+use crate::gadgets::unsigned::bitwise::{eval_not, Bitwise, BitwiseOp};
+use crate::gadgets::unsigned::rotate::{recombine, LimbSplit};
+use crate::gadgets::unsigned::{Word, Word64};
+use p3_field::AbstractField;
+use sp1_derive::AlignedBorrow;
+use std::array;
+
+/// The number of lanes absorbed from each block, for a rate of 136 bytes
+pub const KECCAK_RATE_LANES: usize = 17;
+
+/// The left rotation amounts of the rho step
+const RHO: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// The number of rho rotations whose amount isn't a multiple of 8
+const NUM_UNALIGNED_RHO: usize = 22;
+
+/// The lane that the pi step moves to lane `i`
+const fn pi_source(i: usize) -> usize {
+    let (x, y) = (i % 5, i / 5);
+    (x + 3 * y) % 5 + 5 * x
+}
+
+/// Returns the limb offset and the bit offset of a right rotation by `amount`
+const fn offsets(amount: u32) -> (usize, u32) {
+    ((amount / 8) as usize, amount % 8)
+}
+
+/// Witness for the theta step, which xors each lane with the parities of two
+/// neighbouring columns, one of them rotated by one bit
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct ThetaWitness<T> {
+    /// The xors of the lanes of each column
+    columns: [[Bitwise<T, 8>; 4]; 5],
+    /// Splits of the column parities for their rotations by one bit
+    rotations: [LimbSplit<T, 8>; 5],
+    d: [Bitwise<T, 8>; 5],
+    result: [Bitwise<T, 8>; 25],
+}
+
+impl<F: AbstractField> ThetaWitness<F> {
+    pub fn populate(&mut self, state: [u64; 25], byte_record: &mut impl ByteRecord) -> [u64; 25] {
+        let columns: [u64; 5] = array::from_fn(|x| {
+            let mut c = state[x];
+            for (y, xor) in self.columns[x].iter_mut().enumerate() {
+                c = xor.populate(BitwiseOp::Xor, &c, &state[x + 5 * (y + 1)], byte_record);
+            }
+            c
+        });
+        // a left rotation by one bit is a right rotation by 63 bits
+        let (_, k) = offsets(63);
+        for (split, c) in self.rotations.iter_mut().zip(&columns) {
+            split.populate(c, k, byte_record);
+        }
+        let d: [u64; 5] = array::from_fn(|x| {
+            let rotated = columns[(x + 1) % 5].rotate_left(1);
+            self.d[x].populate(BitwiseOp::Xor, &columns[(x + 4) % 5], &rotated, byte_record)
+        });
+        array::from_fn(|i| {
+            self.result[i].populate(BitwiseOp::Xor, &state[i], &d[i % 5], byte_record)
+        })
+    }
+}
+
+impl<Var> ThetaWitness<Var> {
+    /// Constraints for checking that the result is the theta step applied to the state,
+    /// which is assumed to be range checked. The result is range checked since it's the
+    /// output of lookups.
+    pub fn eval<Expr: AbstractField>(
+        &self,
+        state: &[Word64<Expr>; 25],
+        record: &mut impl ByteAirRecord<Expr>,
+        is_real: impl Into<Expr>,
+    ) -> [Word64<Var>; 25]
+    where
+        Var: Copy + Into<Expr>,
+    {
+        let is_real = is_real.into();
+        let columns: [Word64<Expr>; 5] = array::from_fn(|x| {
+            let mut c = state[x].clone();
+            for (y, xor) in self.columns[x].iter().enumerate() {
+                c = xor
+                    .eval(
+                        BitwiseOp::Xor,
+                        &c,
+                        &state[x + 5 * (y + 1)],
+                        record,
+                        is_real.clone(),
+                    )
+                    .into();
+            }
+            c
+        });
+        let (q, k) = offsets(63);
+        let rotated: [Word64<Expr>; 5] = array::from_fn(|x| {
+            let (low, high) = self.rotations[x].eval(&columns[x], k, record, is_real.clone());
+            recombine(&low, &high, q, k, true)
+        });
+        let d: [Word64<Expr>; 5] = array::from_fn(|x| {
+            let (lhs, rhs) = (&columns[(x + 4) % 5], &rotated[(x + 1) % 5]);
+            self.d[x]
+                .eval(BitwiseOp::Xor, lhs, rhs, record, is_real.clone())
+                .into()
+        });
+        array::from_fn(|i| {
+            self.result[i].eval(
+                BitwiseOp::Xor,
+                &state[i],
+                &d[i % 5],
+                record,
+                is_real.clone(),
+            )
+        })
+    }
+}
+
+impl<T> ThetaWitness<T> {
+    pub const fn num_requires() -> usize {
+        30 * Bitwise::<T, 8>::num_requires()
+            + 5 * LimbSplit::<T, 8>::num_requires()
+            + 25 * Bitwise::<T, 8>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<ThetaWitness<u8>>()
+    }
+}
+
+/// Witness for the remaining steps of a round: rho rotates the lanes, pi permutes them,
+/// chi xors each lane with `!next & next_next` along its row and iota xors the round
+/// constant into the first lane
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct RhoPiChiIotaWitness<T> {
+    /// Splits of the lanes whose rotations aren't byte aligned, in lane order
+    rotations: [LimbSplit<T, 8>; NUM_UNALIGNED_RHO],
+    and_not: [Bitwise<T, 8>; 25],
+    result: [Bitwise<T, 8>; 25],
+    iota: Bitwise<T, 8>,
+}
+
+impl<F: AbstractField> RhoPiChiIotaWitness<F> {
+    pub fn populate(
+        &mut self,
+        state: [u64; 25],
+        rc: u64,
+        byte_record: &mut impl ByteRecord,
+    ) -> [u64; 25] {
+        let mut splits = self.rotations.iter_mut();
+        let rotated: [u64; 25] = array::from_fn(|i| {
+            let (_, k) = offsets((64 - RHO[i]) % 64);
+            if k != 0 {
+                let split = splits.next().expect("Not enough rotation witnesses");
+                split.populate(&state[i], k, byte_record);
+            }
+            state[i].rotate_left(RHO[i])
+        });
+        let moved: [u64; 25] = array::from_fn(|i| rotated[pi_source(i)]);
+        let and_not: [u64; 25] = array::from_fn(|i| {
+            let (x, y) = (i % 5, i / 5);
+            let (next, next_next) = (moved[(x + 1) % 5 + 5 * y], moved[(x + 2) % 5 + 5 * y]);
+            self.and_not[i].populate(BitwiseOp::And, &!next, &next_next, byte_record)
+        });
+        let mut result: [u64; 25] = array::from_fn(|i| {
+            self.result[i].populate(BitwiseOp::Xor, &moved[i], &and_not[i], byte_record)
+        });
+        result[0] = self
+            .iota
+            .populate(BitwiseOp::Xor, &result[0], &rc, byte_record);
+        result
+    }
+}
+
+impl<Var> RhoPiChiIotaWitness<Var> {
+    /// Constraints for checking that the result is the rho, pi, chi and iota steps
+    /// applied to the state, with round constant `rc`, where the inputs are assumed to
+    /// be range checked. The result is range checked since it's the output of lookups.
+    pub fn eval<Expr: AbstractField>(
+        &self,
+        state: &[Word64<Expr>; 25],
+        rc: &Word64<Expr>,
+        record: &mut impl ByteAirRecord<Expr>,
+        is_real: impl Into<Expr>,
+    ) -> [Word64<Var>; 25]
+    where
+        Var: Copy + Into<Expr>,
+    {
+        let is_real = is_real.into();
+        let mut splits = self.rotations.iter();
+        let rotated: [Word64<Expr>; 25] = array::from_fn(|i| {
+            let (q, k) = offsets((64 - RHO[i]) % 64);
+            if k == 0 {
+                Word::from_fn(|j| state[i][(j + q) % 8].clone())
+            } else {
+                let split = splits.next().expect("Not enough rotation witnesses");
+                let (low, high) = split.eval(&state[i], k, record, is_real.clone());
+                recombine(&low, &high, q, k, true)
+            }
+        });
+        let moved: [Word64<Expr>; 25] = array::from_fn(|i| rotated[pi_source(i)].clone());
+        let and_not: [Word64<Expr>; 25] = array::from_fn(|i| {
+            let (x, y) = (i % 5, i / 5);
+            let not_next = eval_not(moved[(x + 1) % 5 + 5 * y].clone());
+            let next_next = &moved[(x + 2) % 5 + 5 * y];
+            self.and_not[i]
+                .eval(
+                    BitwiseOp::And,
+                    &not_next,
+                    next_next,
+                    record,
+                    is_real.clone(),
+                )
+                .into()
+        });
+        let mut result: [Word64<Var>; 25] = array::from_fn(|i| {
+            self.result[i].eval(
+                BitwiseOp::Xor,
+                &moved[i],
+                &and_not[i],
+                record,
+                is_real.clone(),
+            )
+        });
+        result[0] = self
+            .iota
+            .eval(BitwiseOp::Xor, &result[0].into(), rc, record, is_real);
+        result
+    }
+}
+
+impl<T> RhoPiChiIotaWitness<T> {
+    pub const fn num_requires() -> usize {
+        NUM_UNALIGNED_RHO * LimbSplit::<T, 8>::num_requires() + 51 * Bitwise::<T, 8>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<RhoPiChiIotaWitness<u8>>()
+    }
+}
+
+/// Witness for a whole round of the permutation, which is the theta step followed by
+/// the rho, pi, chi and iota steps
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct RoundWitness<T> {
+    theta: ThetaWitness<T>,
+    rho_pi_chi_iota: RhoPiChiIotaWitness<T>,
+}
+
+impl<F: AbstractField> RoundWitness<F> {
+    pub fn populate(
+        &mut self,
+        state: [u64; 25],
+        rc: u64,
+        byte_record: &mut impl ByteRecord,
+    ) -> [u64; 25] {
+        let state = self.theta.populate(state, byte_record);
+        self.rho_pi_chi_iota.populate(state, rc, byte_record)
+    }
+}
+
+impl<Var> RoundWitness<Var> {
+    /// Constraints for checking that the result is a round applied to the state, with
+    /// round constant `rc`, where the inputs are assumed to be range checked. The result
+    /// is range checked since it's the output of lookups.
+    pub fn eval<Expr: AbstractField>(
+        &self,
+        state: &[Word64<Expr>; 25],
+        rc: &Word64<Expr>,
+        record: &mut impl ByteAirRecord<Expr>,
+        is_real: impl Into<Expr>,
+    ) -> [Word64<Var>; 25]
+    where
+        Var: Copy + Into<Expr>,
+    {
+        let is_real = is_real.into();
+        let state = self.theta.eval(state, record, is_real.clone());
+        let state: [Word64<Expr>; 25] = state.map(|word| word.into());
+        self.rho_pi_chi_iota.eval(&state, rc, record, is_real)
+    }
+}
+
+impl<T> RoundWitness<T> {
+    pub const fn num_requires() -> usize {
+        ThetaWitness::<T>::num_requires() + RhoPiChiIotaWitness::<T>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<RoundWitness<u8>>()
+    }
+}
+
+/// Witness for absorbing a block into the state, which xors the lanes of the block into
+/// the first `KECCAK_RATE_LANES` lanes of the state
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct AbsorbWitness<T> {
+    lanes: [Bitwise<T, 8>; KECCAK_RATE_LANES],
+}
+
+impl<F: AbstractField> AbsorbWitness<F> {
+    pub fn populate(
+        &mut self,
+        state: [u64; 25],
+        block: [u64; KECCAK_RATE_LANES],
+        byte_record: &mut impl ByteRecord,
+    ) -> [u64; 25] {
+        let mut result = state;
+        for (i, xor) in self.lanes.iter_mut().enumerate() {
+            result[i] = xor.populate(BitwiseOp::Xor, &state[i], &block[i], byte_record);
+        }
+        result
+    }
+}
+
+impl<Var> AbsorbWitness<Var> {
+    /// Constraints for checking that the result is the state with the block absorbed,
+    /// where the inputs are assumed to be range checked
+    pub fn eval<Expr: AbstractField>(
+        &self,
+        state: &[Word64<Expr>; 25],
+        block: &[Word64<Expr>; KECCAK_RATE_LANES],
+        record: &mut impl ByteAirRecord<Expr>,
+        is_real: impl Into<Expr>,
+    ) -> [Word64<Expr>; 25]
+    where
+        Var: Copy + Into<Expr>,
+    {
+        let is_real = is_real.into();
+        let mut result = state.clone();
+        for (i, xor) in self.lanes.iter().enumerate() {
+            result[i] = xor
+                .eval(
+                    BitwiseOp::Xor,
+                    &state[i],
+                    &block[i],
+                    record,
+                    is_real.clone(),
+                )
+                .into();
+        }
+        result
+    }
+}
+
+impl<T> AbsorbWitness<T> {
+    pub const fn num_requires() -> usize {
+        KECCAK_RATE_LANES * Bitwise::<T, 8>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<AbsorbWitness<u8>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::debug::ByteRecordTester;
+    use expect_test::expect;
+    use p3_baby_bear::BabyBear;
+    use proptest::prelude::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_witness_size() {
+        expect!["440"].assert_eq(&ThetaWitness::<u8>::witness_size().to_string());
+        expect!["584"].assert_eq(&RhoPiChiIotaWitness::<u8>::witness_size().to_string());
+        expect!["1024"].assert_eq(&RoundWitness::<u8>::witness_size().to_string());
+        expect!["136"].assert_eq(&AbsorbWitness::<u8>::witness_size().to_string());
+    }
+
+    #[test]
+    fn test_num_requires() {
+        expect!["440"].assert_eq(&ThetaWitness::<u8>::num_requires().to_string());
+        expect!["584"].assert_eq(&RhoPiChiIotaWitness::<u8>::num_requires().to_string());
+        expect!["1024"].assert_eq(&RoundWitness::<u8>::num_requires().to_string());
+        expect!["136"].assert_eq(&AbsorbWitness::<u8>::num_requires().to_string());
+    }
+
+    #[test]
+    fn test_unaligned_rho() {
+        let unaligned = RHO.iter().filter(|r| *r % 8 != 0).count();
+        assert_eq!(unaligned, NUM_UNALIGNED_RHO);
+    }
+
+    fn word(x: u64) -> Word64<F> {
+        Word::from_unsigned(&x)
+    }
+
+    /// A direct implementation of a round, following the specification
+    fn reference_round(state: [u64; 25], rc: u64) -> [u64; 25] {
+        let c: [u64; 5] = array::from_fn(|x| (0..5).fold(0, |acc, y| acc ^ state[x + 5 * y]));
+        let d: [u64; 5] = array::from_fn(|x| c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1));
+        let a: [u64; 25] = array::from_fn(|i| state[i] ^ d[i % 5]);
+        let mut b = [0; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = a[x + 5 * y].rotate_left(RHO[x + 5 * y]);
+            }
+        }
+        let mut res: [u64; 25] = array::from_fn(|i| {
+            let (x, y) = (i % 5, i / 5);
+            b[i] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y])
+        });
+        res[0] ^= rc;
+        res
+    }
+
+    fn test_round(state: [u64; 25], rc: u64) {
+        let record = &mut ByteRecordTester::default();
+        let mut theta = ThetaWitness::<F>::default();
+        let mut rest = RhoPiChiIotaWitness::<F>::default();
+        let mid = theta.populate(state, record);
+        let out = rest.populate(mid, rc, record);
+        assert_eq!(out, reference_round(state, rc));
+
+        let mid_f = theta.eval(
+            &state.map(word),
+            &mut record.passing(ThetaWitness::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(mid_f, mid.map(word));
+        let out_f = rest.eval(
+            &mid.map(word),
+            &word(rc),
+            &mut record.passing(RhoPiChiIotaWitness::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(out_f, out.map(word));
+
+        let mut round = RoundWitness::<F>::default();
+        assert_eq!(round.populate(state, rc, record), out);
+        let round_f = round.eval(
+            &state.map(word),
+            &word(rc),
+            &mut record.passing(RoundWitness::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(round_f, out.map(word));
+    }
+
+    fn test_absorb(state: [u64; 25], block: [u64; KECCAK_RATE_LANES]) {
+        let record = &mut ByteRecordTester::default();
+        let mut witness = AbsorbWitness::<F>::default();
+        let out = witness.populate(state, block, record);
+        let expected: [u64; 25] = array::from_fn(|i| match block.get(i) {
+            Some(lane) => state[i] ^ lane,
+            None => state[i],
+        });
+        assert_eq!(out, expected);
+        let out_f = witness.eval(
+            &state.map(word),
+            &block.map(word),
+            &mut record.passing(AbsorbWitness::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(out_f, out.map(word));
+    }
+
+    proptest! {
+
+    #[test]
+    fn test_rounds(state: [u64; 25], rc: u64) {
+        test_round(state, rc)
+    }
+
+    #[test]
+    fn test_absorbs(state: [u64; 25], block: [u64; KECCAK_RATE_LANES]) {
+        test_absorb(state, block)
+    }
+
+    }
+}
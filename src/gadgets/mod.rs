@@ -1,4 +1,8 @@
 pub mod big_num;
 pub mod bytes;
 pub mod debug;
+pub mod edwards;
+pub mod keccak;
+pub mod sha256;
+pub mod signed;
 pub mod unsigned;
@@ -0,0 +1,592 @@
+//! Gadgets for the SHA-256 compression function, over little-endian `Word32`s.
+//! Rotations and shifts split the limbs of a word at the offset within a limb, which
+//! turns them into linear combinations of the parts, and the bitwise operations are
+//! lookups to the bytes chip.
+
+use crate::gadgets::bytes::{ByteAirRecord, ByteRecord};
+use crate::gadgets::unsigned::add::Sum;
+use crate::gadgets::unsigned::bitwise::{eval_not, Bitwise, BitwiseOp};
+use crate::gadgets::unsigned::rotate::{recombine, LimbSplit};
+use crate::gadgets::unsigned::{Word, Word32};
+use p3_air::AirBuilder;
+use p3_field::AbstractField;
+use sp1_derive::AlignedBorrow;
+use std::array;
+
+/// The sigma functions of SHA-256. Each one xors two rotations of its input with a
+/// third rotation, for the uppercase ones used by the rounds, or with a shift, for the
+/// lowercase ones used by the message schedule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sigma {
+    Upper0,
+    Upper1,
+    Lower0,
+    Lower1,
+}
+
+impl Sigma {
+    /// The amounts of the rotations and of the final rotation or shift, none of which
+    /// is a multiple of 8
+    const fn amounts(self) -> [u32; 3] {
+        match self {
+            Sigma::Upper0 => [2, 13, 22],
+            Sigma::Upper1 => [6, 11, 25],
+            Sigma::Lower0 => [7, 18, 3],
+            Sigma::Lower1 => [17, 19, 10],
+        }
+    }
+
+    const fn shifts(self) -> bool {
+        matches!(self, Sigma::Lower0 | Sigma::Lower1)
+    }
+
+    pub fn apply(self, x: u32) -> u32 {
+        let [r0, r1, r2] = self.amounts();
+        let last = if self.shifts() {
+            x >> r2
+        } else {
+            x.rotate_right(r2)
+        };
+        x.rotate_right(r0) ^ x.rotate_right(r1) ^ last
+    }
+}
+
+/// Witness for the application of a `Sigma` function
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct SigmaWitness<T> {
+    splits: [LimbSplit<T, 4>; 3],
+    partial: Bitwise<T, 4>,
+    result: Bitwise<T, 4>,
+}
+
+impl<F: AbstractField> SigmaWitness<F> {
+    pub fn populate(&mut self, sigma: Sigma, x: u32, byte_record: &mut impl ByteRecord) -> u32 {
+        let [r0, r1, r2] = sigma.amounts();
+        for (split, r) in self.splits.iter_mut().zip([r0, r1, r2]) {
+            split.populate(&x, r % 8, byte_record);
+        }
+        let last = if sigma.shifts() {
+            x >> r2
+        } else {
+            x.rotate_right(r2)
+        };
+        let partial = self.partial.populate(
+            BitwiseOp::Xor,
+            &x.rotate_right(r0),
+            &x.rotate_right(r1),
+            byte_record,
+        );
+        self.result
+            .populate(BitwiseOp::Xor, &partial, &last, byte_record)
+    }
+}
+
+impl<Var> SigmaWitness<Var> {
+    /// Constraints for checking that the result is `sigma(x)`, where `x` is assumed to
+    /// be range checked. The result is range checked since it's the output of lookups.
+    pub fn eval<Expr: AbstractField>(
+        &self,
+        sigma: Sigma,
+        x: &Word32<Expr>,
+        record: &mut impl ByteAirRecord<Expr>,
+        is_real: impl Into<Expr>,
+    ) -> Word32<Var>
+    where
+        Var: Copy + Into<Expr>,
+    {
+        let is_real = is_real.into();
+        let amounts = sigma.amounts();
+        let [moved0, moved1, moved2]: [Word32<Expr>; 3] = array::from_fn(|i| {
+            let (q, k) = ((amounts[i] / 8) as usize, amounts[i] % 8);
+            let (low, high) = self.splits[i].eval(x, k, record, is_real.clone());
+            recombine(&low, &high, q, k, i < 2 || !sigma.shifts())
+        });
+        let partial = self
+            .partial
+            .eval(BitwiseOp::Xor, &moved0, &moved1, record, is_real.clone());
+        self.result
+            .eval(BitwiseOp::Xor, &partial.into(), &moved2, record, is_real)
+    }
+}
+
+impl<T> SigmaWitness<T> {
+    pub const fn num_requires() -> usize {
+        3 * LimbSplit::<T, 4>::num_requires() + 2 * Bitwise::<T, 4>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<SigmaWitness<u8>>()
+    }
+}
+
+/// Witness for `ch(e, f, g) = (e & f) ^ (!e & g)`, which is computed as the sum of the
+/// two terms since they have no bits in common
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct ChWitness<T> {
+    e_and_f: Bitwise<T, 4>,
+    not_e_and_g: Bitwise<T, 4>,
+}
+
+impl<F: AbstractField> ChWitness<F> {
+    pub fn populate(&mut self, e: u32, f: u32, g: u32, byte_record: &mut impl ByteRecord) -> u32 {
+        let e_and_f = self.e_and_f.populate(BitwiseOp::And, &e, &f, byte_record);
+        let not_e_and_g = self
+            .not_e_and_g
+            .populate(BitwiseOp::And, &!e, &g, byte_record);
+        e_and_f | not_e_and_g
+    }
+}
+
+impl<Var> ChWitness<Var> {
+    pub fn eval<Expr: AbstractField>(
+        &self,
+        e: &Word32<Expr>,
+        f: &Word32<Expr>,
+        g: &Word32<Expr>,
+        record: &mut impl ByteAirRecord<Expr>,
+        is_real: impl Into<Expr>,
+    ) -> Word32<Expr>
+    where
+        Var: Copy + Into<Expr>,
+    {
+        let is_real = is_real.into();
+        let e_and_f = self
+            .e_and_f
+            .eval(BitwiseOp::And, e, f, record, is_real.clone());
+        let not_e = eval_not(e.clone());
+        let not_e_and_g = self
+            .not_e_and_g
+            .eval(BitwiseOp::And, &not_e, g, record, is_real);
+        Word::from_fn(|i| {
+            let (lhs, rhs): (Expr, Expr) = (e_and_f[i].into(), not_e_and_g[i].into());
+            lhs + rhs
+        })
+    }
+}
+
+impl<T> ChWitness<T> {
+    pub const fn num_requires() -> usize {
+        2 * Bitwise::<T, 4>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<ChWitness<u8>>()
+    }
+}
+
+/// Witness for `maj(a, b, c) = (a & b) ^ (a & c) ^ (b & c)`, which is computed as
+/// `(a & b) + (c & (a ^ b))` since these two terms have no bits in common
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct MajWitness<T> {
+    a_and_b: Bitwise<T, 4>,
+    a_xor_b: Bitwise<T, 4>,
+    c_and_a_xor_b: Bitwise<T, 4>,
+}
+
+impl<F: AbstractField> MajWitness<F> {
+    pub fn populate(&mut self, a: u32, b: u32, c: u32, byte_record: &mut impl ByteRecord) -> u32 {
+        let a_and_b = self.a_and_b.populate(BitwiseOp::And, &a, &b, byte_record);
+        let a_xor_b = self.a_xor_b.populate(BitwiseOp::Xor, &a, &b, byte_record);
+        let c_and_a_xor_b = self
+            .c_and_a_xor_b
+            .populate(BitwiseOp::And, &c, &a_xor_b, byte_record);
+        a_and_b | c_and_a_xor_b
+    }
+}
+
+impl<Var> MajWitness<Var> {
+    pub fn eval<Expr: AbstractField>(
+        &self,
+        a: &Word32<Expr>,
+        b: &Word32<Expr>,
+        c: &Word32<Expr>,
+        record: &mut impl ByteAirRecord<Expr>,
+        is_real: impl Into<Expr>,
+    ) -> Word32<Expr>
+    where
+        Var: Copy + Into<Expr>,
+    {
+        let is_real = is_real.into();
+        let a_and_b = self
+            .a_and_b
+            .eval(BitwiseOp::And, a, b, record, is_real.clone());
+        let a_xor_b = self
+            .a_xor_b
+            .eval(BitwiseOp::Xor, a, b, record, is_real.clone());
+        let c_and_a_xor_b =
+            self.c_and_a_xor_b
+                .eval(BitwiseOp::And, c, &a_xor_b.into(), record, is_real);
+        Word::from_fn(|i| {
+            let (lhs, rhs): (Expr, Expr) = (a_and_b[i].into(), c_and_a_xor_b[i].into());
+            lhs + rhs
+        })
+    }
+}
+
+impl<T> MajWitness<T> {
+    pub const fn num_requires() -> usize {
+        3 * Bitwise::<T, 4>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<MajWitness<u8>>()
+    }
+}
+
+/// Witness for a round of the SHA-256 compression function on the state
+/// `[a, b, c, d, e, f, g, h]`, with round constant `k` and message schedule word `w`.
+/// The round returns the new `a` and `e`, since the other words of the new state are
+/// the previous `a`, `b`, `c`, `e`, `f` and `g`.
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct RoundWitness<T> {
+    upper1: SigmaWitness<T>,
+    ch: ChWitness<T>,
+    /// Partial sums of `t1 = h + Σ1(e) + ch(e, f, g) + k + w`
+    t1: [Sum<T, 4>; 4],
+    upper0: SigmaWitness<T>,
+    maj: MajWitness<T>,
+    /// `t2 = Σ0(a) + maj(a, b, c)`
+    t2: Sum<T, 4>,
+    a: Sum<T, 4>,
+    e: Sum<T, 4>,
+}
+
+impl<F: AbstractField> RoundWitness<F> {
+    pub fn populate(
+        &mut self,
+        state: [u32; 8],
+        k: u32,
+        w: u32,
+        byte_record: &mut impl ByteRecord,
+    ) -> (u32, u32) {
+        let [a, b, c, d, e, f, g, h] = state;
+        let upper1 = self.upper1.populate(Sigma::Upper1, e, byte_record);
+        let ch = self.ch.populate(e, f, g, byte_record);
+        let mut t1 = h;
+        for (sum, term) in self.t1.iter_mut().zip([upper1, ch, k, w]) {
+            t1 = sum.populate(&t1, &term, byte_record);
+        }
+        let upper0 = self.upper0.populate(Sigma::Upper0, a, byte_record);
+        let maj = self.maj.populate(a, b, c, byte_record);
+        let t2 = self.t2.populate(&upper0, &maj, byte_record);
+        let new_a = self.a.populate(&t1, &t2, byte_record);
+        let new_e = self.e.populate(&d, &t1, byte_record);
+        (new_a, new_e)
+    }
+}
+
+impl<Var> RoundWitness<Var> {
+    /// Constraints for checking that the results are the new `a` and `e` of the state,
+    /// where the inputs are assumed to be range checked. The results are range checked
+    /// since they're the outputs of additions.
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        state: &[Word32<AB::Expr>; 8],
+        k: &Word32<AB::Expr>,
+        w: &Word32<AB::Expr>,
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> (Word32<AB::Var>, Word32<AB::Var>)
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let [a, b, c, d, e, f, g, h] = state;
+        let upper1 = self.upper1.eval(Sigma::Upper1, e, record, is_real.clone());
+        let ch = self.ch.eval(e, f, g, record, is_real.clone());
+        let mut t1 = h.clone();
+        for (sum, term) in self
+            .t1
+            .iter()
+            .zip([upper1.into(), ch, k.clone(), w.clone()])
+        {
+            t1 = sum.eval(builder, t1, term, record, is_real.clone()).into();
+        }
+        let upper0 = self.upper0.eval(Sigma::Upper0, a, record, is_real.clone());
+        let maj = self.maj.eval(a, b, c, record, is_real.clone());
+        let t2 = self
+            .t2
+            .eval(builder, upper0.into(), maj, record, is_real.clone());
+        let new_a = self
+            .a
+            .eval(builder, t1.clone(), t2.into(), record, is_real.clone());
+        let new_e = self.e.eval(builder, d.clone(), t1, record, is_real);
+        (new_a, new_e)
+    }
+}
+
+impl<T> RoundWitness<T> {
+    pub const fn num_requires() -> usize {
+        2 * SigmaWitness::<T>::num_requires()
+            + ChWitness::<T>::num_requires()
+            + MajWitness::<T>::num_requires()
+            + 7 * Sum::<T, 4>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<RoundWitness<u8>>()
+    }
+}
+
+/// Witness for the message schedule word `w[t] = σ1(w[t-2]) + w[t-7] + σ0(w[t-15]) +
+/// w[t-16]`
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct ScheduleWitness<T> {
+    lower0: SigmaWitness<T>,
+    lower1: SigmaWitness<T>,
+    sums: [Sum<T, 4>; 3],
+}
+
+impl<F: AbstractField> ScheduleWitness<F> {
+    /// Populates the witness given `[w[t-16], w[t-15], w[t-7], w[t-2]]`
+    pub fn populate(&mut self, words: [u32; 4], byte_record: &mut impl ByteRecord) -> u32 {
+        let [w16, w15, w7, w2] = words;
+        let lower0 = self.lower0.populate(Sigma::Lower0, w15, byte_record);
+        let lower1 = self.lower1.populate(Sigma::Lower1, w2, byte_record);
+        let mut res = w16;
+        for (sum, term) in self.sums.iter_mut().zip([lower0, w7, lower1]) {
+            res = sum.populate(&res, &term, byte_record);
+        }
+        res
+    }
+}
+
+impl<Var> ScheduleWitness<Var> {
+    /// Constraints for checking that the result is the next word of the message
+    /// schedule given `[w[t-16], w[t-15], w[t-7], w[t-2]]`, which are assumed to be
+    /// range checked
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        words: &[Word32<AB::Expr>; 4],
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> Word32<AB::Var>
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let [w16, w15, w7, w2] = words;
+        let lower0 = self
+            .lower0
+            .eval(Sigma::Lower0, w15, record, is_real.clone());
+        let lower1 = self.lower1.eval(Sigma::Lower1, w2, record, is_real.clone());
+        let [sum0, sum1, sum2] = &self.sums;
+        let res = sum0.eval(builder, w16.clone(), lower0.into(), record, is_real.clone());
+        let res = sum1.eval(builder, res.into(), w7.clone(), record, is_real.clone());
+        sum2.eval(builder, res.into(), lower1.into(), record, is_real)
+    }
+}
+
+impl<T> ScheduleWitness<T> {
+    pub const fn num_requires() -> usize {
+        2 * SigmaWitness::<T>::num_requires() + 3 * Sum::<T, 4>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<ScheduleWitness<u8>>()
+    }
+}
+
+/// Witness for adding the result of compressing a block to the previous state, word by
+/// word
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct StateSum<T> {
+    sums: [Sum<T, 4>; 8],
+}
+
+impl<F: AbstractField> StateSum<F> {
+    pub fn populate(
+        &mut self,
+        state: [u32; 8],
+        compressed: [u32; 8],
+        byte_record: &mut impl ByteRecord,
+    ) -> [u32; 8] {
+        array::from_fn(|i| self.sums[i].populate(&state[i], &compressed[i], byte_record))
+    }
+}
+
+impl<Var> StateSum<Var> {
+    /// Constraints for checking that the result is the word by word sum of the states,
+    /// which are assumed to be range checked
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        state: &[Word32<AB::Expr>; 8],
+        compressed: &[Word32<AB::Expr>; 8],
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> [Word32<AB::Var>; 8]
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        array::from_fn(|i| {
+            self.sums[i].eval(
+                builder,
+                state[i].clone(),
+                compressed[i].clone(),
+                record,
+                is_real.clone(),
+            )
+        })
+    }
+}
+
+impl<T> StateSum<T> {
+    pub const fn num_requires() -> usize {
+        8 * Sum::<T, 4>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<StateSum<u8>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::debug::{ByteRecordTester, GadgetTester};
+    use expect_test::expect;
+    use p3_baby_bear::BabyBear;
+    use proptest::prelude::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_witness_size() {
+        expect!["20"].assert_eq(&SigmaWitness::<u8>::witness_size().to_string());
+        expect!["8"].assert_eq(&ChWitness::<u8>::witness_size().to_string());
+        expect!["12"].assert_eq(&MajWitness::<u8>::witness_size().to_string());
+        expect!["88"].assert_eq(&RoundWitness::<u8>::witness_size().to_string());
+        expect!["52"].assert_eq(&ScheduleWitness::<u8>::witness_size().to_string());
+        expect!["32"].assert_eq(&StateSum::<u8>::witness_size().to_string());
+    }
+
+    #[test]
+    fn test_num_requires() {
+        expect!["20"].assert_eq(&SigmaWitness::<u8>::num_requires().to_string());
+        expect!["8"].assert_eq(&ChWitness::<u8>::num_requires().to_string());
+        expect!["12"].assert_eq(&MajWitness::<u8>::num_requires().to_string());
+        expect!["74"].assert_eq(&RoundWitness::<u8>::num_requires().to_string());
+        expect!["46"].assert_eq(&ScheduleWitness::<u8>::num_requires().to_string());
+        expect!["16"].assert_eq(&StateSum::<u8>::num_requires().to_string());
+    }
+
+    fn word(x: u32) -> Word32<F> {
+        Word::from_unsigned(&x)
+    }
+
+    fn test_sigma(sigma: Sigma, x: u32) {
+        let record = &mut ByteRecordTester::default();
+        let mut witness = SigmaWitness::<F>::default();
+        let out = witness.populate(sigma, x, record);
+        assert_eq!(out, sigma.apply(x));
+        let out_f = witness.eval(
+            sigma,
+            &word(x),
+            &mut record.passing(SigmaWitness::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(out_f, word(out));
+    }
+
+    fn test_round(state: [u32; 8], k: u32, w: u32) {
+        let [a, b, c, d, e, f, g, h] = state;
+        let t1 = h
+            .wrapping_add(Sigma::Upper1.apply(e))
+            .wrapping_add((e & f) ^ (!e & g))
+            .wrapping_add(k)
+            .wrapping_add(w);
+        let t2 = Sigma::Upper0
+            .apply(a)
+            .wrapping_add((a & b) ^ (a & c) ^ (b & c));
+        let expected = (t1.wrapping_add(t2), d.wrapping_add(t1));
+
+        let record = &mut ByteRecordTester::default();
+        let mut witness = RoundWitness::<F>::default();
+        let out = witness.populate(state, k, w, record);
+        assert_eq!(out, expected);
+        let (a_f, e_f) = witness.eval(
+            &mut GadgetTester::passing(),
+            &state.map(word),
+            &word(k),
+            &word(w),
+            &mut record.passing(RoundWitness::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!((a_f, e_f), (word(out.0), word(out.1)));
+    }
+
+    fn test_schedule(words: [u32; 4]) {
+        let [w16, w15, w7, w2] = words;
+        let expected = Sigma::Lower1
+            .apply(w2)
+            .wrapping_add(w7)
+            .wrapping_add(Sigma::Lower0.apply(w15))
+            .wrapping_add(w16);
+
+        let record = &mut ByteRecordTester::default();
+        let mut witness = ScheduleWitness::<F>::default();
+        let out = witness.populate(words, record);
+        assert_eq!(out, expected);
+        let out_f = witness.eval(
+            &mut GadgetTester::passing(),
+            &words.map(word),
+            &mut record.passing(ScheduleWitness::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(out_f, word(out));
+    }
+
+    fn test_state_sum(state: [u32; 8], compressed: [u32; 8]) {
+        let record = &mut ByteRecordTester::default();
+        let mut witness = StateSum::<F>::default();
+        let out = witness.populate(state, compressed, record);
+        assert_eq!(
+            out,
+            array::from_fn(|i| state[i].wrapping_add(compressed[i]))
+        );
+        let out_f = witness.eval(
+            &mut GadgetTester::passing(),
+            &state.map(word),
+            &compressed.map(word),
+            &mut record.passing(StateSum::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(out_f, out.map(word));
+    }
+
+    proptest! {
+
+    #[test]
+    fn test_sigmas(x: u32) {
+        for sigma in [Sigma::Upper0, Sigma::Upper1, Sigma::Lower0, Sigma::Lower1] {
+            test_sigma(sigma, x)
+        }
+    }
+
+    #[test]
+    fn test_rounds(state: [u32; 8], k: u32, w: u32) {
+        test_round(state, k, w)
+    }
+
+    #[test]
+    fn test_schedules(words: [u32; 4]) {
+        test_schedule(words)
+    }
+
+    #[test]
+    fn test_state_sums(state: [u32; 8], compressed: [u32; 8]) {
+        test_state_sum(state, compressed)
+    }
+
+    }
+}
@@ -0,0 +1,127 @@
+//! Gadgets for signed integers in two's complement representation, built on
+//! top of the `Word` type from the unsigned gadgets
+
+use crate::gadgets::bytes::{ByteAirRecord, ByteRecord};
+use crate::gadgets::unsigned::Word;
+use num_traits::{Signed, ToBytes};
+use p3_field::{AbstractField, PrimeField};
+use sp1_derive::AlignedBorrow;
+
+/// The most significant byte of a negative integer is at least this value
+const SIGN_BYTE_THRESHOLD: u8 = 128;
+
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct SignWitness<T> {
+    is_negative: T,
+}
+
+impl<F: PrimeField> SignWitness<F> {
+    pub fn populate<const W: usize, I>(
+        &mut self,
+        input: &I,
+        byte_record: &mut impl ByteRecord,
+    ) -> bool
+    where
+        I: ToBytes<Bytes = [u8; W]> + Signed,
+    {
+        let msb = input.to_le_bytes()[W - 1];
+        let is_negative = !byte_record.less_than(msb, SIGN_BYTE_THRESHOLD);
+        debug_assert_eq!(is_negative, input.is_negative());
+        self.is_negative = F::from_bool(is_negative);
+        is_negative
+    }
+}
+
+impl<Var> SignWitness<Var> {
+    /// Constraints for checking that `is_negative` is the sign of the input, assumed
+    /// to be a range checked little-endian two's complement integer.
+    /// The returned `is_negative` is boolean since it's the complement of the result
+    /// of a `less_than` lookup.
+    pub fn eval<Expr: AbstractField, const W: usize>(
+        &self,
+        input: &Word<Expr, W>,
+        record: &mut impl ByteAirRecord<Expr>,
+        is_real: impl Into<Expr>,
+    ) -> Expr
+    where
+        Var: Copy + Into<Expr>,
+    {
+        let is_negative: Expr = self.is_negative.into();
+        let is_non_negative = Expr::one() - is_negative.clone();
+        record.less_than(
+            input[W - 1].clone(),
+            Expr::from_canonical_u8(SIGN_BYTE_THRESHOLD),
+            is_non_negative,
+            is_real,
+        );
+        is_negative
+    }
+}
+
+impl<T> SignWitness<T> {
+    pub const fn num_requires() -> usize {
+        1
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<SignWitness<u8>>()
+    }
+
+    pub fn iter_result(&self) -> impl IntoIterator<Item = T>
+    where
+        T: Clone,
+    {
+        [self.is_negative.clone()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::debug::ByteRecordTester;
+    use expect_test::expect;
+    use p3_baby_bear::BabyBear;
+    use proptest::prelude::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_witness_size() {
+        expect!["1"].assert_eq(&SignWitness::<u8>::witness_size().to_string());
+    }
+
+    #[test]
+    fn test_num_requires() {
+        expect!["1"].assert_eq(&SignWitness::<u8>::num_requires().to_string());
+    }
+
+    fn test_sign<const W: usize, I: ToBytes<Bytes = [u8; W]> + Signed>(input: I) {
+        let record = &mut ByteRecordTester::default();
+
+        let mut witness = SignWitness::<F>::default();
+        let is_negative = witness.populate(&input, record);
+        assert_eq!(is_negative, input.is_negative());
+
+        let is_negative_f = witness.eval(
+            &Word::<F, W>::from_signed(&input),
+            &mut record.passing(SignWitness::<F>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(is_negative_f, F::from_bool(is_negative));
+    }
+
+    proptest! {
+
+    #[test]
+    fn test_sign_32(a: i32) {
+        test_sign::<4, _>(a);
+    }
+
+    #[test]
+    fn test_sign_64(a: i64) {
+        test_sign::<8, _>(a);
+    }
+
+    }
+}
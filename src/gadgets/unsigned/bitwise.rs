@@ -0,0 +1,174 @@
+use crate::gadgets::bytes::{ByteAirRecord, ByteRecord};
+use crate::gadgets::unsigned::Word;
+use num_traits::{FromBytes, ToBytes, Unsigned};
+use p3_field::AbstractField;
+use sp1_derive::AlignedBorrow;
+use std::array;
+
+/// The bitwise operations supported by the bytes chip
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitwiseOp {
+    And,
+    Or,
+    Xor,
+}
+
+/// Wrapper type for a bitwise operation, which contains the output of the computation.
+/// Each limb of the result is obtained through a lookup to the bytes chip.
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct Bitwise<T, const W: usize> {
+    result: Word<T, W>,
+}
+
+impl<F: AbstractField, const W: usize> Bitwise<F, W> {
+    pub fn populate<U>(
+        &mut self,
+        op: BitwiseOp,
+        lhs: &U,
+        rhs: &U,
+        byte_record: &mut impl ByteRecord,
+    ) -> U
+    where
+        U: ToBytes<Bytes = [u8; W]> + FromBytes<Bytes = [u8; W]> + Unsigned,
+    {
+        let lhs = lhs.to_le_bytes();
+        let rhs = rhs.to_le_bytes();
+        let out: [u8; W] = array::from_fn(|i| match op {
+            BitwiseOp::And => byte_record.and(lhs[i], rhs[i]),
+            BitwiseOp::Or => byte_record.or(lhs[i], rhs[i]),
+            BitwiseOp::Xor => byte_record.xor(lhs[i], rhs[i]),
+        });
+        self.result = Word(out.map(F::from_canonical_u8));
+        U::from_le_bytes(&out)
+    }
+}
+
+impl<Var, const W: usize> Bitwise<Var, W> {
+    /// Constraints for checking that the result is the bitwise operation applied to
+    /// lhs and rhs, where the operands are assumed to be range checked little-endian
+    /// unsigned integers. The result is range checked since it's the output of lookups.
+    pub fn eval<Expr: AbstractField>(
+        &self,
+        op: BitwiseOp,
+        lhs: &Word<Expr, W>,
+        rhs: &Word<Expr, W>,
+        record: &mut impl ByteAirRecord<Expr>,
+        is_real: impl Into<Expr>,
+    ) -> Word<Var, W>
+    where
+        Var: Copy + Into<Expr>,
+    {
+        let is_real = is_real.into();
+        for i in 0..W {
+            let (lhs, rhs, out) = (lhs[i].clone(), rhs[i].clone(), self.result[i]);
+            match op {
+                BitwiseOp::And => record.and(lhs, rhs, out, is_real.clone()),
+                BitwiseOp::Or => record.or(lhs, rhs, out, is_real.clone()),
+                BitwiseOp::Xor => record.xor(lhs, rhs, out, is_real.clone()),
+            }
+        }
+        self.result
+    }
+}
+
+impl<T, const W: usize> Bitwise<T, W> {
+    pub const fn num_requires() -> usize {
+        W
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<Bitwise<u8, W>>()
+    }
+
+    pub fn iter_result(&self) -> impl IntoIterator<Item = T>
+    where
+        T: Clone,
+    {
+        self.result.0.clone()
+    }
+}
+
+/// Returns the bitwise negation of a range checked little-endian unsigned integer.
+/// Since `!x = 255 - x` holds for every limb, no witness is needed.
+pub fn eval_not<Expr: AbstractField, const W: usize>(input: Word<Expr, W>) -> Word<Expr, W> {
+    input.map(|limb| Expr::from_canonical_u8(u8::MAX) - limb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::debug::ByteRecordTester;
+    use expect_test::expect;
+    use p3_baby_bear::BabyBear;
+    use proptest::prelude::*;
+    use std::fmt::Debug;
+    use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_witness_size() {
+        expect!["4"].assert_eq(&Bitwise::<u8, 4>::witness_size().to_string());
+        expect!["8"].assert_eq(&Bitwise::<u8, 8>::witness_size().to_string());
+    }
+
+    #[test]
+    fn test_num_requires() {
+        expect!["4"].assert_eq(&Bitwise::<u8, 4>::num_requires().to_string());
+        expect!["8"].assert_eq(&Bitwise::<u8, 8>::num_requires().to_string());
+    }
+
+    fn test_bitwise<
+        const W: usize,
+        U: ToBytes<Bytes = [u8; W]>
+            + FromBytes<Bytes = [u8; W]>
+            + Unsigned
+            + BitAnd<Output = U>
+            + BitOr<Output = U>
+            + BitXor<Output = U>
+            + Not<Output = U>
+            + Copy
+            + Debug,
+    >(
+        lhs: U,
+        rhs: U,
+    ) {
+        let record = &mut ByteRecordTester::default();
+
+        for (op, expected) in [
+            (BitwiseOp::And, lhs & rhs),
+            (BitwiseOp::Or, lhs | rhs),
+            (BitwiseOp::Xor, lhs ^ rhs),
+        ] {
+            let mut witness = Bitwise::<F, W>::default();
+            let out = witness.populate(op, &lhs, &rhs, record);
+            assert_eq!(out, expected);
+            let out_f = witness.eval(
+                op,
+                &Word::<F, W>::from_unsigned(&lhs),
+                &Word::<F, W>::from_unsigned(&rhs),
+                &mut record.passing(Bitwise::<F, W>::num_requires()),
+                F::one(),
+            );
+            assert_eq!(out_f, Word::from_unsigned(&expected));
+        }
+
+        let not_f = eval_not(Word::<F, W>::from_unsigned(&lhs));
+        assert_eq!(not_f, Word::from_unsigned(&!lhs));
+    }
+
+    proptest! {
+
+    #[test]
+    fn test_bitwise_32(a: u32, b: u32) {
+        test_bitwise::<4, _>(a, b)
+    }
+
+    #[test]
+    fn test_bitwise_64(a: u64, b: u64) {
+        test_bitwise::<8, _>(a, b)
+    }
+
+    }
+}
@@ -1,6 +1,6 @@
 use crate::gadgets::bytes::{ByteAirRecord, ByteRecord};
 use core::slice;
-use num_traits::{ToBytes, Unsigned};
+use num_traits::{Signed, ToBytes, Unsigned};
 use p3_field::AbstractField;
 use sp1_derive::AlignedBorrow;
 use std::array;
@@ -9,12 +9,15 @@ use std::iter::zip;
 use std::ops::{Index, IndexMut};
 
 pub mod add;
+pub mod bitwise;
 pub mod cmp;
 pub mod div_rem;
 pub mod field;
 pub mod is_zero;
 pub mod less_than;
 pub mod mul;
+pub mod rotate;
+pub mod shift;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, AlignedBorrow)]
 #[repr(C)]
@@ -98,6 +101,11 @@ impl<F: AbstractField, const W: usize> Word<F, W> {
     pub fn from_unsigned<U: ToBytes<Bytes = [u8; W]> + Unsigned>(u: &U) -> Self {
         Self(u.to_le_bytes().map(F::from_canonical_u8))
     }
+
+    /// Uses the two's complement representation of a signed integer
+    pub fn from_signed<I: ToBytes<Bytes = [u8; W]> + Signed>(i: &I) -> Self {
+        Self(i.to_le_bytes().map(F::from_canonical_u8))
+    }
 }
 
 #[derive(Copy, Clone, Debug, AlignedBorrow)]
@@ -0,0 +1,134 @@
+use crate::gadgets::bytes::{ByteAirRecord, ByteRecord};
+use crate::gadgets::unsigned::Word;
+use num_traits::ToBytes;
+use p3_field::AbstractField;
+use sp1_derive::AlignedBorrow;
+use std::array;
+
+/// Witness for splitting each limb of a word at bit `k`, with `0 < k < 8`. Only the
+/// high parts are stored since the low ones follow from the limbs. Moving a word by a
+/// constant amount of bits turns into a linear combination of the parts.
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct LimbSplit<T, const W: usize> {
+    high: [T; W],
+}
+
+impl<F: AbstractField, const W: usize> LimbSplit<F, W> {
+    pub fn populate<U>(&mut self, x: &U, k: u32, byte_record: &mut impl ByteRecord)
+    where
+        U: ToBytes<Bytes = [u8; W]>,
+    {
+        for (high, limb) in self.high.iter_mut().zip(x.to_le_bytes()) {
+            let (lo, hi) = (limb & ((1 << k) - 1), limb >> k);
+            byte_record.range_check_u8_pair(lo << (8 - k), hi);
+            *high = F::from_canonical_u8(hi);
+        }
+    }
+}
+
+impl<Var, const W: usize> LimbSplit<Var, W> {
+    /// Returns the low and high parts of the limbs of `x`, which is assumed to be range
+    /// checked. Looking up `low * 2^(8-k)` and `high` as bytes ensures that the parts
+    /// are integers smaller than `2^k` and `2^(8-k)` respectively.
+    pub fn eval<Expr: AbstractField>(
+        &self,
+        x: &Word<Expr, W>,
+        k: u32,
+        record: &mut impl ByteAirRecord<Expr>,
+        is_real: impl Into<Expr>,
+    ) -> ([Expr; W], [Expr; W])
+    where
+        Var: Copy + Into<Expr>,
+    {
+        let is_real = is_real.into();
+        let high: [Expr; W] = self.high.map(Into::into);
+        let low: [Expr; W] =
+            array::from_fn(|i| x[i].clone() - high[i].clone() * Expr::from_canonical_u32(1 << k));
+        for (low, high) in low.iter().zip(high.iter()) {
+            let low_shifted = low.clone() * Expr::from_canonical_u32(1 << (8 - k));
+            record.range_check_u8_pair(low_shifted, high.clone(), is_real.clone());
+        }
+        (low, high)
+    }
+}
+
+impl<T, const W: usize> LimbSplit<T, W> {
+    pub const fn num_requires() -> usize {
+        W
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<LimbSplit<u8, W>>()
+    }
+}
+
+/// Recombines the parts of the limbs of a word split at bit `k` into the limbs of the
+/// word moved right by `8 * q + k` bits, which wrap around if `rotate` is set
+pub fn recombine<Expr: AbstractField, const W: usize>(
+    low: &[Expr; W],
+    high: &[Expr; W],
+    q: usize,
+    k: u32,
+    rotate: bool,
+) -> Word<Expr, W> {
+    let part = |parts: &[Expr; W], i: usize| {
+        if rotate || i < W {
+            parts[i % W].clone()
+        } else {
+            Expr::zero()
+        }
+    };
+    Word::from_fn(|j| {
+        part(high, j + q) + part(low, j + q + 1) * Expr::from_canonical_u32(1 << (8 - k))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::debug::ByteRecordTester;
+    use expect_test::expect;
+    use p3_baby_bear::BabyBear;
+    use proptest::prelude::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_witness_size() {
+        expect!["4"].assert_eq(&LimbSplit::<u8, 4>::witness_size().to_string());
+        expect!["8"].assert_eq(&LimbSplit::<u8, 8>::witness_size().to_string());
+    }
+
+    #[test]
+    fn test_num_requires() {
+        expect!["4"].assert_eq(&LimbSplit::<u8, 4>::num_requires().to_string());
+        expect!["8"].assert_eq(&LimbSplit::<u8, 8>::num_requires().to_string());
+    }
+
+    fn test_move(x: u64, q: usize, k: u32) {
+        let amount = 8 * q as u32 + k;
+        let record = &mut ByteRecordTester::default();
+        let mut witness = LimbSplit::<F, 8>::default();
+        witness.populate(&x, k, record);
+        let (low, high) = witness.eval(
+            &Word::<F, 8>::from_unsigned(&x),
+            k,
+            &mut record.passing(LimbSplit::<F, 8>::num_requires()),
+            F::one(),
+        );
+        let rotated = recombine(&low, &high, q, k, true);
+        assert_eq!(rotated, Word::from_unsigned(&x.rotate_right(amount)));
+        let shifted = recombine(&low, &high, q, k, false);
+        assert_eq!(shifted, Word::from_unsigned(&(x >> amount)));
+    }
+
+    proptest! {
+
+    #[test]
+    fn test_moves(x: u64, q in 0..8usize, k in 1..8u32) {
+        test_move(x, q, k)
+    }
+
+    }
+}
@@ -0,0 +1,366 @@
+use crate::gadgets::bytes::{ByteAirRecord, ByteRecord};
+use crate::gadgets::unsigned::div_rem::DivRem;
+use crate::gadgets::unsigned::mul::Product;
+use crate::gadgets::unsigned::Word;
+use num_traits::ops::overflowing::OverflowingSub;
+use num_traits::{FromBytes, ToBytes, Unsigned};
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, PrimeField};
+use sp1_derive::AlignedBorrow;
+use std::array;
+use std::iter::zip;
+use std::ops::Div;
+
+/// Witness for computing `2^(shift % (8 * W))` as a `Word`, where only the least
+/// significant limb of `shift` is used. This matches the semantics of Rust's
+/// `wrapping_shl` and `wrapping_shr`, which mask the shift amount.
+#[derive(Clone, Debug, AlignedBorrow)]
+#[repr(C)]
+pub struct PowerOfTwo<T, const W: usize> {
+    /// Little-endian bit decomposition of the least significant limb of the shift
+    bits: [T; 8],
+    /// 2^(bits[0] + 2 * bits[1])
+    bit_pow_partial: T,
+    /// 2^(bits[0] + 2 * bits[1] + 4 * bits[2]), the power of two within a limb
+    bit_pow: T,
+    /// One-hot encoding of the limb that contains the non-zero bit of the result
+    limb_selectors: [T; W],
+    result: Word<T, W>,
+}
+
+impl<T, const W: usize> PowerOfTwo<T, W> {
+    /// The number of bits of the shift that are relevant for words of size `W`
+    const SHIFT_BITS: usize = {
+        assert!(W.is_power_of_two() && W <= 32);
+        (8 * W).trailing_zeros() as usize
+    };
+}
+
+impl<F: AbstractField, const W: usize> PowerOfTwo<F, W> {
+    pub fn populate<U>(&mut self, shift: &U) -> U
+    where
+        U: ToBytes<Bytes = [u8; W]> + FromBytes<Bytes = [u8; W]> + Unsigned,
+    {
+        let shift_limb = shift.to_le_bytes()[0];
+        self.bits = array::from_fn(|i| F::from_bool((shift_limb >> i) & 1 == 1));
+        let shift = usize::from(shift_limb) % (8 * W);
+        let (limb_shift, bit_shift) = (shift / 8, shift % 8);
+        self.bit_pow_partial = F::from_canonical_u8(1 << (bit_shift % 4));
+        self.bit_pow = F::from_canonical_u8(1 << bit_shift);
+        self.limb_selectors = array::from_fn(|i| F::from_bool(i == limb_shift));
+        let mut out = [0u8; W];
+        out[limb_shift] = 1 << bit_shift;
+        self.result = Word(out.map(F::from_canonical_u8));
+        U::from_le_bytes(&out)
+    }
+}
+
+impl<Var, const W: usize> PowerOfTwo<Var, W> {
+    /// Constraints for checking that the result is `2^(shift % (8 * W))`, where the
+    /// least significant limb of `shift` is assumed to be range checked.
+    /// The limbs of the result are either zero or a power of two smaller than 256.
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        shift: &Word<AB::Expr, W>,
+        is_real: impl Into<AB::Expr>,
+    ) -> Word<AB::Var, W>
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let builder = &mut builder.when(is_real);
+
+        // shift[0] = ∑ bits[i] * 2^i
+        let mut shift_limb = AB::Expr::zero();
+        for (i, &bit) in self.bits.iter().enumerate() {
+            builder.assert_bool(bit);
+            let bit: AB::Expr = bit.into();
+            shift_limb += bit * AB::Expr::from_canonical_u32(1 << i);
+        }
+        builder.assert_eq(shift[0].clone(), shift_limb);
+
+        // 2^bit_shift = (1 + bits[0]) * (1 + 3 * bits[1]) * (1 + 15 * bits[2]),
+        // split in two constraints to keep the degree low
+        let [b0, b1, b2]: [AB::Expr; 3] = array::from_fn(|i| self.bits[i].into());
+        let bit_pow_partial: AB::Expr = self.bit_pow_partial.into();
+        builder.assert_eq(
+            bit_pow_partial.clone(),
+            (AB::Expr::one() + b0) * (AB::Expr::one() + b1 * AB::Expr::from_canonical_u8(3)),
+        );
+        builder.assert_eq(
+            self.bit_pow,
+            bit_pow_partial * (AB::Expr::one() + b2 * AB::Expr::from_canonical_u8(15)),
+        );
+
+        // limb_shift = ∑ bits[i] * 2^(i-3) for the remaining relevant bits, and
+        // the selector of that limb is the only one set
+        let mut limb_shift = AB::Expr::zero();
+        for i in 3..Self::SHIFT_BITS {
+            let bit: AB::Expr = self.bits[i].into();
+            limb_shift += bit * AB::Expr::from_canonical_u32(1 << (i - 3));
+        }
+        let mut selected = AB::Expr::zero();
+        let mut selected_index = AB::Expr::zero();
+        for (i, &selector) in self.limb_selectors.iter().enumerate() {
+            builder.assert_bool(selector);
+            let selector: AB::Expr = selector.into();
+            selected += selector.clone();
+            selected_index += selector * AB::Expr::from_canonical_usize(i);
+        }
+        builder.assert_one(selected);
+        builder.assert_eq(selected_index, limb_shift);
+
+        // result[i] = 2^bit_shift if i = limb_shift, and 0 otherwise
+        for (&limb, &selector) in zip(self.result.iter(), self.limb_selectors.iter()) {
+            let selector: AB::Expr = selector.into();
+            builder.assert_eq(limb, selector * self.bit_pow.into());
+        }
+        self.result
+    }
+}
+
+impl<T, const W: usize> PowerOfTwo<T, W> {
+    pub const fn num_requires() -> usize {
+        0
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<PowerOfTwo<u8, W>>()
+    }
+}
+
+impl<T: Default, const W: usize> Default for PowerOfTwo<T, W> {
+    fn default() -> Self {
+        Self {
+            bits: Default::default(),
+            bit_pow_partial: T::default(),
+            bit_pow: T::default(),
+            limb_selectors: array::from_fn(|_| T::default()),
+            result: Default::default(),
+        }
+    }
+}
+
+/// Wrapper type for the left shift `lhs << (shift % (8 * W))`, computed as a
+/// multiplication by a power of two.
+#[derive(Clone, Debug, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct ShiftLeft<T, const W: usize> {
+    pow: PowerOfTwo<T, W>,
+    product: Product<T, W>,
+}
+
+impl<F: AbstractField, const W: usize> ShiftLeft<F, W> {
+    pub fn populate<U>(&mut self, lhs: &U, shift: &U, byte_record: &mut impl ByteRecord) -> U
+    where
+        U: ToBytes<Bytes = [u8; W]> + FromBytes<Bytes = [u8; W]> + Unsigned,
+    {
+        let pow = self.pow.populate(shift);
+        self.product.populate(lhs, &pow, byte_record)
+    }
+}
+
+impl<Var, const W: usize> ShiftLeft<Var, W> {
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        lhs: &Word<AB::Expr, W>,
+        shift: &Word<AB::Expr, W>,
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> Word<AB::Var, W>
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let pow = self.pow.eval(builder, shift, is_real.clone());
+        self.product
+            .eval(builder, lhs, &pow.into(), record, is_real)
+    }
+}
+
+impl<T, const W: usize> ShiftLeft<T, W> {
+    pub const fn num_requires() -> usize {
+        PowerOfTwo::<T, W>::num_requires() + Product::<T, W>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<ShiftLeft<u8, W>>()
+    }
+
+    pub fn iter_result(&self) -> impl IntoIterator<Item = T>
+    where
+        T: Clone,
+    {
+        self.product.iter_result()
+    }
+}
+
+/// Wrapper type for the logical right shift `lhs >> (shift % (8 * W))`, computed as
+/// a division by a power of two.
+#[derive(Clone, Default, AlignedBorrow)]
+#[repr(C)]
+pub struct ShiftRight<T, const W: usize> {
+    pow: PowerOfTwo<T, W>,
+    div_rem: DivRem<T, W>,
+}
+
+impl<F: PrimeField, const W: usize> ShiftRight<F, W> {
+    pub fn populate<U>(&mut self, lhs: &U, shift: &U, byte_record: &mut impl ByteRecord) -> U
+    where
+        U: ToBytes<Bytes = [u8; W]>
+            + FromBytes<Bytes = [u8; W]>
+            + Unsigned
+            + Div
+            + Copy
+            + OverflowingSub
+            + Ord,
+    {
+        let pow = self.pow.populate(shift);
+        let (q, _r) = self.div_rem.populate(lhs, &pow, byte_record);
+        q
+    }
+}
+
+impl<Var, const W: usize> ShiftRight<Var, W> {
+    pub fn eval<AB: AirBuilder<Var = Var>>(
+        &self,
+        builder: &mut AB,
+        lhs: &Word<AB::Expr, W>,
+        shift: &Word<AB::Expr, W>,
+        record: &mut impl ByteAirRecord<AB::Expr>,
+        is_real: impl Into<AB::Expr>,
+    ) -> Word<AB::Var, W>
+    where
+        Var: Copy + Into<AB::Expr>,
+    {
+        let is_real = is_real.into();
+        let pow = self.pow.eval(builder, shift, is_real.clone());
+        let (q, _r) = self
+            .div_rem
+            .eval(builder, lhs, &pow.into(), record, is_real);
+        q
+    }
+}
+
+impl<T, const W: usize> ShiftRight<T, W> {
+    pub const fn num_requires() -> usize {
+        PowerOfTwo::<T, W>::num_requires() + DivRem::<T, W>::num_requires()
+    }
+
+    pub const fn witness_size() -> usize {
+        size_of::<ShiftRight<u8, W>>()
+    }
+
+    pub fn iter_result(&self) -> impl IntoIterator<Item = T>
+    where
+        T: Clone,
+    {
+        self.div_rem.iter_result().into_iter().take(W)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::debug::{ByteRecordTester, GadgetTester};
+    use expect_test::expect;
+    use p3_baby_bear::BabyBear;
+    use proptest::prelude::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_witness_size() {
+        expect!["18"].assert_eq(&PowerOfTwo::<u8, 4>::witness_size().to_string());
+        expect!["26"].assert_eq(&PowerOfTwo::<u8, 8>::witness_size().to_string());
+
+        expect!["26"].assert_eq(&ShiftLeft::<u8, 4>::witness_size().to_string());
+        expect!["42"].assert_eq(&ShiftLeft::<u8, 8>::witness_size().to_string());
+
+        expect!["52"].assert_eq(&ShiftRight::<u8, 4>::witness_size().to_string());
+        expect!["88"].assert_eq(&ShiftRight::<u8, 8>::witness_size().to_string());
+    }
+
+    #[test]
+    fn test_num_requires() {
+        expect!["6"].assert_eq(&ShiftLeft::<u8, 4>::num_requires().to_string());
+        expect!["12"].assert_eq(&ShiftLeft::<u8, 8>::num_requires().to_string());
+
+        expect!["12"].assert_eq(&ShiftRight::<u8, 4>::num_requires().to_string());
+        expect!["22"].assert_eq(&ShiftRight::<u8, 8>::num_requires().to_string());
+    }
+
+    fn test_shift_32(lhs: u32, shift: u32) {
+        let record = &mut ByteRecordTester::default();
+        let expected_shl = lhs.wrapping_shl(shift);
+        let expected_shr = lhs.wrapping_shr(shift);
+
+        let mut shl_witness = ShiftLeft::<F, 4>::default();
+        let shl = shl_witness.populate(&lhs, &shift, record);
+        assert_eq!(shl, expected_shl);
+        let shl_f = shl_witness.eval(
+            &mut GadgetTester::passing(),
+            &Word::from_unsigned(&lhs),
+            &Word::from_unsigned(&shift),
+            &mut record.passing(ShiftLeft::<F, 4>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(shl_f, Word::from_unsigned(&expected_shl));
+
+        let mut shr_witness = ShiftRight::<F, 4>::default();
+        let shr = shr_witness.populate(&lhs, &shift, record);
+        assert_eq!(shr, expected_shr);
+        let shr_f = shr_witness.eval(
+            &mut GadgetTester::passing(),
+            &Word::from_unsigned(&lhs),
+            &Word::from_unsigned(&shift),
+            &mut record.passing(ShiftRight::<F, 4>::num_requires()),
+            F::one(),
+        );
+        assert_eq!(shr_f, Word::from_unsigned(&expected_shr));
+    }
+
+    fn test_pow_64(shift: u64) {
+        let mut witness = PowerOfTwo::<F, 8>::default();
+        let pow = witness.populate(&shift);
+        assert_eq!(pow, 1u64.wrapping_shl(shift as u32));
+        let pow_f = witness.eval(
+            &mut GadgetTester::passing(),
+            &Word::from_unsigned(&shift),
+            F::one(),
+        );
+        assert_eq!(pow_f, Word::from_unsigned(&pow));
+    }
+
+    #[test]
+    fn test_pow_wrong_result() {
+        let mut witness = PowerOfTwo::<F, 8>::default();
+        witness.populate(&3u64);
+        witness.result[0] = F::from_canonical_u8(4);
+        witness.eval(
+            &mut GadgetTester::failing(),
+            &Word::from_unsigned(&3u64),
+            F::one(),
+        );
+    }
+
+    proptest! {
+
+    #[test]
+    fn test_shift(lhs: u32, shift: u32) {
+        test_shift_32(lhs, shift)
+    }
+
+    #[test]
+    fn test_shift_small(lhs: u32, shift in 0u32..40) {
+        test_shift_32(lhs, shift)
+    }
+
+    #[test]
+    fn test_pow(shift: u64) {
+        test_pow_64(shift)
+    }
+
+    }
+}
@@ -72,8 +72,27 @@ pub struct QueryRecord<F: PrimeField32> {
     pub(crate) bytes: BytesRecord,
     pub(crate) emitted: Vec<List<F>>,
     pub(crate) debug_data: DebugData,
+    /// The maximum number of new function queries an execution may register,
+    /// with `None` meaning unlimited
+    pub(crate) fuel: Option<usize>,
 }
 
+/// The error returned when an execution exceeds the fuel of its `QueryRecord`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfFuel(pub usize);
+
+impl std::fmt::Display for OutOfFuel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Out of fuel: exceeded the limit of {} function queries",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for OutOfFuel {}
+
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
 pub struct Shard<F: PrimeField32> {
     pub(crate) index: u32,
@@ -264,9 +283,17 @@ impl<F: PrimeField32> QueryRecord<F> {
             bytes: BytesRecord::default(),
             emitted: vec![],
             debug_data: DebugData::default(),
+            fuel: None,
         }
     }
 
+    /// Limits the number of new function queries each execution may register.
+    /// Memoized calls are free. `None` removes the limit.
+    #[inline]
+    pub fn set_fuel(&mut self, fuel: Option<usize>) {
+        self.fuel = fuel;
+    }
+
     #[inline]
     pub fn get_output(&self, func: &Func<F>, inp: &[F]) -> &[F] {
         self.func_queries[func.index]
@@ -426,6 +453,18 @@ impl<F: PrimeField32> Func<F> {
         queries: &mut QueryRecord<F>,
         dbg_func_idx: Option<usize>,
     ) -> Result<(List<F>, u32)> {
+        let mut fuel = queries.fuel;
+        macro_rules! consume_fuel {
+            () => {
+                if let Some(remaining) = fuel.as_mut() {
+                    if *remaining == 0 {
+                        return Err(OutOfFuel(queries.fuel.unwrap()).into());
+                    }
+                    *remaining -= 1;
+                }
+            };
+        }
+        consume_fuel!();
         let mut func_index = self.index;
         let mut query_result = QueryResult::default();
         query_result.provide.count = 1;
@@ -504,6 +543,7 @@ impl<F: PrimeField32> Func<F> {
                             });
                         }
                     } else {
+                        consume_fuel!();
                         // insert dummy entry
                         let (callee_nonce, _) = queries.func_queries[*callee_index]
                             .insert_full(inp.clone().into(), QueryResult::default());
@@ -579,6 +619,7 @@ impl<F: PrimeField32> Func<F> {
                             });
                         }
                     } else {
+                        consume_fuel!();
                         let (callee_nonce, _) = queries.func_queries[*callee_index]
                             .insert_full(inp.clone().into(), QueryResult::default());
                         let mut map_buffer = inp;
@@ -776,7 +817,7 @@ mod tests {
         lair::{
             chipset::NoChip,
             demo_toplevel,
-            execute::{QueryRecord, Shard},
+            execute::{OutOfFuel, QueryRecord, Shard},
             field_from_u32,
             func_chip::FuncChip,
             toplevel::Toplevel,
@@ -808,6 +849,30 @@ mod tests {
         assert_eq!(out.as_ref(), [F::from_canonical_u32(0)]);
     }
 
+    #[test]
+    fn lair_fuel_test() {
+        let toplevel = demo_toplevel::<F>();
+
+        // computing the factorial of 5 registers 6 function queries
+        let factorial = toplevel.func_by_name("factorial");
+        let args = &[F::from_canonical_u32(5)];
+        let queries = &mut QueryRecord::new(&toplevel);
+        queries.set_fuel(Some(5));
+        let err = toplevel
+            .execute(factorial, args, queries, None)
+            .unwrap_err();
+        assert_eq!(err.downcast_ref::<OutOfFuel>(), Some(&OutOfFuel(5)));
+
+        let queries = &mut QueryRecord::new(&toplevel);
+        queries.set_fuel(Some(6));
+        let out = toplevel.execute(factorial, args, queries, None).unwrap();
+        assert_eq!(out.as_ref(), [F::from_canonical_u32(120)]);
+
+        // memoized calls don't consume fuel
+        let out = toplevel.execute(factorial, args, queries, None).unwrap();
+        assert_eq!(out.as_ref(), [F::from_canonical_u32(120)]);
+    }
+
     #[test]
     fn lair_execute_iter_test() {
         let toplevel = demo_toplevel::<F>();
@@ -6,6 +6,7 @@ use num_traits::FromPrimitive;
 use p3_baby_bear::BabyBear;
 
 use crate::core::chipset::LurkChip;
+use crate::core::error::EvalErr;
 use crate::core::state::BUILTIN_SYMBOLS;
 use crate::core::tag::Tag;
 use crate::core::zstore::{builtin_set, lurk_zstore, ZPtr};
@@ -342,7 +343,7 @@ ascent! {
     ingress(env), lookup0(env, expr, env) <-- eval_input(expr, env), if expr.is_sym();
 
     // Unbound variable: If env is nil during lookup0, var is unbound. Return an error.
-    eval(var, outer_env, Ptr(Tag::Err.elt(), LE::zero())) <-- lookup0(outer_env, var, env), if env.is_nil();
+    eval(var, outer_env, Ptr(Tag::Err.elt(), EvalErr::UnboundVar.to_field())) <-- lookup0(outer_env, var, env), if env.is_nil();
 
     // If env is a cons, ingress the first binding.
     ingress(binding) <-- lookup0(outer_env, _, env), cons_rel(binding, tail, env);
@@ -1032,7 +1033,10 @@ mod test {
     use super::*;
 
     fn err() -> WidePtr {
-        WidePtr(Tag::Err.value(), Wide::widen(LE::from_canonical_u32(0)))
+        WidePtr(
+            Tag::Err.value(),
+            Wide::widen(EvalErr::UnboundVar.to_field()),
+        )
     }
 
     fn wide_ptr(tag: LE, digest: [LE; 8]) -> WidePtr {
@@ -1040,7 +1044,7 @@ mod test {
     }
 
     fn read_wideptr(zstore: &mut ZStore<BabyBear, LurkChip>, src: &str) -> WidePtr {
-        let ZPtr { tag, digest } = zstore.read(src, &Default::default());
+        let ZPtr { tag, digest } = zstore.read(src, &Default::default()).unwrap();
         wide_ptr(tag.elt(), digest)
     }
 
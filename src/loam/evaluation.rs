@@ -8,6 +8,7 @@ use rustc_hash::FxHashMap;
 use strum::EnumCount;
 
 use crate::core::chipset::LurkChip;
+use crate::core::error::EvalErr;
 use crate::core::state::BUILTIN_SYMBOLS;
 use crate::core::tag::Tag;
 use crate::core::zstore::{builtin_set, lurk_zstore, ZPtr, ZStore};
@@ -534,7 +535,7 @@ ascent! {
     ingress(env), lookup0(env, expr, env) <-- eval_input(expr, env), if expr.is_sym();
 
     // Unbound variable: If env is nil during lookup0, var is unbound. Return an error.
-    eval(var, outer_env, Ptr(Tag::Err.elt(), LE::zero())) <-- lookup0(outer_env, var, env), if env.is_nil();
+    eval(var, outer_env, Ptr(Tag::Err.elt(), EvalErr::UnboundVar.to_field())) <-- lookup0(outer_env, var, env), if env.is_nil();
 
     // If env is a cons, ingress the first binding.
     ingress(binding) <-- lookup0(outer_env, _, env), cons_rel(binding, tail, env);
@@ -1224,7 +1225,10 @@ mod test {
     use crate::core::zstore::{self, ZPtr};
 
     fn err() -> WidePtr {
-        WidePtr(Tag::Err.value(), Wide::widen(LE::from_canonical_u32(0)))
+        WidePtr(
+            Tag::Err.value(),
+            Wide::widen(EvalErr::UnboundVar.to_field()),
+        )
     }
 
     fn wide_ptr(tag: LE, digest: [LE; 8]) -> WidePtr {
@@ -1232,7 +1236,7 @@ mod test {
     }
 
     fn read_wideptr(zstore: &mut ZStore<BabyBear, LurkChip>, src: &str) -> WidePtr {
-        let ZPtr { tag, digest } = zstore.read(src, &Default::default());
+        let ZPtr { tag, digest } = zstore.read(src, &Default::default()).unwrap();
         wide_ptr(tag.elt(), digest)
     }
 
@@ -1391,8 +1395,8 @@ mod test {
     #[test]
     fn test_lambda() {
         let mut zstore = lurk_zstore();
-        let args = zstore.read("(x)", &Default::default());
-        let body = zstore.read("(+ x 1)", &Default::default());
+        let args = zstore.read("(x)", &Default::default()).unwrap();
+        let body = zstore.read("(+ x 1)", &Default::default()).unwrap();
         let env = *zstore.nil();
 
         let fun = zstore.intern_fun(args, body, env);
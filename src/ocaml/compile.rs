@@ -10,6 +10,7 @@ use crate::{
     core::{
         parser::Span,
         state::{builtin_sym, user_sym, StateRcCell, BUILTIN_SYMBOLS},
+        syntax::DECIMAL_SCALE,
         zstore::{ZPtr, ZStore},
     },
     lair::chipset::Chipset,
@@ -136,10 +137,16 @@ fn transform_lambda<F: Field, C1: Chipset<F>>(
                 zstore.intern_u64(*i)
             }
         }
-        LambdaSyntax::Float(_, _) => {
-            // We do not support floats, but emit a `(fail)` so we still generate something
-            let fail = zstore.intern_symbol_no_lang(&builtin_sym("fail"));
-            zstore.intern_list([fail])
+        LambdaSyntax::Float(_, f) => {
+            // Floats are rounded to the nearest decimal. Those out of the decimal range
+            // aren't supported, but emit a `(fail)` so we still generate something
+            let scaled = (f * DECIMAL_SCALE as f64).round();
+            if (i64::MIN as f64..i64::MAX as f64).contains(&scaled) {
+                zstore.intern_decimal(scaled as i64)
+            } else {
+                let fail = zstore.intern_symbol_no_lang(&builtin_sym("fail"));
+                zstore.intern_list([fail])
+            }
         }
         LambdaSyntax::Char(_, c) => zstore.intern_char(*c),
         LambdaSyntax::String(_, s) => zstore.intern_string(s),